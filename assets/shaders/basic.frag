@@ -6,7 +6,9 @@ in VS_OUTPUT {
 
 out vec4 Color;
 
+uniform float opacity;
+
 void main()
 {
-    Color = vec4(IN.Color, 1.0f);
+    Color = vec4(IN.Color, opacity);
 }
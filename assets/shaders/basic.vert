@@ -19,12 +19,14 @@ out VS_OUTPUT {
 
 uniform vec4 scale;
 uniform mat4 rotate;
+// Subtracted before scaling, so that the camera can move the board around.
+uniform vec2 offset;
 
 void main()
 {
     // At the end of the main function, whatever we set gl_Position to
     // will be used as the output of the vertex shader
-    gl_Position = vec4(Position, 1.0) * rotate * scale;
+    gl_Position = (vec4(Position, 1.0) * rotate - vec4(offset, 0.0, 0.0)) * scale;
 
     OUT.Color = Color;
 }
@@ -3,17 +3,27 @@
 #![allow(unused_variables)]
 
 use std::cmp;
+use audio::SoundEffect;
 use drawing;
+use fonts;
+use economy::costs;
 use gameboard;
-use gameboard::gameboard::GameBoardSpacePos;
+use gameboard::gameboard::{all_board_piece_destinations,all_game_board_positions,GameBoard,GameBoardSpacePos};
 use GameUIData;
 use gl;
 use highlight_space_for_city_setup;
 use highlight_spaces_for_board_setup;
 use images::SVGImages;
+use localization;
+use notifications::NotificationKind;
+use rand;
+use rand::Rng;
 use render_gl;
+use rules::{PieceType,RuleError};
 use sdl2;
+use settings::BoardSetupMode;
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum PlayerActionType
 {
     SetupBoard,
@@ -28,6 +38,23 @@ pub enum PlayerActionType
     End
 }
 
+impl PlayerActionType {
+    pub fn name(&self) -> String {
+        localization::text(match self {
+            PlayerActionType::SetupBoard => "action_setup_board",
+            PlayerActionType::SetupCities => "action_setup_cities",
+            PlayerActionType::ChooseAction => "action_choose_action",
+            PlayerActionType::Recruitment => "action_recruitment",
+            PlayerActionType::Movement => "action_movement",
+            PlayerActionType::Construction => "action_construction",
+            PlayerActionType::NewCity => "action_new_city",
+            PlayerActionType::Expedition => "action_expedition",
+            PlayerActionType::NobleTitle => "action_noble_title",
+            PlayerActionType::End => "action_end"
+        })
+    }
+}
+
 // This is like defining an interface.
 pub trait PlayerActionControl {
 
@@ -37,6 +64,16 @@ pub trait PlayerActionControl {
 
     fn key_pressed(&mut self, game_ui_data: &mut GameUIData, scancode: &sdl2::keyboard::Scancode) -> Option<StateTransition>; // returns the next state, or None if the state hasn't changed
 
+    // The scroll amount is positive when scrolling up (away from the user), and negative when scrolling down.
+    fn mouse_wheel_scrolled(&mut self, _game_ui_data: &mut GameUIData, _scroll_amount: i32) -> Option<StateTransition> { // returns the next state, or None if the state hasn't changed
+        None
+    }
+
+    // Called when the player runs out of time.  Makes a random legal move, or returns None if there isn't one.
+    fn random_move(&mut self, _game_ui_data: &mut GameUIData) -> Option<StateTransition> {
+        None
+    }
+
     fn draw_highlight(
         &self,
         game_ui_data: &mut GameUIData,
@@ -51,33 +88,117 @@ pub trait PlayerActionControl {
 
 pub struct StateTransition {
     pub next_action: Box<PlayerActionControl>,
-    pub turn_completed: bool
+    // True if the player has finished an action (or a placement, during setup).  See turns.rs for when this ends the turn.
+    pub action_completed: bool
+}
+
+fn random_element<T: Copy>(items: &[T]) -> Option<T> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items[rand::thread_rng().gen_range(0, items.len())])
+    }
+}
+
+// The action that a new game, or a player's turn, starts with.
+pub fn first_action(game_ui_data: &GameUIData) -> Box<PlayerActionControl> {
+    if game_ui_data.city_setup_completed() {
+        Box::new(ChooseAction{})
+    } else if game_ui_data.board_setup_completed() {
+        // The map was generated automatically, so skip straight to placing cities.
+        Box::new(SetupCities{})
+    } else {
+        Box::new(SetupBoard{})
+    }
 }
 
 #[derive(Clone)]
 pub struct SetupBoard {}
+impl SetupBoard {
+    fn drop_board_piece(&self, game_ui_data: &mut GameUIData, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)) -> Option<StateTransition> {
+        match game_ui_data.drop_board_piece(positions) {
+            Ok(()) => {
+                if game_ui_data.board_setup_completed() {
+                    Some(StateTransition{next_action: Box::new(SetupCities{}), action_completed: true})
+                } else {
+                    Some(StateTransition{next_action: Box::new(SetupBoard{}), action_completed: true})
+                }
+            }
+            Err(e) => {
+                game_ui_data.report_rule_error(e);
+                None
+            }
+        }
+    }
+}
 impl PlayerActionControl for SetupBoard {
     fn get_action_type(&self) -> PlayerActionType {
         PlayerActionType::SetupBoard
     }
 
     fn mouse_clicked(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
-        let board_piece_dropped = game_ui_data.drop_board_piece();
+        match game_ui_data.three_pos_under_mouse {
+            Some(positions) => self.drop_board_piece(game_ui_data, positions),
+            None => None
+        }
+    }
 
-        const PIECES_PER_PLAYER: usize = 9;
+    fn random_move(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
+        let destinations: Vec<_> = all_board_piece_destinations().into_iter()
+            .filter(|&positions| game_ui_data.board_piece_placement_ok(positions))
+            .collect();
+        let positions = random_element(&destinations)?;
+        self.drop_board_piece(game_ui_data, positions)
+    }
 
-        if board_piece_dropped {
-            if game_ui_data.unplaced_board_pieces.len() <= gameboard::gameboard::game_constants::BOARD_PIECES.len() - PIECES_PER_PLAYER * game_ui_data.num_players as usize {
-                Some(StateTransition{next_action: Box::new(SetupCities{}), turn_completed: true})
-            } else {
-                Some(StateTransition{next_action: Box::new(SetupBoard{}), turn_completed: true})
+    fn key_pressed(&mut self, game_ui_data: &mut GameUIData, scancode: &sdl2::keyboard::Scancode) -> Option<StateTransition> {
+        use gameboard::gameboard::BoardPieceOrientation;
+        use sdl2::keyboard::Scancode::*;
+
+        if *scancode == R {
+            game_ui_data.board_piece_orientation = game_ui_data.board_piece_orientation.next();
+        }
+
+        // Only draft mode has a hand of pieces to choose from.
+        match game_ui_data.settings.board_setup_mode {
+            BoardSetupMode::Draft { .. } => {}
+            _ => { return None; }
+        }
+
+        let hand_index = match scancode {
+            Num1 | Kp1 => Some(0),
+            Num2 | Kp2 => Some(1),
+            Num3 | Kp3 => Some(2),
+            Num4 | Kp4 => Some(3),
+            Num5 | Kp5 => Some(4),
+            Num6 | Kp6 => Some(5),
+            Num7 | Kp7 => Some(6),
+            Num8 | Kp8 => Some(7),
+            Num9 | Kp9 => Some(8),
+            _ => None
+        };
+        if let Some(hand_index) = hand_index {
+            if hand_index < game_ui_data.board_piece_hand.len() {
+                game_ui_data.selected_board_piece = hand_index;
             }
-        } else {
-            None
         }
+
+        match scancode {
+            A => { game_ui_data.board_piece_orientation = BoardPieceOrientation::A; }
+            B => { game_ui_data.board_piece_orientation = BoardPieceOrientation::B; }
+            C => { game_ui_data.board_piece_orientation = BoardPieceOrientation::C; }
+            _ => {}
+        }
+        None
     }
 
-    fn key_pressed(&mut self, game_ui_data: &mut GameUIData, scancode: &sdl2::keyboard::Scancode) -> Option<StateTransition> {
+    fn mouse_wheel_scrolled(&mut self, game_ui_data: &mut GameUIData, scroll_amount: i32) -> Option<StateTransition> {
+        // Scrolling down turns the piece clockwise, scrolling up turns it back.
+        if scroll_amount < 0 {
+            game_ui_data.board_piece_orientation = game_ui_data.board_piece_orientation.next();
+        } else if scroll_amount > 0 {
+            game_ui_data.board_piece_orientation = game_ui_data.board_piece_orientation.previous();
+        }
         None
     }
 
@@ -96,38 +217,73 @@ impl PlayerActionControl for SetupBoard {
                     &gl,
                     &shader_program,
                     (pos_under_mouse_a, pos_under_mouse_b, pos_under_mouse_c),
-                    &game_ui_data.game_board);
+                    &game_ui_data.game_board,
+                    game_ui_data.next_board_piece(),
+                    game_ui_data.board_piece_placement_ok((pos_under_mouse_a, pos_under_mouse_b, pos_under_mouse_c)));
             }
             None => {}
         }
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Game Setup".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Lay board game pieces to build the map.".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("game_setup"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("board_setup_help"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("board_setup_turn_help"));
+
+        if let BoardSetupMode::Draft { .. } = game_ui_data.settings.board_setup_mode {
+            use gameboard::gameboard::BoardPieceOrientation;
+
+            let hand_text = game_ui_data.board_piece_hand.iter().enumerate().map(|(hand_index, board_piece)| {
+                let orientation = if hand_index == game_ui_data.selected_board_piece { game_ui_data.board_piece_orientation } else { BoardPieceOrientation::A };
+                let (space_type_a, space_type_b, space_type_c) = board_piece.oriented(orientation);
+                format!(
+                    "{}{}. {} {} {}",
+                    if hand_index == game_ui_data.selected_board_piece { "> " } else { "" },
+                    hand_index + 1,
+                    space_type_a.display_name(),
+                    space_type_b.display_name(),
+                    space_type_c.display_name())
+            }).collect::<Vec<String>>().join("    ");
+
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.66 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                hand_text);
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.58 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                localization::text("draft_help"));
+        }
     }
 }
 
 #[derive(Clone)]
 pub struct SetupCities {}
+impl SetupCities {
+    fn drop_city(&self, game_ui_data: &mut GameUIData, position: GameBoardSpacePos) -> Option<StateTransition> {
+        match game_ui_data.drop_city(position) {
+            Ok(()) => {
+                if game_ui_data.city_setup_completed() {
+                    Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: true})
+                } else {
+                    Some(StateTransition{next_action: Box::new(SetupCities{}), action_completed: true})
+                }
+            }
+            Err(e) => {
+                game_ui_data.report_rule_error(e);
+                None
+            }
+        }
+    }
+}
 impl PlayerActionControl for SetupCities {
     fn get_action_type(&self) -> PlayerActionType {
         PlayerActionType::SetupCities
     }
 
     fn mouse_clicked(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
-        let city_dropped = game_ui_data.drop_city();
-
-        if city_dropped {
-            if game_ui_data.game_board.num_cities() >= 3 * game_ui_data.num_players as usize {
-                Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: true})
-            } else {
-                Some(StateTransition{next_action: Box::new(SetupCities{}), turn_completed: true})
-            }
-        } else {
-            None
+        match game_ui_data.one_pos_under_mouse {
+            Some(position) => self.drop_city(game_ui_data, position),
+            None => None
         }
     }
 
@@ -135,6 +291,14 @@ impl PlayerActionControl for SetupCities {
         None
     }
 
+    fn random_move(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
+        let positions: Vec<_> = all_game_board_positions().into_iter()
+            .filter(|&position| game_ui_data.game_board.space_ok_for_city(position))
+            .collect();
+        let position = random_element(&positions)?;
+        self.drop_city(game_ui_data, position)
+    }
+
     fn draw_highlight(
         &self,
         game_ui_data: &mut GameUIData,
@@ -153,17 +317,18 @@ impl PlayerActionControl for SetupCities {
                     &images.get_city_image(&game_ui_data.player_color),
                     pos_under_mouse,
                     &game_ui_data.game_board,
-                    drawable_size);
+                    drawable_size,
+                    &game_ui_data.camera);
             }
             None => {}
         }
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "City Setup".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Place cities to determine your starting positions.".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("action_setup_cities"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("city_setup_help"));
     }
 }
 
@@ -182,21 +347,33 @@ impl PlayerActionControl for ChooseAction {
         use sdl2::keyboard::Scancode::*;
         match scancode {
             Num1 | Kp1 => {
-                if Recruitment::is_action_viable(game_ui_data) { Some(StateTransition{next_action: Box::new(Recruitment{ selected_city: None }), turn_completed: false}) }
+                if Recruitment::is_action_viable(game_ui_data) { ChooseAction::start_action(game_ui_data, Box::new(Recruitment{ selected_city: None })) }
                 else { None }
             }
             Num2 | Kp2 => {
-                if Movement::is_action_viable(game_ui_data) { Some(StateTransition{next_action: Box::new(Movement{ selected_knight: None, first_move: None }), turn_completed: false}) }
+                if Movement::is_action_viable(game_ui_data) { ChooseAction::start_action(game_ui_data, Box::new(Movement{ selected_knight: None, moves: Vec::new() })) }
                 else { None }
             }
-            Num3 | Kp3 => { Some(StateTransition{next_action: Box::new(Construction{}), turn_completed: false}) }
-            Num4 | Kp4 => { Some(StateTransition{next_action: Box::new(NewCity{}), turn_completed: false}) }
-            Num5 | Kp5 => { Some(StateTransition{next_action: Box::new(Expedition{}), turn_completed: false}) }
-            Num6 | Kp6 => { Some(StateTransition{next_action: Box::new(NobleTitle{}), turn_completed: false}) }
+            Num3 | Kp3 => { ChooseAction::start_action(game_ui_data, Box::new(Construction{ selected_space: None })) }
+            Num4 | Kp4 => { ChooseAction::start_action(game_ui_data, Box::new(NewCity{})) }
+            Num5 | Kp5 => { ChooseAction::start_action(game_ui_data, Box::new(Expedition{})) }
+            Num6 | Kp6 => { ChooseAction::start_action(game_ui_data, Box::new(NobleTitle{})) }
+            E => {
+                // Stop early, without using the rest of the actions.
+                if game_ui_data.turn_state.actions_taken() > 0 {
+                    game_ui_data.end_turn();
+                }
+                None
+            }
             _ => { None }
         }
     }
 
+    fn random_move(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
+        let mut action = ChooseAction::random_action(game_ui_data)?;
+        action.random_move(game_ui_data)
+    }
+
     fn draw_highlight(
         &self,
         game_ui_data: &mut GameUIData,
@@ -209,10 +386,43 @@ impl PlayerActionControl for ChooseAction {
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Choose Action".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "1. Recruitment  2. Movement  3. Construction  4. New City  5. Expedition  6. Noble Title".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("action_choose_action"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("choose_action_menu"));
+        if game_ui_data.turn_state.actions_taken() > 0 {
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                localization::format("choose_action_actions_left", &[&game_ui_data.turn_state.actions_left(&game_ui_data.settings.turn_rules)]));
+        }
+    }
+}
+
+impl ChooseAction {
+    // Only recruiting and moving can be done at random.
+    fn random_action(game_ui_data: &mut GameUIData) -> Option<Box<PlayerActionControl>> {
+        let turn_rules = game_ui_data.settings.turn_rules;
+        let mut choices: Vec<Box<PlayerActionControl>> = Vec::new();
+        if Recruitment::is_action_viable(game_ui_data) && game_ui_data.turn_state.action_allowed(PlayerActionType::Recruitment, &turn_rules) {
+            choices.push(Box::new(Recruitment{ selected_city: None }));
+        }
+        if Movement::is_action_viable(game_ui_data) && game_ui_data.turn_state.action_allowed(PlayerActionType::Movement, &turn_rules) {
+            choices.push(Box::new(Movement{ selected_knight: None, moves: Vec::new() }));
+        }
+        if choices.is_empty() {
+            None
+        } else {
+            let choice = rand::thread_rng().gen_range(0, choices.len());
+            Some(choices.swap_remove(choice))
+        }
+    }
+
+    fn start_action(game_ui_data: &mut GameUIData, next_action: Box<PlayerActionControl>) -> Option<StateTransition> {
+        let turn_rules = game_ui_data.settings.turn_rules;
+        if !game_ui_data.turn_state.action_allowed(next_action.get_action_type(), &turn_rules) {
+            game_ui_data.report_rule_error(RuleError::ActionRepeatLimit { max: turn_rules.max_repeats_per_action });
+            return None;
+        }
+        Some(StateTransition{next_action: next_action, action_completed: false})
     }
 }
 
@@ -221,7 +431,7 @@ pub struct Recruitment { selected_city: Option<GameBoardSpacePos> }
 impl Recruitment {
     fn is_action_viable(game_ui_data: &mut GameUIData) -> bool {
         let player_inventory = game_ui_data.get_mut_active_player_inventory();
-        player_inventory.num_knights > 0
+        player_inventory.num_knights > 0 && player_inventory.resources.can_afford(&costs::KNIGHT)
     }
     fn is_space_viable(position: GameBoardSpacePos, game_ui_data: &mut GameUIData) -> bool {
         for city in game_ui_data.game_board.cities() {
@@ -231,16 +441,49 @@ impl Recruitment {
         }
         false
     }
+    // Cities next to water can recruit more knights at once.
+    fn city_recruit_limit(position: GameBoardSpacePos, game_board: &GameBoard) -> u8 {
+        match position.all_neighboring_positions().iter().find(|&&gameboard_pos| game_board.get_board_space_type(gameboard_pos) == gameboard::gameboard::GameBoardSpaceType::Water) {
+            Some(_) => { 3 }
+            None => { 2 }
+        }
+    }
     fn max_number_of_knights_to_add(position: GameBoardSpacePos, game_ui_data: &mut GameUIData) -> u8 {
         let player_inventory = game_ui_data.get_active_player_inventory();
-        match position.all_neighboring_positions().iter().find(|&&gameboard_pos| game_ui_data.game_board.get_board_space_type(gameboard_pos) == gameboard::gameboard::GameBoardSpaceType::Water) {
-            Some(_) => { cmp::min(player_inventory.num_knights, 3) }
-            None => { cmp::min(player_inventory.num_knights, 2) }
+        // Each knight has to be paid for, too.
+        let num_affordable = cmp::min(player_inventory.resources.how_many_affordable(&costs::KNIGHT), 3) as u8;
+        cmp::min(cmp::min(player_inventory.num_knights, Recruitment::city_recruit_limit(position, &game_ui_data.game_board)), num_affordable)
+    }
+    fn check_recruits(position: GameBoardSpacePos, num_knights: u8, game_board: &GameBoard) -> Result<(), RuleError> {
+        let max = Recruitment::city_recruit_limit(position, game_board);
+        if num_knights > max {
+            return Err(RuleError::TooManyRecruits { max: max });
         }
+        game_board.check_knight_placement(position)
     }
-    fn add_knights(&self, game_ui_data: &mut GameUIData, num_knights: usize) {
+    fn add_knights(&self, game_ui_data: &mut GameUIData, num_knights: u8) -> Result<(), RuleError> {
+        let position = self.selected_city.unwrap();
+        // Check the city's space before anything is taken from the supply, so that nothing is lost if the knights can't go there.
+        Recruitment::check_recruits(position, num_knights, &game_ui_data.game_board)?;
+        game_ui_data.get_mut_active_player_inventory().take(PieceType::Knight, num_knights, &costs::KNIGHT.times(num_knights as u32))?;
+        let player_color = game_ui_data.player_color;
         for i in 0..num_knights {
-            game_ui_data.game_board.add_knight(self.selected_city.unwrap(), game_ui_data.player_color);
+            game_ui_data.game_board.add_knight(position, player_color)?;
+            game_ui_data.animations.drop_piece(PieceType::Knight, player_color, position);
+        }
+        game_ui_data.audio.play(SoundEffect::PiecePlaced);
+        Ok(())
+    }
+    fn recruit(&self, game_ui_data: &mut GameUIData, num_knights: u8) -> Option<StateTransition> {
+        if self.selected_city.is_none() {
+            return None;
+        }
+        match self.add_knights(game_ui_data, num_knights) {
+            Ok(()) => Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: true}),
+            Err(e) => {
+                game_ui_data.report_rule_error(e);
+                None
+            }
         }
     }
 }
@@ -268,37 +511,9 @@ impl PlayerActionControl for Recruitment {
     fn key_pressed(&mut self, game_ui_data: &mut GameUIData, scancode: &sdl2::keyboard::Scancode) -> Option<StateTransition> {
         use sdl2::keyboard::Scancode::*;
         match scancode {
-            Num1 | Kp1 => {
-                if let Some(game_board_pos) = self.selected_city {
-                    assert!(Recruitment::max_number_of_knights_to_add(game_board_pos, game_ui_data) >= 1);
-                    self.add_knights(game_ui_data, 1);
-                    game_ui_data.get_mut_active_player_inventory().num_knights -= 1;
-                    Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: true})
-                }
-                else { None }
-            }
-            Num2 | Kp2 => {
-                if let Some(game_board_pos) = self.selected_city {
-                    if Recruitment::max_number_of_knights_to_add(game_board_pos, game_ui_data) >= 2 {
-                        self.add_knights(game_ui_data, 2);
-                        game_ui_data.get_mut_active_player_inventory().num_knights -= 2;
-                        Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: true})
-                    }
-                    else { None }
-                }
-                else { None }
-            }
-            Num3 | Kp3 => {
-                if let Some(game_board_pos) = self.selected_city {
-                    if Recruitment::max_number_of_knights_to_add(game_board_pos, game_ui_data) >= 3 {
-                        self.add_knights(game_ui_data, 3);
-                        game_ui_data.get_mut_active_player_inventory().num_knights -= 3;
-                        Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: true})
-                    }
-                    else { None }
-                }
-                else { None }
-            }
+            Num1 | Kp1 => { self.recruit(game_ui_data, 1) }
+            Num2 | Kp2 => { self.recruit(game_ui_data, 2) }
+            Num3 | Kp3 => { self.recruit(game_ui_data, 3) }
 
             Backspace => {
                 if self.selected_city.is_some() {
@@ -308,13 +523,21 @@ impl PlayerActionControl for Recruitment {
                 }
                 else {
                     // Undo action selection
-                    Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: false})
+                    Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: false})
                 }
             }
             _ => { None }
         }
     }
 
+    // Recruit one knight in one of the player's cities.
+    fn random_move(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
+        let player_color = game_ui_data.player_color;
+        let cities: Vec<GameBoardSpacePos> = game_ui_data.game_board.cities().filter(|city| city.owner == player_color).map(|city| city.position).collect();
+        self.selected_city = random_element(&cities);
+        self.recruit(game_ui_data, 1)
+    }
+
     fn draw_highlight(
         &self,
         game_ui_data: &mut GameUIData,
@@ -342,52 +565,49 @@ impl PlayerActionControl for Recruitment {
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Recruitment".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("action_recruitment"));
         match self.selected_city {
             None => {
-                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                    "Pick a city to add knights to.".to_string());
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::text("recruitment_pick_city"));
             }
             Some(selected_city) => {
-                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                    format!("Enter the number of knights to recruit.  Max: {}", Recruitment::max_number_of_knights_to_add(selected_city, game_ui_data)));
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::format("recruitment_how_many", &[&Recruitment::max_number_of_knights_to_add(selected_city, game_ui_data)]));
             }
         }
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Press Backspace to cancel.".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("press_backspace_to_cancel"));
     }
 }
 
 #[derive(Clone)]
 pub struct Movement {
     selected_knight: Option<GameBoardSpacePos>,
-    first_move: Option<(GameBoardSpacePos, GameBoardSpacePos)>
+    // The moves made so far in this action, as (from, to).
+    moves: Vec<(GameBoardSpacePos, GameBoardSpacePos)>
 }
 impl Movement {
     fn is_action_viable(game_ui_data: &mut GameUIData) -> bool {
         let knights = game_ui_data.game_board.knights();
         let player_color = game_ui_data.player_color;
-        let movable_knights = knights.filter(|ref knight| knight.owner == player_color && Movement::is_from_space_viable(knight.position, game_ui_data, None));
+        let movable_knights = knights.filter(|ref knight| knight.owner == player_color && Movement::is_from_space_viable(knight.position, game_ui_data, &[]));
         let movable_knight_count = movable_knights.count();
         movable_knight_count > 0
     }
 
-    fn is_from_space_viable(from_pos: GameBoardSpacePos, game_ui_data: &GameUIData, first_move: Option<(GameBoardSpacePos, GameBoardSpacePos)>) -> bool {
+    fn is_from_space_viable(from_pos: GameBoardSpacePos, game_ui_data: &GameUIData, moves: &[(GameBoardSpacePos, GameBoardSpacePos)]) -> bool {
         let game_board = &game_ui_data.game_board;
         let num_owned_knights_in_from_pos =
             game_board.knights()
                       .filter(|ref knight| knight.position == from_pos && knight.owner == game_ui_data.player_color)
                       .count();
 
-        if let Some((first_move_from_pos, first_move_to_pos)) = first_move {
-            // If the first knight has been moved to this position, and there is only one knight now at this position,
-            // then there is no knight that can be moved from this position. (you cannot move a single knight twice in a turn)
-            if first_move_to_pos == from_pos && num_owned_knights_in_from_pos == 1 {
-                return false
-            }
-        }
-        if num_owned_knights_in_from_pos > 0 {
+        // Knights that have already been moved to this position can't be moved again. (you cannot move a single knight twice in one action)
+        let num_moved_knights_in_from_pos = moves.iter().filter(|&&(_, to_pos)| to_pos == from_pos).count();
+
+        if num_owned_knights_in_from_pos > num_moved_knights_in_from_pos {
             // There are knights in this position that can be moved.
             // Confirm that there is at least one neighboring position that could potentially be moved into.
             let neighboring_positions = from_pos.all_neighboring_positions();
@@ -399,7 +619,7 @@ impl Movement {
     }
 
     fn is_to_space_viable(from_pos: GameBoardSpacePos, to_pos: GameBoardSpacePos, game_ui_data: &GameUIData) -> bool {
-        to_pos.is_neighbor(from_pos) && game_ui_data.game_board.space_ok_for_knight(to_pos, game_ui_data.player_color)
+        game_ui_data.game_board.knight_reachable_spaces(from_pos, game_ui_data.player_color, 1).contains(&to_pos)
     }
 
 }
@@ -415,19 +635,26 @@ impl PlayerActionControl for Movement {
                 // Knight has been selected.
                 // Move the knight to the space under the cursor if it is a viable to space.
                 let to_pos = pos_under_mouse;
-                if Movement::is_to_space_viable(from_pos, to_pos, game_ui_data) && to_pos.is_neighbor(from_pos) {
-                    let killed_knights = game_ui_data.game_board.move_knight(from_pos, to_pos, game_ui_data.player_color).unwrap();
-                    for knight in killed_knights {
-                        game_ui_data.player_inventories.get_mut(&knight.owner).unwrap().num_knights += 1;
-                    }
-                    if self.first_move.is_some() {
-                        // Already moved once, so turn is over.
-                        return Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: true})
-                    }
-                    else {
-                        // Moved first knight.
-                        self.first_move = Some((from_pos, to_pos));
+                let player_color = game_ui_data.player_color;
+                match game_ui_data.game_board.move_knight(from_pos, to_pos, player_color) {
+                    Ok(battle_report) => {
+                        // Knights that fell back after attacking a city walk there and back again.
+                        let fell_back = battle_report.as_ref().map_or(false, |battle_report| battle_report.attacker_fell_back);
+                        let path = if fell_back { vec![from_pos, to_pos, from_pos] } else { vec![from_pos, to_pos] };
+                        game_ui_data.animations.move_knight(player_color, path);
+                        game_ui_data.audio.play(SoundEffect::KnightMoved);
+                        if let Some(battle_report) = battle_report {
+                            game_ui_data.record_battle(battle_report);
+                        }
+                        self.moves.push((from_pos, to_pos));
                         self.selected_knight = None;
+                        if self.moves.len() as u32 >= game_ui_data.settings.turn_rules.moves_per_movement {
+                            // Used up all of the moves, so the action is over.
+                            return Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: true})
+                        }
+                    }
+                    Err(e) => {
+                        game_ui_data.report_rule_error(e);
                     }
                 }
             }
@@ -435,7 +662,7 @@ impl PlayerActionControl for Movement {
                 // Knight hasn't been selected yet.
                 // Select the knight under the cursor if it is a viable from space.
                 let from_pos = pos_under_mouse;
-                if Movement::is_from_space_viable(from_pos, game_ui_data, self.first_move) {
+                if Movement::is_from_space_viable(from_pos, game_ui_data, &self.moves) {
                     self.selected_knight = Some(from_pos);
                 } else {
                     self.selected_knight = None;
@@ -450,7 +677,7 @@ impl PlayerActionControl for Movement {
         match scancode {
 
             Backspace => {
-                if self.first_move.is_some() && self.selected_knight.is_none() {
+                if !self.moves.is_empty() && self.selected_knight.is_none() {
                     None
                 }
                 else if self.selected_knight.is_some() {
@@ -460,13 +687,13 @@ impl PlayerActionControl for Movement {
                 }
                 else {
                     // Undo action selection
-                    Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: false})
+                    Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: false})
                 }
             }
             Y => {
-                if self.first_move.is_some() {
-                    // Finish turn
-                    Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: true})
+                if !self.moves.is_empty() {
+                    // Finish moving
+                    Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: true})
                 }
                 else {
                     None
@@ -476,6 +703,51 @@ impl PlayerActionControl for Movement {
         }
     }
 
+    // Move one knight one space, and finish the action.
+    fn random_move(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
+        self.selected_knight = None;
+        let player_color = game_ui_data.player_color;
+        let mut possible_moves = Vec::new();
+        {
+            let knight_positions = game_ui_data.game_board.knights().filter(|knight| knight.owner == player_color).map(|knight| knight.position);
+            for from_pos in knight_positions {
+                if Movement::is_from_space_viable(from_pos, game_ui_data, &self.moves) {
+                    for to_pos in game_ui_data.game_board.knight_reachable_spaces(from_pos, player_color, 1) {
+                        possible_moves.push((from_pos, to_pos));
+                    }
+                }
+            }
+        }
+
+        match random_element(&possible_moves) {
+            Some((from_pos, to_pos)) => {
+                match game_ui_data.game_board.move_knight(from_pos, to_pos, player_color) {
+                    Ok(battle_report) => {
+                        // Knights that fell back after attacking a city walk there and back again.
+                        let fell_back = battle_report.as_ref().map_or(false, |battle_report| battle_report.attacker_fell_back);
+                        let path = if fell_back { vec![from_pos, to_pos, from_pos] } else { vec![from_pos, to_pos] };
+                        game_ui_data.animations.move_knight(player_color, path);
+                        game_ui_data.audio.play(SoundEffect::KnightMoved);
+                        if let Some(battle_report) = battle_report {
+                            game_ui_data.record_battle(battle_report);
+                        }
+                        self.moves.push((from_pos, to_pos));
+                    }
+                    Err(e) => {
+                        game_ui_data.report_rule_error(e);
+                        return None;
+                    }
+                }
+            }
+            None => {
+                if self.moves.is_empty() {
+                    return None;
+                }
+            }
+        }
+        Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: true})
+    }
+
     fn draw_highlight(
         &self,
         game_ui_data: &mut GameUIData,
@@ -514,7 +786,7 @@ impl PlayerActionControl for Movement {
                     GameBoardSpaceType::Void => { } //Don't highlight ok or bad if this is a void space.
                     _ => {
                         // Confirm that there is a move that can be made from this space.
-                        if Movement::is_from_space_viable(from_pos, game_ui_data, self.first_move) {
+                        if Movement::is_from_space_viable(from_pos, game_ui_data, &self.moves) {
                             gameboard::gameboard_drawing::highlight_space_ok(gl, shader_program, from_pos);
                         } else {
                             gameboard::gameboard_drawing::highlight_space_bad(gl, shader_program, from_pos);
@@ -528,49 +800,104 @@ impl PlayerActionControl for Movement {
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
         if let Some(selected_knight) = self.selected_knight {
             // Knight has been selected.
-            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                "Movement".to_string());
-            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                "Select a space to move to.".to_string());
-            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                "Press Backspace to cancel.".to_string());
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                localization::text("action_movement"));
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                localization::text("movement_select_destination"));
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                localization::text("press_backspace_to_cancel"));
         }
         else {
             // Knight hasn't been selected yet.
-            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                "Movement".to_string());
-            if self.first_move.is_none() {
-                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                    "Select a knight to move.".to_string());
-                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                    "Press Backspace to cancel.".to_string());
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                localization::text("action_movement"));
+            if self.moves.is_empty() {
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::text("movement_select_knight"));
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::text("press_backspace_to_cancel"));
             }
             else {
-                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                    "Select a second knight to move.".to_string());
-                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-                    "Press Y to finish your turn.".to_string());
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::format("movement_select_another", &[&game_ui_data.settings.turn_rules.moves_per_movement.saturating_sub(self.moves.len() as u32)]));
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::text("movement_finish"));
             }
         }
     }
 }
 
 #[derive(Clone)]
-pub struct Construction {}
+pub struct Construction { selected_space: Option<GameBoardSpacePos> }
+impl Construction {
+    fn is_space_viable(position: GameBoardSpacePos, game_ui_data: &GameUIData) -> bool {
+        game_ui_data.game_board.space_ok_for_building(position, game_ui_data.player_color)
+    }
+    fn build(game_ui_data: &mut GameUIData, position: GameBoardSpacePos, piece: PieceType) -> Result<(), RuleError> {
+        let player_color = game_ui_data.player_color;
+        game_ui_data.game_board.check_building_placement(position, player_color)?;
+        match piece {
+            PieceType::Village => {
+                game_ui_data.get_mut_active_player_inventory().take(PieceType::Village, 1, &costs::VILLAGE)?;
+                game_ui_data.game_board.add_village(position, player_color)?;
+            }
+            PieceType::Stronghold => {
+                game_ui_data.get_mut_active_player_inventory().take(PieceType::Stronghold, 1, &costs::STRONGHOLD)?;
+                game_ui_data.game_board.add_stronghold(position, player_color)?;
+            }
+            _ => { return Err(RuleError::WrongPhase); }
+        }
+        game_ui_data.animations.drop_piece(piece, player_color, position);
+        game_ui_data.audio.play(SoundEffect::PiecePlaced);
+        game_ui_data.post_game_event(NotificationKind::Construction, localization::format("event_built", &[&player_color.name(), &piece.name()]));
+        Ok(())
+    }
+    fn build_selected(&self, game_ui_data: &mut GameUIData, piece: PieceType) -> Option<StateTransition> {
+        let position = match self.selected_space {
+            Some(position) => position,
+            None => { return None; }
+        };
+        match Construction::build(game_ui_data, position, piece) {
+            Ok(()) => Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: true}),
+            Err(e) => {
+                game_ui_data.report_rule_error(e);
+                None
+            }
+        }
+    }
+}
 impl PlayerActionControl for Construction {
     fn get_action_type(&self) -> PlayerActionType {
         PlayerActionType::Construction
     }
 
     fn mouse_clicked(&mut self, game_ui_data: &mut GameUIData) -> Option<StateTransition> {
+        if let Some(pos_under_mouse) = game_ui_data.one_pos_under_mouse {
+            if Construction::is_space_viable(pos_under_mouse, game_ui_data) {
+                self.selected_space = Some(pos_under_mouse);
+            } else {
+                self.selected_space = None;
+            }
+        }
         None
     }
 
     fn key_pressed(&mut self, game_ui_data: &mut GameUIData, scancode: &sdl2::keyboard::Scancode) -> Option<StateTransition> {
         use sdl2::keyboard::Scancode::*;
         match scancode {
-            // Undo action selection
-            Backspace => { Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: false}) }
+            Num1 | Kp1 => { self.build_selected(game_ui_data, PieceType::Village) }
+            Num2 | Kp2 => { self.build_selected(game_ui_data, PieceType::Stronghold) }
+            Backspace => {
+                if self.selected_space.is_some() {
+                    // Undo space selection
+                    self.selected_space = None;
+                    None
+                }
+                else {
+                    // Undo action selection
+                    Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: false})
+                }
+            }
             _ => { None }
         }
     }
@@ -584,15 +911,38 @@ impl PlayerActionControl for Construction {
         images: &SVGImages,
         drawable_size: (u32, u32))
     {
+        if let Some(selected_space) = self.selected_space {
+            gameboard::gameboard_drawing::highlight_space_ok(gl, shader_program, selected_space);
+        }
+        if let Some(pos_under_mouse) = game_ui_data.one_pos_under_mouse {
+            match game_ui_data.game_board.get_board_space_type(pos_under_mouse) {
+                gameboard::gameboard::GameBoardSpaceType::Void => {}
+                _ => {
+                    if Construction::is_space_viable(pos_under_mouse, game_ui_data) {
+                        gameboard::gameboard_drawing::highlight_space_ok(gl, shader_program, pos_under_mouse);
+                    } else {
+                        gameboard::gameboard_drawing::highlight_space_bad(gl, shader_program, pos_under_mouse);
+                    }
+                }
+            }
+        }
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Construction".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Select a knight to build with.".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Press Backspace to cancel.".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("action_construction"));
+        match self.selected_space {
+            None => {
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::text("construction_select_knight"));
+            }
+            Some(_) => {
+                drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::format("construction_choose_building", &[&costs::VILLAGE, &costs::STRONGHOLD]));
+            }
+        }
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("press_backspace_to_cancel"));
     }
 }
 
@@ -611,7 +961,7 @@ impl PlayerActionControl for NewCity {
         use sdl2::keyboard::Scancode::*;
         match scancode {
             // Undo action selection
-            Backspace => { Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: false}) }
+            Backspace => { Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: false}) }
             _ => { None }
         }
     }
@@ -628,12 +978,12 @@ impl PlayerActionControl for NewCity {
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "New City".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Select a village to upgrade to a city.".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Press Backspace to cancel.".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("action_new_city"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("new_city_help"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("press_backspace_to_cancel"));
     }
 }
 
@@ -652,7 +1002,7 @@ impl PlayerActionControl for Expedition {
         use sdl2::keyboard::Scancode::*;
         match scancode {
             // Undo action selection
-            Backspace => { Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: false}) }
+            Backspace => { Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: false}) }
             _ => { None }
         }
     }
@@ -669,17 +1019,30 @@ impl PlayerActionControl for Expedition {
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Expedition".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Select a board space on the edge of the map.".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Press Backspace to cancel.".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("action_expedition"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("expedition_help"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("press_backspace_to_cancel"));
     }
 }
 
 #[derive(Clone)]
 pub struct NobleTitle {}
+impl NobleTitle {
+    fn upgrade(game_ui_data: &mut GameUIData) -> Result<(), RuleError> {
+        let player_inventory = game_ui_data.get_mut_active_player_inventory();
+        let next_rank = player_inventory.noble_rank.next().ok_or(RuleError::NoHigherTitle)?;
+        if !player_inventory.resources.spend(&costs::NOBLE_TITLE) {
+            return Err(RuleError::CannotAfford { cost: costs::NOBLE_TITLE });
+        }
+        player_inventory.noble_rank = next_rank;
+        let player_color = game_ui_data.player_color;
+        game_ui_data.post_game_event(NotificationKind::Title, localization::format("event_noble_title", &[&player_color.name(), &next_rank.name()]));
+        Ok(())
+    }
+}
 impl PlayerActionControl for NobleTitle {
     fn get_action_type(&self) -> PlayerActionType {
         PlayerActionType::NobleTitle
@@ -692,8 +1055,17 @@ impl PlayerActionControl for NobleTitle {
     fn key_pressed(&mut self, game_ui_data: &mut GameUIData, scancode: &sdl2::keyboard::Scancode) -> Option<StateTransition> {
         use sdl2::keyboard::Scancode::*;
         match scancode {
+            Y => {
+                match NobleTitle::upgrade(game_ui_data) {
+                    Ok(()) => Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: true}),
+                    Err(e) => {
+                        game_ui_data.report_rule_error(e);
+                        None
+                    }
+                }
+            }
             // Undo action selection
-            Backspace => { Some(StateTransition{next_action: Box::new(ChooseAction{}), turn_completed: false}) }
+            Backspace => { Some(StateTransition{next_action: Box::new(ChooseAction{}), action_completed: false}) }
             _ => { None }
         }
     }
@@ -710,14 +1082,39 @@ impl PlayerActionControl for NobleTitle {
     }
 
     fn draw_text(&self, baggage: &mut drawing::TextDrawingBaggage, game_ui_data: &mut GameUIData) {
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, 24, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Noble Title".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Press 'Y' to upgrade your noble title.".to_string());
-        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, 18, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
-            "Press Backspace to cancel.".to_string());
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.90 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("action_noble_title"));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.82 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::format("noble_title_help", &[&costs::NOBLE_TITLE]));
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.74 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("press_backspace_to_cancel"));
     }
 }
 
 #[derive(Clone)]
 pub struct End {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gameboard::gameboard::GameBoardSpaceType;
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    #[test]
+    fn recruit_limits() {
+        let mut game_board = GameBoard::new();
+        for y_pos in 0..7 {
+            game_board.set_board_space_type(pos(0, y_pos), GameBoardSpaceType::Plains);
+        }
+        assert_eq!(Recruitment::check_recruits(pos(0, 1), 2, &game_board), Ok(()));
+        assert_eq!(Recruitment::check_recruits(pos(0, 1), 3, &game_board), Err(RuleError::TooManyRecruits { max: 2 }));
+
+        // Cities next to water can recruit one more.
+        game_board.set_board_space_type(pos(0, 2), GameBoardSpaceType::Water);
+        assert_eq!(Recruitment::check_recruits(pos(0, 1), 3, &game_board), Ok(()));
+        assert_eq!(Recruitment::check_recruits(pos(0, 1), 4, &game_board), Err(RuleError::TooManyRecruits { max: 3 }));
+    }
+}
@@ -0,0 +1,216 @@
+use gameboard::gameboard::{GameBoardSpacePos,GameBoardSpaceType};
+use rules::PieceType;
+use PlayerColor;
+
+// Short animations of the pieces on the board, timed with the SDL ticks (milliseconds since the program started).
+//
+// The board itself changes straight away; the animations only change how it is drawn until they are over.
+// A knight that has moved is already on its new space, so it is hidden there (see hides) and drawn along the way instead.
+// Animations that show something the players have to see before going on are blocking: they play one after the other,
+// and the game doesn't take any clicks or key presses until they are over.
+
+const KNIGHT_STEP_TIME_MS: u32 = 250;
+const DROP_TIME_MS: u32 = 250;
+const FADE_TIME_MS: u32 = 600;
+
+#[derive(Clone)]
+pub enum AnimationKind {
+    // A knight walking along a path of neighboring spaces.
+    KnightMove { owner: PlayerColor, path: Vec<GameBoardSpacePos> },
+    // A new piece dropping onto its space.
+    PieceDrop { piece: PieceType, owner: PlayerColor, position: GameBoardSpacePos },
+    // A board piece dropping onto the board during board setup.
+    BoardPieceDrop { spaces: Vec<(GameBoardSpacePos, GameBoardSpaceType)> },
+    // A piece that was taken off the board, fading away.
+    FadeOut { piece: PieceType, owner: PlayerColor, position: GameBoardSpacePos }
+}
+
+struct Animation {
+    kind: AnimationKind,
+    start_time: u32,
+    duration: u32,
+    blocking: bool
+}
+
+impl Animation {
+    fn end_time(&self) -> u32 {
+        self.start_time + self.duration
+    }
+}
+
+pub struct Animations {
+    animations: Vec<Animation>,
+    current_time: u32
+}
+
+impl Animations {
+    pub fn new() -> Animations {
+        Animations {
+            animations: Vec::new(),
+            current_time: 0
+        }
+    }
+
+    // Call once a frame, with the number of milliseconds since the program started.
+    pub fn update_time(&mut self, current_time: u32) {
+        self.current_time = current_time;
+        self.animations.retain(|animation| animation.end_time() > current_time);
+    }
+
+    pub fn clear(&mut self) {
+        self.animations.clear();
+    }
+
+    // When the blocking animations that have been added so far will be over.
+    fn queue_end_time(&self) -> u32 {
+        self.animations.iter().filter(|animation| animation.blocking).map(|animation| animation.end_time()).max().unwrap_or(0).max(self.current_time)
+    }
+
+    // Every animation waits for the blocking animations before it, so that e.g. the losers of a battle
+    // are still there when the attacking knight arrives.
+    fn add(&mut self, kind: AnimationKind, duration: u32, blocking: bool) {
+        let start_time = self.queue_end_time();
+        self.animations.push(Animation { kind: kind, start_time: start_time, duration: duration, blocking: blocking });
+    }
+
+    pub fn move_knight(&mut self, owner: PlayerColor, path: Vec<GameBoardSpacePos>) {
+        let duration = KNIGHT_STEP_TIME_MS * (path.len() as u32).saturating_sub(1).max(1);
+        self.add(AnimationKind::KnightMove { owner: owner, path: path }, duration, true);
+    }
+
+    pub fn drop_piece(&mut self, piece: PieceType, owner: PlayerColor, position: GameBoardSpacePos) {
+        self.add(AnimationKind::PieceDrop { piece: piece, owner: owner, position: position }, DROP_TIME_MS, true);
+    }
+
+    pub fn drop_board_piece(&mut self, spaces: Vec<(GameBoardSpacePos, GameBoardSpaceType)>) {
+        self.add(AnimationKind::BoardPieceDrop { spaces: spaces }, DROP_TIME_MS, true);
+    }
+
+    // Nobody has to wait for a piece to fade away.
+    pub fn fade_out(&mut self, piece: PieceType, owner: PlayerColor, position: GameBoardSpacePos) {
+        self.add(AnimationKind::FadeOut { piece: piece, owner: owner, position: position }, FADE_TIME_MS, false);
+    }
+
+    // True while the game should wait for the animations.
+    pub fn blocking(&self) -> bool {
+        self.animations.iter().any(|animation| animation.blocking)
+    }
+
+    // The animations to draw, with how far along each one is, from 0.0 to 1.0.
+    // Pieces that are going to fade away are already off the board, so they are drawn as they were until their turn comes.
+    pub fn playing(&self) -> Vec<(&AnimationKind, f32)> {
+        self.animations.iter()
+            .filter(|animation| {
+                match animation.kind {
+                    AnimationKind::FadeOut { .. } => true,
+                    _ => animation.start_time <= self.current_time
+                }
+            })
+            .map(|animation| (&animation.kind, self.current_time.saturating_sub(animation.start_time) as f32 / animation.duration as f32))
+            .collect()
+    }
+
+    // How many of the owner's pieces on this space shouldn't be drawn there yet, because they are still on their way.
+    pub fn hides(&self, piece: PieceType, owner: PlayerColor, position: GameBoardSpacePos) -> usize {
+        self.animations.iter().filter(|animation| {
+            match animation.kind {
+                AnimationKind::KnightMove { owner: knight_owner, ref path } => {
+                    piece == PieceType::Knight && knight_owner == owner && path.last() == Some(&position)
+                }
+                AnimationKind::PieceDrop { piece: drop_piece, owner: drop_owner, position: drop_position } => {
+                    drop_piece == piece && drop_owner == owner && drop_position == position
+                }
+                _ => false
+            }
+        }).count()
+    }
+
+    // Spaces under a board piece that hasn't landed yet, which should be drawn empty.
+    pub fn hidden_spaces(&self) -> Vec<GameBoardSpacePos> {
+        let mut hidden_spaces = Vec::new();
+        for animation in self.animations.iter() {
+            if let AnimationKind::BoardPieceDrop { ref spaces } = animation.kind {
+                hidden_spaces.extend(spaces.iter().map(|&(position, _)| position));
+            }
+        }
+        hidden_spaces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    fn times(animations: &Animations) -> Vec<(u32, u32)> {
+        animations.animations.iter().map(|animation| (animation.start_time, animation.end_time())).collect()
+    }
+
+    #[test]
+    fn blocking_animations_queue_up() {
+        let mut animations = Animations::new();
+        animations.update_time(1000);
+        animations.move_knight(PlayerColor::Red, vec![pos(0, 0), pos(0, 1), pos(0, 2)]);
+        animations.drop_piece(PieceType::Village, PlayerColor::Red, pos(0, 2));
+        assert_eq!(animations.queue_end_time(), 1750);
+        // Fading out doesn't hold up anything after it.
+        animations.fade_out(PieceType::Knight, PlayerColor::Blue, pos(0, 2));
+        animations.drop_piece(PieceType::Knight, PlayerColor::Red, pos(0, 3));
+        assert_eq!(times(&animations), vec![(1000, 1500), (1500, 1750), (1750, 2350), (1750, 2000)]);
+
+        animations.update_time(2100);
+        assert!(!animations.blocking());
+        assert_eq!(animations.playing().len(), 1);
+        // Once the queue has run out, new animations start straight away.
+        animations.drop_piece(PieceType::City, PlayerColor::Red, pos(0, 4));
+        assert_eq!(times(&animations)[1], (2100, 2350));
+        assert!(animations.blocking());
+    }
+
+    #[test]
+    fn knight_move_durations() {
+        let mut animations = Animations::new();
+        animations.move_knight(PlayerColor::Red, vec![pos(0, 0)]);
+        animations.move_knight(PlayerColor::Red, vec![pos(0, 0), pos(0, 1)]);
+        animations.move_knight(PlayerColor::Red, vec![pos(0, 0), pos(0, 1), pos(0, 2), pos(0, 3)]);
+        let durations: Vec<u32> = animations.animations.iter().map(|animation| animation.duration).collect();
+        assert_eq!(durations, vec![KNIGHT_STEP_TIME_MS, KNIGHT_STEP_TIME_MS, 3 * KNIGHT_STEP_TIME_MS]);
+    }
+
+    #[test]
+    fn fade_outs_are_drawn_while_they_wait() {
+        let mut animations = Animations::new();
+        animations.update_time(1000);
+        animations.move_knight(PlayerColor::Red, vec![pos(0, 0), pos(0, 1)]);
+        animations.fade_out(PieceType::Knight, PlayerColor::Blue, pos(0, 1));
+        animations.drop_piece(PieceType::Knight, PlayerColor::Red, pos(0, 2));
+        animations.update_time(1100);
+        let playing = animations.playing();
+        assert_eq!(playing.len(), 2);
+        match playing[1] {
+            (&AnimationKind::FadeOut { .. }, progress) => { assert_eq!(progress, 0.0); }
+            _ => panic!("the fade out should be drawn before it starts")
+        }
+    }
+
+    #[test]
+    fn hidden_pieces() {
+        let mut animations = Animations::new();
+        animations.drop_piece(PieceType::Knight, PlayerColor::Red, pos(0, 1));
+        animations.drop_piece(PieceType::Knight, PlayerColor::Red, pos(0, 1));
+        animations.move_knight(PlayerColor::Red, vec![pos(0, 0), pos(0, 1)]);
+        animations.move_knight(PlayerColor::Red, vec![pos(0, 1), pos(0, 2)]);
+        animations.drop_piece(PieceType::City, PlayerColor::Red, pos(0, 1));
+        animations.fade_out(PieceType::Knight, PlayerColor::Red, pos(0, 1));
+        assert_eq!(animations.hides(PieceType::Knight, PlayerColor::Red, pos(0, 1)), 3);
+        assert_eq!(animations.hides(PieceType::Knight, PlayerColor::Red, pos(0, 2)), 1);
+        assert_eq!(animations.hides(PieceType::City, PlayerColor::Red, pos(0, 1)), 1);
+        assert_eq!(animations.hides(PieceType::Knight, PlayerColor::Blue, pos(0, 1)), 0);
+
+        animations.update_time(10000);
+        assert_eq!(animations.hides(PieceType::Knight, PlayerColor::Red, pos(0, 1)), 0);
+    }
+}
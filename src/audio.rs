@@ -0,0 +1,612 @@
+use filereader::FileReader;
+use music::{MusicPhase,MusicTrack,TrackData};
+use sdl2;
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::sync::Arc;
+use std::sync::mpsc::{self,Receiver,Sender};
+
+// Sound effects and music.
+//
+// The game loop sends commands to the mixer over a channel, and the mixer runs in SDL's audio thread, adding up the
+// voices that are playing into the sound card's buffer.
+// Each effect is loaded from assets/sounds (e.g. sounds/knight_moved.wav) if the file is there, and made up on the spot otherwise.
+// Without a sound card (or without SDL audio) nothing is played, but the mix can still be rendered by hand.
+// The music (see music.rs) has its own volume, and muting silences both.
+
+const SAMPLE_RATE: i32 = 44100;
+
+// The oldest voice stops when this many are playing.
+const MAX_VOICES: usize = 16;
+
+// The oldest track that is fading out stops when this many tracks are playing.
+const MAX_MUSIC_VOICES: usize = 3;
+
+// How much F9 and F10 (or [ and ] for the music) change the volume.
+pub const VOLUME_STEP: f32 = 0.1;
+
+// How long it takes for one track to fade out and the next to fade in, in seconds.
+const MUSIC_FADE_TIME: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SoundEffect {
+    PiecePlaced,
+    KnightMoved,
+    Battle,
+    InvalidMove,
+    TurnChanged
+}
+
+impl SoundEffect {
+    pub const ALL: [SoundEffect; 5] = [SoundEffect::PiecePlaced, SoundEffect::KnightMoved, SoundEffect::Battle, SoundEffect::InvalidMove, SoundEffect::TurnChanged];
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            SoundEffect::PiecePlaced => "sounds/piece_placed.wav",
+            SoundEffect::KnightMoved => "sounds/knight_moved.wav",
+            SoundEffect::Battle => "sounds/battle.wav",
+            SoundEffect::InvalidMove => "sounds/invalid_move.wav",
+            SoundEffect::TurnChanged => "sounds/turn_changed.wav"
+        }
+    }
+}
+
+// Mono samples from -1.0 to 1.0, at the mixer's sample rate.
+pub type Samples = Arc<Vec<f32>>;
+
+pub enum AudioCommand {
+    Play { samples: Samples, volume: f32 },
+    SetVolume(f32),
+    SetMuted(bool),
+    StopAll,
+    // Fade the music that is playing out, and this track in.
+    PlayMusic(MusicTrack),
+    StopMusic,
+    SetMusicVolume(f32)
+}
+
+struct Voice {
+    samples: Samples,
+    position: usize,
+    volume: f32
+}
+
+struct MusicVoice {
+    track: MusicTrack,
+    // From 0.0 to 1.0, going up while the track fades in and down while it fades out.
+    gain: f32,
+    fading_out: bool
+}
+
+pub struct Mixer {
+    commands: Receiver<AudioCommand>,
+    voices: Vec<Voice>,
+    // The last one is the current track, and any others are fading out.
+    music: Vec<MusicVoice>,
+    volume: f32,
+    music_volume: f32,
+    muted: bool
+}
+
+impl Mixer {
+    fn new(commands: Receiver<AudioCommand>) -> Mixer {
+        Mixer {
+            commands: commands,
+            voices: Vec::new(),
+            music: Vec::new(),
+            volume: 1.0,
+            music_volume: 1.0,
+            muted: false
+        }
+    }
+
+    fn handle_commands(&mut self) {
+        while let Ok(command) = self.commands.try_recv() {
+            match command {
+                AudioCommand::Play { samples, volume } => {
+                    if self.voices.len() >= MAX_VOICES {
+                        self.voices.remove(0);
+                    }
+                    self.voices.push(Voice { samples: samples, position: 0, volume: volume });
+                }
+                AudioCommand::SetVolume(volume) => { self.volume = volume; }
+                AudioCommand::SetMuted(muted) => { self.muted = muted; }
+                AudioCommand::StopAll => { self.voices.clear(); }
+                AudioCommand::PlayMusic(track) => {
+                    if self.music.len() >= MAX_MUSIC_VOICES {
+                        self.music.remove(0);
+                    }
+                    for music_voice in self.music.iter_mut() {
+                        music_voice.fading_out = true;
+                    }
+                    self.music.push(MusicVoice { track: track, gain: 0.0, fading_out: false });
+                }
+                AudioCommand::StopMusic => {
+                    for music_voice in self.music.iter_mut() {
+                        music_voice.fading_out = true;
+                    }
+                }
+                AudioCommand::SetMusicVolume(volume) => { self.music_volume = volume; }
+            }
+        }
+    }
+
+    // Fill out with the next samples of everything that is playing.
+    pub fn mix(&mut self, out: &mut [f32]) {
+        self.handle_commands();
+        for sample in out.iter_mut() {
+            *sample = 0.0;
+        }
+        let volume = if self.muted { 0.0 } else { self.volume };
+        for voice in self.voices.iter_mut() {
+            let remaining = &voice.samples[voice.position..];
+            for (sample, voice_sample) in out.iter_mut().zip(remaining.iter()) {
+                *sample += voice_sample * voice.volume * volume;
+            }
+            voice.position += remaining.len().min(out.len());
+        }
+        self.voices.retain(|voice| voice.position < voice.samples.len());
+
+        // The music keeps going while muted, so that it doesn't start over when it comes back.
+        let music_volume = if self.muted { 0.0 } else { self.music_volume };
+        let fade_step = 1.0 / (MUSIC_FADE_TIME * SAMPLE_RATE as f32);
+        for music_voice in self.music.iter_mut() {
+            for sample in out.iter_mut() {
+                music_voice.gain = if music_voice.fading_out {
+                    (music_voice.gain - fade_step).max(0.0)
+                } else {
+                    (music_voice.gain + fade_step).min(1.0)
+                };
+                match music_voice.track.next_sample() {
+                    Some(music_sample) => { *sample += music_sample * music_voice.gain * music_volume; }
+                    None => {
+                        // Nothing more can be played from it.
+                        music_voice.fading_out = true;
+                        music_voice.gain = 0.0;
+                        break;
+                    }
+                }
+            }
+        }
+        self.music.retain(|music_voice| !music_voice.fading_out || music_voice.gain > 0.0);
+        for sample in out.iter_mut() {
+            *sample = sample.max(-1.0).min(1.0);
+        }
+    }
+}
+
+impl sdl2::audio::AudioCallback for Mixer {
+    type Channel = f32;
+
+    fn callback(&mut self, out: &mut [f32]) {
+        self.mix(out);
+    }
+}
+
+enum AudioOutput {
+    // Kept open for as long as the game runs.
+    Device { _audio_device: sdl2::audio::AudioDevice<Mixer> },
+    Null(Mixer)
+}
+
+// The game loop's end of the mixer.
+pub struct Audio {
+    commands: Sender<AudioCommand>,
+    output: AudioOutput,
+    sounds: HashMap<SoundEffect, Samples>,
+    tracks: HashMap<MusicPhase, TrackData>,
+    music_phase: Option<MusicPhase>,
+    volume: f32,
+    music_volume: f32,
+    muted: bool
+}
+
+impl Audio {
+    // Play through the sound card, or through the null backend if there isn't one.
+    pub fn open(audio_subsystem: Option<&sdl2::AudioSubsystem>, filereader: &FileReader) -> Audio {
+        let (commands, receiver) = mpsc::channel();
+        let output = match audio_subsystem {
+            Some(audio_subsystem) => {
+                let desired_spec = sdl2::audio::AudioSpecDesired {
+                    freq: Some(SAMPLE_RATE),
+                    channels: Some(1), //mono
+                    samples: None // device default sample buffer size
+                };
+                // The mixer goes to the audio thread if the device can be opened, and is dropped if it can't.
+                match audio_subsystem.open_playback(None, &desired_spec, move |_spec| Mixer::new(receiver)) {
+                    Ok(audio_device) => {
+                        println!("Audio device buffer size: {} samples", audio_device.spec().samples);
+                        audio_device.resume();
+                        AudioOutput::Device { _audio_device: audio_device }
+                    }
+                    Err(e) => {
+                        println!("Could not open the audio device, playing without sound: {}", e);
+                        let (null_commands, null_receiver) = mpsc::channel();
+                        return Audio::with_output(null_commands, AudioOutput::Null(Mixer::new(null_receiver)), filereader);
+                    }
+                }
+            }
+            None => AudioOutput::Null(Mixer::new(receiver))
+        };
+        Audio::with_output(commands, output, filereader)
+    }
+
+    // No sound card at all, e.g. for running without a display.
+    pub fn null(filereader: &FileReader) -> Audio {
+        let (commands, receiver) = mpsc::channel();
+        Audio::with_output(commands, AudioOutput::Null(Mixer::new(receiver)), filereader)
+    }
+
+    fn with_output(commands: Sender<AudioCommand>, output: AudioOutput, filereader: &FileReader) -> Audio {
+        let mut sounds = HashMap::new();
+        for &effect in SoundEffect::ALL.iter() {
+            let samples = match filereader.load_bytes(effect.file_name()) {
+                Ok(bytes) => {
+                    match decode_wav(&bytes, SAMPLE_RATE as u32) {
+                        Ok(samples) => samples,
+                        Err(e) => {
+                            println!("Could not read {}, using a made-up sound: {}", effect.file_name(), e);
+                            synthesize(effect, SAMPLE_RATE as u32)
+                        }
+                    }
+                }
+                // Not having the file is normal.
+                Err(_) => synthesize(effect, SAMPLE_RATE as u32)
+            };
+            sounds.insert(effect, Arc::new(samples));
+        }
+
+        let mut tracks = HashMap::new();
+        for &phase in MusicPhase::ALL.iter() {
+            if let Some(track_data) = TrackData::load(filereader, phase, SAMPLE_RATE as u32) {
+                tracks.insert(phase, track_data);
+            }
+        }
+
+        Audio {
+            commands: commands,
+            output: output,
+            sounds: sounds,
+            tracks: tracks,
+            music_phase: None,
+            volume: 1.0,
+            music_volume: 1.0,
+            muted: false
+        }
+    }
+
+    // The mixer is in another thread, or gone if there's no sound, so the commands can't fail in any way that matters.
+    // The null backend takes its commands straight away, so that they don't pile up in the channel when nothing renders.
+    fn send(&mut self, command: AudioCommand) {
+        let _ = self.commands.send(command);
+        if let AudioOutput::Null(ref mut mixer) = self.output {
+            mixer.handle_commands();
+        }
+    }
+
+    pub fn play(&mut self, effect: SoundEffect) {
+        let samples = self.sounds[&effect].clone();
+        self.send(AudioCommand::Play { samples: samples, volume: 1.0 });
+    }
+
+    pub fn stop_all(&mut self) {
+        self.send(AudioCommand::StopAll);
+    }
+
+    pub fn volume(&self) -> f32 {
+        self.volume
+    }
+
+    // From 0.0 (silent) to 1.0 (full volume).
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.max(0.0).min(1.0);
+        self.send(AudioCommand::SetVolume(self.volume));
+    }
+
+    // Crossfade to the music for this part of the game.  Nothing changes if it is already playing.
+    pub fn set_music_phase(&mut self, phase: MusicPhase) {
+        if self.music_phase == Some(phase) {
+            return;
+        }
+        self.music_phase = Some(phase);
+        match self.tracks.get(&phase).and_then(|track_data| track_data.start(SAMPLE_RATE as u32)) {
+            Some(track) => self.send(AudioCommand::PlayMusic(track)),
+            // Fade out whatever was playing before.
+            None => self.send(AudioCommand::StopMusic)
+        }
+    }
+
+    pub fn music_volume(&self) -> f32 {
+        self.music_volume
+    }
+
+    // From 0.0 (silent) to 1.0 (full volume), separate from the sound effects.
+    pub fn set_music_volume(&mut self, music_volume: f32) {
+        self.music_volume = music_volume.max(0.0).min(1.0);
+        self.send(AudioCommand::SetMusicVolume(self.music_volume));
+    }
+
+    pub fn muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn set_muted(&mut self, muted: bool) {
+        self.muted = muted;
+        self.send(AudioCommand::SetMuted(muted));
+    }
+
+    // With the null backend, mix the next samples by hand.  Does nothing when a sound card is playing them.
+    pub fn render(&mut self, out: &mut [f32]) {
+        if let AudioOutput::Null(ref mut mixer) = self.output {
+            mixer.mix(out);
+        }
+    }
+}
+
+// Read a .wav file into mono samples at sample_rate.
+// Handles 8 and 16 bit integer samples and 32 bit float samples, with any number of channels, which are mixed together.
+pub fn decode_wav(bytes: &[u8], sample_rate: u32) -> Result<Vec<f32>, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a WAVE file".to_string());
+    }
+
+    let read_u16 = |offset: usize| bytes[offset] as u16 | (bytes[offset + 1] as u16) << 8;
+    let read_u32 = |offset: usize| read_u16(offset) as u32 | (read_u16(offset + 2) as u32) << 16;
+
+    let mut format = None;
+    let mut data = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let chunk_id = &bytes[offset..offset + 4];
+        let chunk_size = read_u32(offset + 4) as usize;
+        let chunk_start = offset + 8;
+        let chunk_end = (chunk_start + chunk_size).min(bytes.len());
+        if chunk_id == b"fmt " && chunk_size >= 16 {
+            if chunk_start + 16 > bytes.len() {
+                return Err("the fmt chunk is cut off".to_string());
+            }
+            // (format tag, channels, sample rate, bits per sample)
+            format = Some((read_u16(chunk_start), read_u16(chunk_start + 2) as usize, read_u32(chunk_start + 4), read_u16(chunk_start + 14)));
+        } else if chunk_id == b"data" {
+            data = Some(&bytes[chunk_start..chunk_end]);
+        }
+        // Chunks are padded to an even number of bytes.
+        offset = chunk_start + chunk_size + chunk_size % 2;
+    }
+
+    let (format_tag, channels, file_sample_rate, bits_per_sample) = format.ok_or("no fmt chunk".to_string())?;
+    let data = data.ok_or("no data chunk".to_string())?;
+    if channels == 0 || file_sample_rate == 0 {
+        return Err("no channels".to_string());
+    }
+
+    let sample_size = bits_per_sample as usize / 8;
+    let read_sample: Box<Fn(&[u8]) -> f32> = match (format_tag, bits_per_sample) {
+        (1, 8) => Box::new(|sample: &[u8]| (sample[0] as f32 - 128.0) / 128.0),
+        (1, 16) => Box::new(|sample: &[u8]| (sample[0] as u16 | (sample[1] as u16) << 8) as i16 as f32 / 32768.0),
+        (3, 32) => Box::new(|sample: &[u8]| f32::from_bits(sample[0] as u32 | (sample[1] as u32) << 8 | (sample[2] as u32) << 16 | (sample[3] as u32) << 24)),
+        _ => { return Err(format!("format {} with {} bits per sample is not supported", format_tag, bits_per_sample)); }
+    };
+
+    let frame_size = sample_size * channels;
+    let mono: Vec<f32> = data.chunks(frame_size)
+        .filter(|frame| frame.len() == frame_size)
+        .map(|frame| frame.chunks(sample_size).map(|sample| read_sample(sample)).sum::<f32>() / channels as f32)
+        .collect();
+
+    Ok(resample(&mono, file_sample_rate, sample_rate))
+}
+
+// Change the sample rate, going in a straight line between samples.
+fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let length = (samples.len() as u64 * to_rate as u64 / from_rate as u64) as usize;
+    (0..length).map(|i| {
+        let position = i as f32 * from_rate as f32 / to_rate as f32;
+        let index = position as usize;
+        let next = (index + 1).min(samples.len() - 1);
+        let fraction = position - index as f32;
+        samples[index] * (1.0 - fraction) + samples[next] * fraction
+    }).collect()
+}
+
+// The same noise every time, so the effects always sound the same.
+struct Noise {
+    state: u32
+}
+
+impl Noise {
+    fn next(&mut self) -> f32 {
+        // xorshift
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state as f32 / ::std::u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+// Sounds made out of tones and noise, for when there is no .wav file.
+pub fn synthesize(effect: SoundEffect, sample_rate: u32) -> Vec<f32> {
+    let rate = sample_rate as f32;
+    let mut noise = Noise { state: 0x2545F491 };
+    // In seconds.
+    let length = match effect {
+        SoundEffect::PiecePlaced => 0.15,
+        SoundEffect::KnightMoved => 0.2,
+        SoundEffect::Battle => 0.45,
+        SoundEffect::InvalidMove => 0.2,
+        SoundEffect::TurnChanged => 0.35
+    };
+    (0..(length * rate) as usize).map(|i| {
+        let t = i as f32 / rate;
+        match effect {
+            // A low knock.
+            SoundEffect::PiecePlaced => {
+                0.6 * (2.0 * PI * 180.0 * t).sin() * (-t * 35.0).exp() + 0.15 * noise.next() * (-t * 80.0).exp()
+            }
+            // Two hoof beats.
+            SoundEffect::KnightMoved => {
+                let beat = |start: f32| if t >= start { (-(t - start) * 60.0).exp() } else { 0.0 };
+                let envelope = beat(0.0) + 0.8 * beat(0.1);
+                envelope * (0.3 * noise.next() + 0.4 * (2.0 * PI * 220.0 * t).sin())
+            }
+            // Clashing swords.
+            SoundEffect::Battle => {
+                let ring = (2.0 * PI * 1250.0 * t).sin() + 0.7 * (2.0 * PI * 1730.0 * t).sin() + 0.4 * (2.0 * PI * 2610.0 * t).sin();
+                0.25 * ring * (-t * 9.0).exp() + 0.4 * noise.next() * (-t * 30.0).exp()
+            }
+            // A low buzz.
+            SoundEffect::InvalidMove => {
+                let square = if (t * 110.0).fract() < 0.5 { 1.0 } else { -1.0 };
+                0.25 * square * (1.0 - t / length)
+            }
+            // Two rising chimes.
+            SoundEffect::TurnChanged => {
+                let chime = |start: f32, frequency: f32| if t >= start { (2.0 * PI * frequency * (t - start)).sin() * (-(t - start) * 12.0).exp() } else { 0.0 };
+                0.4 * (chime(0.0, 660.0) + chime(0.15, 880.0))
+            }
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    // A .wav file with the given format and sample data.
+    fn wav_file(format_tag: u16, channels: u16, sample_rate: u32, bits_per_sample: u16, data: &[u8]) -> Vec<u8> {
+        let block_align = channels * bits_per_sample / 8;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16_u32.to_le_bytes());
+        bytes.extend_from_slice(&format_tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits_per_sample.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    // There are no sound files next to the test executable, so every effect is synthesized.
+    fn null_audio() -> Audio {
+        Audio::null(&FileReader::from_relative_exe_path(Path::new("assets")).unwrap())
+    }
+
+    #[test]
+    fn decode_wav_reads_16_bit_samples() {
+        let data: Vec<u8> = [0_i16, 16384, -32768].iter().flat_map(|sample| sample.to_le_bytes().to_vec()).collect();
+        let samples = decode_wav(&wav_file(1, 1, 8000, 16, &data), 8000).unwrap();
+        assert_eq!(samples, vec![0.0, 0.5, -1.0]);
+    }
+
+    #[test]
+    fn decode_wav_mixes_channels_together() {
+        // Two stereo frames of 8 bit samples: (full, silent) and (silent, silent).
+        let samples = decode_wav(&wav_file(1, 2, 8000, 8, &[255, 128, 128, 128]), 8000).unwrap();
+        assert_eq!(samples.len(), 2);
+        assert!((samples[0] - 127.0 / 256.0).abs() < 1e-6);
+        assert_eq!(samples[1], 0.0);
+    }
+
+    #[test]
+    fn decode_wav_reads_float_samples() {
+        let data: Vec<u8> = [0.25_f32, -0.75].iter().flat_map(|sample| sample.to_bits().to_le_bytes().to_vec()).collect();
+        assert_eq!(decode_wav(&wav_file(3, 1, 8000, 32, &data), 8000).unwrap(), vec![0.25, -0.75]);
+    }
+
+    #[test]
+    fn decode_wav_resamples() {
+        let data = vec![128_u8; 100];
+        assert_eq!(decode_wav(&wav_file(1, 1, 11025, 8, &data), 44100).unwrap().len(), 400);
+    }
+
+    #[test]
+    fn decode_wav_rejects_bad_files() {
+        assert!(decode_wav(b"not a wave file", 44100).is_err());
+        // 24 bit samples aren't supported.
+        assert!(decode_wav(&wav_file(1, 1, 8000, 24, &[0, 0, 0]), 8000).is_err());
+        // The header says there is a 16 byte fmt chunk, but the file ends first.
+        let mut truncated = wav_file(1, 1, 8000, 16, &[]);
+        truncated.truncate(24);
+        assert!(decode_wav(&truncated, 8000).is_err());
+    }
+
+    #[test]
+    fn resample_keeps_the_shape() {
+        assert_eq!(resample(&[0.0, 1.0], 100, 100), vec![0.0, 1.0]);
+        assert_eq!(resample(&[0.0, 1.0, 0.0, -1.0], 100, 200), vec![0.0, 0.5, 1.0, 0.5, 0.0, -0.5, -1.0, -1.0]);
+        assert_eq!(resample(&[0.0, 1.0, 0.0, -1.0], 200, 100), vec![0.0, 0.0]);
+        assert!(resample(&[], 100, 200).is_empty());
+    }
+
+    #[test]
+    fn null_audio_mixes_played_effects() {
+        let mut audio = null_audio();
+        let mut out = vec![0.0; 256];
+        audio.render(&mut out);
+        assert!(out.iter().all(|&sample| sample == 0.0));
+
+        audio.play(SoundEffect::PiecePlaced);
+        audio.render(&mut out);
+        let expected = synthesize(SoundEffect::PiecePlaced, SAMPLE_RATE as u32);
+        for (sample, expected_sample) in out.iter().zip(expected.iter()) {
+            assert!((sample - expected_sample.max(-1.0).min(1.0)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn null_audio_volume_and_mute() {
+        let mut audio = null_audio();
+        let mut full = vec![0.0; 256];
+        audio.play(SoundEffect::Battle);
+        audio.render(&mut full);
+
+        let mut audio = null_audio();
+        let mut half = vec![0.0; 256];
+        audio.set_volume(0.5);
+        audio.play(SoundEffect::Battle);
+        audio.render(&mut half);
+        for (half_sample, full_sample) in half.iter().zip(full.iter()) {
+            assert!((half_sample * 2.0 - full_sample).abs() < 1e-5);
+        }
+
+        let mut audio = null_audio();
+        let mut muted = vec![0.0; 256];
+        audio.set_muted(true);
+        audio.play(SoundEffect::Battle);
+        audio.render(&mut muted);
+        assert!(muted.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn null_audio_stops_effects() {
+        let mut audio = null_audio();
+        audio.play(SoundEffect::TurnChanged);
+        audio.stop_all();
+        let mut out = vec![0.0; 256];
+        audio.render(&mut out);
+        assert!(out.iter().all(|&sample| sample == 0.0));
+    }
+
+    #[test]
+    fn null_audio_doesnt_keep_too_many_voices() {
+        let mut audio = null_audio();
+        for _ in 0..100 {
+            audio.play(SoundEffect::KnightMoved);
+        }
+        if let AudioOutput::Null(ref mixer) = audio.output {
+            assert_eq!(mixer.voices.len(), MAX_VOICES);
+        } else {
+            panic!("not the null backend");
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use drawing;
+use gl;
+use render_gl;
+use std;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Vertex buffers that are created once and reused, instead of being created and deleted for every shape.
+//
+// Shapes that stay the same from frame to frame, like the game board, go in a ShapeBatch.
+// The batch is only uploaded to the GPU again when its shapes change, and is drawn with one draw call for the
+// filled shapes and one for the outlines.
+// Everything else is streamed through one shared buffer per vertex format, with draw_vertices.
+//
+// Every draw call and vertex is counted, so the cost of a frame can be shown on screen (F11).
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VertexFormat {
+    // x, y, z, r, g, b  (basic.vert)
+    Colored,
+    // x, y, u, v  (text.vert and image.vert)
+    Textured
+}
+
+impl VertexFormat {
+    fn floats_per_vertex(&self) -> usize {
+        match self {
+            VertexFormat::Colored => 6,
+            VertexFormat::Textured => 4
+        }
+    }
+}
+
+static DRAW_CALLS: AtomicUsize = AtomicUsize::new(0);
+static VERTICES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Clone, Copy)]
+pub struct RenderStats {
+    pub draw_calls: usize,
+    pub vertices: usize
+}
+
+// Returns the counts since the last time this was called, so call it once a frame.
+pub fn take_render_stats() -> RenderStats {
+    RenderStats {
+        draw_calls: DRAW_CALLS.swap(0, Ordering::Relaxed),
+        vertices: VERTICES.swap(0, Ordering::Relaxed)
+    }
+}
+
+pub struct VertexBuffer {
+    gl: gl::Gl,
+    vao: gl::types::GLuint,
+    vbo: gl::types::GLuint,
+    format: VertexFormat,
+    // Bytes allocated on the GPU.  The buffer only grows, so it isn't reallocated every time it is filled.
+    capacity: usize,
+    num_vertices: usize
+}
+
+impl VertexBuffer {
+    pub fn new(gl: &gl::Gl, format: VertexFormat) -> VertexBuffer {
+        let mut vbo: gl::types::GLuint = 0;
+        let mut vao: gl::types::GLuint = 0;
+        let stride = (format.floats_per_vertex() * std::mem::size_of::<f32>()) as gl::types::GLint;
+        unsafe {
+            gl.GenBuffers(1, &mut vbo);
+            gl.BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl.GenVertexArrays(1, &mut vao);
+            gl.BindVertexArray(vao);
+            match format {
+                VertexFormat::Colored => {
+                    // position attribute values
+                    gl.VertexAttribPointer(0, 3, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+                    gl.EnableVertexAttribArray(0);
+                    // color attribute values
+                    gl.VertexAttribPointer(1, 3, gl::FLOAT, gl::FALSE, stride, (3 * std::mem::size_of::<f32>()) as *const gl::types::GLvoid);
+                    gl.EnableVertexAttribArray(1);
+                }
+                VertexFormat::Textured => {
+                    // position and texture coordinates, together
+                    gl.VertexAttribPointer(0, 4, gl::FLOAT, gl::FALSE, stride, std::ptr::null());
+                    gl.EnableVertexAttribArray(0);
+                }
+            }
+        }
+        VertexBuffer {
+            gl: gl.clone(),
+            vao: vao,
+            vbo: vbo,
+            format: format,
+            capacity: 0,
+            num_vertices: 0
+        }
+    }
+
+    // Replace the contents of the buffer.
+    pub fn upload(&mut self, vertices: &[f32]) {
+        let size = vertices.len() * std::mem::size_of::<f32>();
+        unsafe {
+            self.gl.BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            if size > self.capacity {
+                self.gl.BufferData(
+                    gl::ARRAY_BUFFER,
+                    size as gl::types::GLsizeiptr,
+                    vertices.as_ptr() as *const gl::types::GLvoid,
+                    gl::DYNAMIC_DRAW);
+                self.capacity = size;
+            } else {
+                self.gl.BufferSubData(
+                    gl::ARRAY_BUFFER,
+                    0,
+                    size as gl::types::GLsizeiptr,
+                    vertices.as_ptr() as *const gl::types::GLvoid);
+            }
+        }
+        self.num_vertices = vertices.len() / self.format.floats_per_vertex();
+    }
+
+    pub fn draw(&self, mode: gl::types::GLenum) {
+        self.draw_range(mode, 0, self.num_vertices);
+    }
+
+    pub fn draw_range(&self, mode: gl::types::GLenum, first: usize, count: usize) {
+        if count == 0 {
+            return;
+        }
+        unsafe {
+            self.gl.BindVertexArray(self.vao);
+            self.gl.DrawArrays(mode, first as gl::types::GLint, count as gl::types::GLsizei);
+        }
+        DRAW_CALLS.fetch_add(1, Ordering::Relaxed);
+        VERTICES.fetch_add(count, Ordering::Relaxed);
+    }
+}
+
+impl Drop for VertexBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteVertexArrays(1, &mut self.vao);
+            self.gl.DeleteBuffers(1, &mut self.vbo);
+        }
+    }
+}
+
+thread_local! {
+    static STREAM_BUFFERS: RefCell<Vec<VertexBuffer>> = RefCell::new(Vec::new());
+}
+
+// Upload vertices that are only drawn once to the shared buffer for their format, and let draw decide how to draw them.
+pub fn with_stream_buffer<F>(gl: &gl::Gl, format: VertexFormat, vertices: &[f32], draw: F) where F: FnOnce(&VertexBuffer) {
+    STREAM_BUFFERS.with(|stream_buffers| {
+        let mut stream_buffers = stream_buffers.borrow_mut();
+        if !stream_buffers.iter().any(|buffer| buffer.format == format) {
+            stream_buffers.push(VertexBuffer::new(gl, format));
+        }
+        let buffer = stream_buffers.iter_mut().find(|buffer| buffer.format == format).unwrap();
+        buffer.upload(vertices);
+        draw(buffer);
+    });
+}
+
+pub fn draw_vertices(gl: &gl::Gl, format: VertexFormat, mode: gl::types::GLenum, vertices: &[f32]) {
+    with_stream_buffer(gl, format, vertices, |buffer| buffer.draw(mode));
+}
+
+pub struct ShapeBatch {
+    triangles: Vec<f32>,
+    lines: Vec<f32>,
+    triangle_buffer: VertexBuffer,
+    line_buffer: VertexBuffer,
+    // True if the shapes have changed since they were last uploaded.
+    dirty: bool
+}
+
+impl ShapeBatch {
+    pub fn new(gl: &gl::Gl) -> ShapeBatch {
+        ShapeBatch {
+            triangles: Vec::new(),
+            lines: Vec::new(),
+            triangle_buffer: VertexBuffer::new(gl, VertexFormat::Colored),
+            line_buffer: VertexBuffer::new(gl, VertexFormat::Colored),
+            dirty: false
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.triangles.clear();
+        self.lines.clear();
+        self.dirty = true;
+    }
+
+    pub fn add_hexagon(&mut self, hex_spec: &drawing::HexagonSpec) {
+        drawing::push_hexagon_triangles(&mut self.triangles, hex_spec);
+        self.dirty = true;
+    }
+
+    pub fn add_hexagon_outline(&mut self, hex_spec: &drawing::HexagonSpec) {
+        drawing::push_hexagon_lines(&mut self.lines, hex_spec);
+        self.dirty = true;
+    }
+
+    pub fn add_rectangle(&mut self, rect_spec: &drawing::RectangleSpec) {
+        drawing::push_rectangle_triangles(&mut self.triangles, rect_spec);
+        self.dirty = true;
+    }
+
+    pub fn add_rectangle_outline(&mut self, rect_spec: &drawing::RectangleSpec) {
+        drawing::push_rectangle_lines(&mut self.lines, rect_spec);
+        self.dirty = true;
+    }
+
+    pub fn add_line(&mut self, from: (f32, f32), to: (f32, f32), color: &drawing::ColorSpec) {
+        drawing::push_line(&mut self.lines, from, to, color);
+        self.dirty = true;
+    }
+
+    // All of the outlines in the batch are drawn with the same line width.
+    pub fn draw(&mut self, shader_program: &render_gl::Program, line_width: f32) {
+        if self.dirty {
+            self.triangle_buffer.upload(&self.triangles);
+            self.line_buffer.upload(&self.lines);
+            self.dirty = false;
+        }
+        shader_program.set_used();
+        self.triangle_buffer.draw(gl::TRIANGLES);
+        if !self.lines.is_empty() {
+            unsafe {
+                self.line_buffer.gl.LineWidth(line_width);
+            }
+            self.line_buffer.draw(gl::LINES);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use drawing;
+use gameboard::gameboard::game_constants;
+use gameboard::gameboard_drawing::{drawing_constants,scaling_for_board};
+use gl;
+use render_gl;
+
+// The view of the game board.  The mouse wheel (or Page Up / Page Down) zooms in and out around the mouse cursor,
+// and dragging with the right mouse button (or the arrow keys) moves the board around.  Home goes back to the whole board.
+//
+// The camera only moves what is on the board: the hexagons are moved in basic.vert (see write_shader_data),
+// and the pieces and text on top of them are placed with to_view_pos and scaling.  The rest of the UI stays where it is.
+
+const MIN_ZOOM: f32 = 1.0;
+const MAX_ZOOM: f32 = 4.0;
+
+// How much one step of the mouse wheel zooms.
+const ZOOM_STEP: f32 = 1.2;
+
+// How far one press of an arrow key moves the board, in drawing units at 1x zoom.
+const PAN_STEP: f32 = 0.1;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Camera {
+    // The drawing position in the middle of the screen.
+    center_x: f32,
+    center_y: f32,
+    zoom: f32
+}
+
+impl Camera {
+    pub fn new() -> Camera {
+        Camera {
+            center_x: 0.0,
+            center_y: 0.0,
+            zoom: MIN_ZOOM
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Camera::new();
+    }
+
+    // Zoom in (positive steps) or out (negative steps), keeping the drawing position under the mouse cursor where it is.
+    pub fn zoom_at(&mut self, steps: i32, focus: drawing::PositionSpec) {
+        let new_zoom = (self.zoom * ZOOM_STEP.powi(steps)).max(MIN_ZOOM).min(MAX_ZOOM);
+        let ratio = self.zoom / new_zoom;
+        self.center_x = focus.x - (focus.x - self.center_x) * ratio;
+        self.center_y = focus.y - (focus.y - self.center_y) * ratio;
+        self.zoom = new_zoom;
+        self.keep_board_in_view();
+    }
+
+    // Move the view by a distance in drawing units.
+    pub fn pan(&mut self, x_distance: f32, y_distance: f32) {
+        self.center_x += x_distance;
+        self.center_y += y_distance;
+        self.keep_board_in_view();
+    }
+
+    // Move the view one step in a direction (-1, 0 or 1 for each axis), for the arrow keys.
+    // The steps get smaller as the board gets bigger, so that each step moves the board the same distance on the screen.
+    pub fn pan_step(&mut self, x_direction: f32, y_direction: f32) {
+        let step = PAN_STEP / self.zoom;
+        self.pan(x_direction * step, y_direction * step);
+    }
+
+    // Move the board along with the mouse while it is dragged.
+    pub fn drag(&mut self, x_pixels: i32, y_pixels: i32, drawable_size: (u32, u32)) {
+        let (window_width, window_height) = drawable_size;
+        let (x_scale, y_scale) = self.scaling(drawable_size);
+        // Screen y goes down, drawing y goes up.
+        self.pan(
+            -(x_pixels as f32) / window_width as f32 * 2.0 / x_scale,
+            y_pixels as f32 / window_height as f32 * 2.0 / y_scale);
+    }
+
+    // The center can go anywhere over the board, but not past its edges, so some of the board is always on the screen.
+    fn keep_board_in_view(&mut self) {
+        let board_width = drawing_constants::HEXAGON_X_SPACING * game_constants::MAX_BOARD_WIDTH as f32 + 0.25 * drawing_constants::HEXAGON_WIDTH;
+        let board_height = drawing_constants::HEXAGON_Y_SPACING * game_constants::MAX_BOARD_HEIGHT as f32 + 0.5 * drawing_constants::HEXAGON_HEIGHT;
+        self.center_x = self.center_x.max(drawing_constants::GAME_BOARD_ORIGIN_X).min(drawing_constants::GAME_BOARD_ORIGIN_X + board_width);
+        self.center_y = self.center_y.max(drawing_constants::GAME_BOARD_ORIGIN_Y).min(drawing_constants::GAME_BOARD_ORIGIN_Y + board_height);
+    }
+
+    // Like scaling_for_board, but with the zoom.
+    // Sizes on the board are multiplied by this, and so are positions after going through to_view_pos.
+    pub fn scaling(&self, drawable_size: (u32, u32)) -> (f32, f32) {
+        let (x_scale, y_scale) = scaling_for_board(drawable_size);
+        (x_scale * self.zoom, y_scale * self.zoom)
+    }
+
+    // A drawing position, measured from the middle of the view.
+    pub fn to_view_pos(&self, drawing_pos: &drawing::PositionSpec) -> drawing::PositionSpec {
+        drawing::PositionSpec { x: drawing_pos.x - self.center_x, y: drawing_pos.y - self.center_y }
+    }
+
+    // The reverse of to_view_pos, for finding what is under the mouse.
+    pub fn from_view_pos(&self, view_pos: &drawing::PositionSpec) -> drawing::PositionSpec {
+        drawing::PositionSpec { x: view_pos.x + self.center_x, y: view_pos.y + self.center_y }
+    }
+
+    // Everything drawn with the shader program after this is seen through the camera.
+    // Use drawing::write_scale_data to go back to drawing the UI.
+    pub fn write_shader_data(&self, gl: &gl::Gl, shader_program: &render_gl::Program, aspect_ratio: f32) {
+        drawing::write_view_data(gl, shader_program, aspect_ratio, self.zoom, drawing::PositionSpec { x: self.center_x, y: self.center_y });
+    }
+}
@@ -1,46 +1,140 @@
-
 use drawing::ColorSpec;
 use gameboard::gameboard::GameBoardSpaceType;
+use localization;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use PlayerColor;
 
 // This is like defining an interface.  We'll have enums implement this trait if there are colors associated with the enum value.
+// The colors come from the current palette, so that players who can't tell some colors apart can pick a palette that works for them.
 pub trait Color {
     fn color(&self) -> ColorSpec;
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Palette {
+    Standard,
+    // For red-green color blindness (deuteranopia and protanopia), which make the red and green players and the
+    // Forest and Plains spaces hard to tell apart.
+    Deuteranopia,
+    Protanopia,
+    // Colors far apart in brightness, for low vision.
+    HighContrast
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [Palette::Standard, Palette::Deuteranopia, Palette::Protanopia, Palette::HighContrast];
+
+    // Look up a palette by the name used in the settings file (e.g. "high_contrast").
+    pub fn from_name(name: &str) -> Option<Palette> {
+        Palette::ALL.iter().find(|palette| palette.settings_name() == name).cloned()
+    }
+
+    pub fn settings_name(&self) -> &'static str {
+        match self {
+            Palette::Standard => "standard",
+            Palette::Deuteranopia => "deuteranopia",
+            Palette::Protanopia => "protanopia",
+            Palette::HighContrast => "high_contrast"
+        }
+    }
+
+    pub fn name(&self) -> String {
+        localization::text(match self {
+            Palette::Standard => "palette_standard",
+            Palette::Deuteranopia => "palette_deuteranopia",
+            Palette::Protanopia => "palette_protanopia",
+            Palette::HighContrast => "palette_high_contrast"
+        })
+    }
+
+    pub fn next(&self) -> Palette {
+        let index = Palette::ALL.iter().position(|palette| palette == self).unwrap();
+        Palette::ALL[(index + 1) % Palette::ALL.len()]
+    }
+}
+
+static CURRENT_PALETTE: AtomicUsize = AtomicUsize::new(0);
+
+pub fn current_palette() -> Palette {
+    Palette::ALL[CURRENT_PALETTE.load(Ordering::Relaxed)]
+}
+
+// Everything drawn after this uses the new colors.  Anything that keeps colors around (the board mesh and the
+// piece images) checks current_palette() and updates itself.
+pub fn set_palette(palette: Palette) {
+    let index = Palette::ALL.iter().position(|&other_palette| other_palette == palette).unwrap();
+    CURRENT_PALETTE.store(index, Ordering::Relaxed);
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> ColorSpec {
+    ColorSpec { r: r, g: g, b: b }
+}
+
+// A lighter or darker shade of a color, for patterns and markers drawn on top of it.
+pub fn contrasting_shade(color: ColorSpec) -> ColorSpec {
+    let brightness = 0.299 * color.r as f32 + 0.587 * color.g as f32 + 0.114 * color.b as f32;
+    let shade = |channel: u8| {
+        if brightness > 128.0 {
+            (channel as f32 * 0.55) as u8
+        } else {
+            (channel as f32 + (255.0 - channel as f32) * 0.4) as u8
+        }
+    };
+    rgb(shade(color.r), shade(color.g), shade(color.b))
+}
+
 impl Color for GameBoardSpaceType
 {
     fn color(&self) -> ColorSpec {
-        match self {
-            GameBoardSpaceType::Void => ColorSpec {
-                r: 0x00,
-                g: 0x00,
-                b: 0x00
-            },
-            GameBoardSpaceType::Water => ColorSpec {
-                r: 0x20,
-                g: 0x20,
-                b: 0x80
+        match current_palette() {
+            Palette::Standard => match self {
+                GameBoardSpaceType::Void => ColorSpec {
+                    r: 0x00,
+                    g: 0x00,
+                    b: 0x00
+                },
+                GameBoardSpaceType::Water => ColorSpec {
+                    r: 0x20,
+                    g: 0x20,
+                    b: 0x80
+                },
+                GameBoardSpaceType::Mountain => ColorSpec {
+                    r: 0x40,
+                    g: 0x40,
+                    b: 0x40
+                },
+                GameBoardSpaceType::Forest => ColorSpec {
+                    r: 0x11,
+                    g: 0x46,
+                    b: 0x11,
+                },
+                GameBoardSpaceType::Plains => ColorSpec {
+                    r: 0x00,
+                    g: 0x80,
+                    b: 0x40
+                },
+                GameBoardSpaceType::Field => ColorSpec {
+                    r: 0x80,
+                    g: 0x70,
+                    b: 0x00
+                }
             },
-            GameBoardSpaceType::Mountain => ColorSpec {
-                r: 0x40,
-                g: 0x40,
-                b: 0x40
+            // Forest and Plains differ in brightness and in blue, which both kinds of red-green color blindness can see.
+            Palette::Deuteranopia | Palette::Protanopia => match self {
+                GameBoardSpaceType::Void => rgb(0x00, 0x00, 0x00),
+                GameBoardSpaceType::Water => rgb(0x22, 0x44, 0x88),
+                GameBoardSpaceType::Mountain => rgb(0x55, 0x55, 0x55),
+                GameBoardSpaceType::Forest => rgb(0x11, 0x33, 0x44),
+                GameBoardSpaceType::Plains => rgb(0x44, 0xAA, 0x99),
+                GameBoardSpaceType::Field => rgb(0xCC, 0xBB, 0x44)
             },
-            GameBoardSpaceType::Forest => ColorSpec {
-                r: 0x11,
-                g: 0x46,
-                b: 0x11,
-            },
-            GameBoardSpaceType::Plains => ColorSpec {
-                r: 0x00,
-                g: 0x80,
-                b: 0x40
-            },
-            GameBoardSpaceType::Field => ColorSpec {
-                r: 0x80,
-                g: 0x70,
-                b: 0x00
+            Palette::HighContrast => match self {
+                GameBoardSpaceType::Void => rgb(0x00, 0x00, 0x00),
+                GameBoardSpaceType::Water => rgb(0x00, 0x00, 0x99),
+                GameBoardSpaceType::Mountain => rgb(0x77, 0x77, 0x77),
+                GameBoardSpaceType::Forest => rgb(0x00, 0x33, 0x00),
+                GameBoardSpaceType::Plains => rgb(0x66, 0xCC, 0x66),
+                GameBoardSpaceType::Field => rgb(0xEE, 0xCC, 0x00)
             }
         }
     }
@@ -48,27 +142,81 @@ impl Color for GameBoardSpaceType
 
 impl Color for PlayerColor {
     fn color(&self) -> ColorSpec {
-        match self {
-            PlayerColor::Red => ColorSpec {
-                r: 0xC0,
-                g: 0x00,
-                b: 0x00
+        match current_palette() {
+            Palette::Standard => match self {
+                PlayerColor::Red => ColorSpec {
+                    r: 0xC0,
+                    g: 0x00,
+                    b: 0x00
+                },
+                PlayerColor::Blue => ColorSpec {
+                    r: 0x00,
+                    g: 0x00,
+                    b: 0xFF
+                },
+                PlayerColor::Green => ColorSpec {
+                    r: 0x00,
+                    g: 0xD0,
+                    b: 0x00
+                },
+                PlayerColor::Yellow => ColorSpec {
+                    r: 0xFF,
+                    g: 0xD7,
+                    b: 0x00,
+                }
             },
-            PlayerColor::Blue => ColorSpec {
-                r: 0x00,
-                g: 0x00,
-                b: 0xFF
+            // From the Okabe-Ito palette, which was chosen to be told apart with any kind of color blindness.
+            Palette::Deuteranopia => match self {
+                PlayerColor::Red => rgb(0xD5, 0x5E, 0x00),
+                PlayerColor::Blue => rgb(0x00, 0x72, 0xB2),
+                PlayerColor::Green => rgb(0x00, 0x9E, 0x73),
+                PlayerColor::Yellow => rgb(0xF0, 0xE4, 0x42)
             },
-            PlayerColor::Green => ColorSpec {
-                r: 0x00,
-                g: 0xD0,
-                b: 0x00
+            // Red looks dark with protanopia, so the red player is a brighter orange.
+            Palette::Protanopia => match self {
+                PlayerColor::Red => rgb(0xE6, 0x9F, 0x00),
+                PlayerColor::Blue => rgb(0x00, 0x72, 0xB2),
+                PlayerColor::Green => rgb(0x56, 0xB4, 0xE9),
+                PlayerColor::Yellow => rgb(0xF0, 0xE4, 0x42)
             },
-            PlayerColor::Yellow => ColorSpec {
-                r: 0xFF,
-                g: 0xD7,
-                b: 0x00,
+            Palette::HighContrast => match self {
+                PlayerColor::Red => rgb(0xFF, 0x33, 0x33),
+                PlayerColor::Blue => rgb(0x33, 0xCC, 0xFF),
+                PlayerColor::Green => rgb(0xAA, 0xFF, 0x00),
+                PlayerColor::Yellow => rgb(0xFF, 0xEE, 0x00)
             }
         }
     }
 }
+
+// Every player's pieces also carry a shape, so that they can be told apart without the colors.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Emblem {
+    Circle,
+    Square,
+    Triangle,
+    Diamond
+}
+
+impl Emblem {
+    // Whether a point is inside the emblem, for an emblem that fills the square from (-1, -1) to (1, 1), with y going up.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        match self {
+            Emblem::Circle => x * x + y * y <= 1.0,
+            Emblem::Square => x.abs() <= 0.8 && y.abs() <= 0.8,
+            Emblem::Triangle => y >= -0.8 && y <= 1.0 && x.abs() <= (1.0 - y) * 0.5 * 1.1,
+            Emblem::Diamond => x.abs() + y.abs() <= 1.0
+        }
+    }
+}
+
+impl PlayerColor {
+    pub fn emblem(&self) -> Emblem {
+        match self {
+            PlayerColor::Red => Emblem::Circle,
+            PlayerColor::Blue => Emblem::Square,
+            PlayerColor::Green => Emblem::Triangle,
+            PlayerColor::Yellow => Emblem::Diamond
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use gameboard::gameboard::{GameBoard,GameBoardSpacePos,GameBoardSpaceType};
+use localization;
+use PlayerColor;
+use std::fmt;
+
+// Resources are collected by settlements from the land around them, and spent on knights, buildings and noble titles.
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceType {
+    Stone,
+    Wood,
+    Grain,
+    Horses
+}
+
+impl ResourceType {
+    pub const ALL: [ResourceType; 4] = [ResourceType::Stone, ResourceType::Wood, ResourceType::Grain, ResourceType::Horses];
+
+    pub fn name(&self) -> String {
+        localization::text(match self {
+            ResourceType::Stone => "resource_stone",
+            ResourceType::Wood => "resource_wood",
+            ResourceType::Grain => "resource_grain",
+            ResourceType::Horses => "resource_horses"
+        })
+    }
+
+    // The resource that a space produces, if any.
+    pub fn produced_by(space_type: GameBoardSpaceType) -> Option<ResourceType> {
+        match space_type {
+            GameBoardSpaceType::Mountain => Some(ResourceType::Stone),
+            GameBoardSpaceType::Forest => Some(ResourceType::Wood),
+            GameBoardSpaceType::Field => Some(ResourceType::Grain),
+            GameBoardSpaceType::Plains => Some(ResourceType::Horses),
+            GameBoardSpaceType::Void | GameBoardSpaceType::Water => None
+        }
+    }
+}
+
+// An amount of each resource.  Used both for what a player has, and for what something costs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Resources {
+    pub stone: u32,
+    pub wood: u32,
+    pub grain: u32,
+    pub horses: u32
+}
+
+impl Resources {
+    pub fn none() -> Resources {
+        Resources { stone: 0, wood: 0, grain: 0, horses: 0 }
+    }
+
+    pub fn get(&self, resource_type: ResourceType) -> u32 {
+        match resource_type {
+            ResourceType::Stone => self.stone,
+            ResourceType::Wood => self.wood,
+            ResourceType::Grain => self.grain,
+            ResourceType::Horses => self.horses
+        }
+    }
+
+    fn get_mut(&mut self, resource_type: ResourceType) -> &mut u32 {
+        match resource_type {
+            ResourceType::Stone => &mut self.stone,
+            ResourceType::Wood => &mut self.wood,
+            ResourceType::Grain => &mut self.grain,
+            ResourceType::Horses => &mut self.horses
+        }
+    }
+
+    pub fn add(&mut self, resource_type: ResourceType, amount: u32) {
+        *self.get_mut(resource_type) += amount;
+    }
+
+    pub fn add_all(&mut self, other: &Resources) {
+        for resource_type in ResourceType::ALL.iter() {
+            self.add(*resource_type, other.get(*resource_type));
+        }
+    }
+
+    pub fn can_afford(&self, cost: &Resources) -> bool {
+        ResourceType::ALL.iter().all(|&resource_type| self.get(resource_type) >= cost.get(resource_type))
+    }
+
+    // Multiply a cost by a number of items.
+    pub fn times(&self, count: u32) -> Resources {
+        Resources {
+            stone: self.stone * count,
+            wood: self.wood * count,
+            grain: self.grain * count,
+            horses: self.horses * count
+        }
+    }
+
+    // Take away the cost, if there is enough of everything.  Returns false (and spends nothing) if there isn't.
+    // Whatever was being paid for must not happen then, so the result can't be ignored.
+    #[must_use]
+    pub fn spend(&mut self, cost: &Resources) -> bool {
+        if !self.can_afford(cost) {
+            return false;
+        }
+        for resource_type in ResourceType::ALL.iter() {
+            *self.get_mut(*resource_type) -= cost.get(*resource_type);
+        }
+        true
+    }
+
+    // Throw away anything over max of each resource.  Returns what was thrown away.
+    pub fn limit_to(&mut self, max: u32) -> Resources {
+        let mut discarded = Resources::none();
+        for resource_type in ResourceType::ALL.iter() {
+            let amount = self.get_mut(*resource_type);
+            if *amount > max {
+                discarded.add(*resource_type, *amount - max);
+                *amount = max;
+            }
+        }
+        discarded
+    }
+
+    // How many of something costing cost can be paid for.  Things that are free are limited to u32::MAX.
+    pub fn how_many_affordable(&self, cost: &Resources) -> u32 {
+        ResourceType::ALL.iter()
+            .filter(|&&resource_type| cost.get(resource_type) > 0)
+            .map(|&resource_type| self.get(resource_type) / cost.get(resource_type))
+            .min()
+            .unwrap_or(std::u32::MAX)
+    }
+}
+
+// Lists the nonzero amounts, e.g. "2 Wood, 1 Grain".
+impl fmt::Display for Resources {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let amounts: Vec<String> = ResourceType::ALL.iter()
+            .filter(|&&resource_type| self.get(resource_type) > 0)
+            .map(|&resource_type| format!("{} {}", self.get(resource_type), resource_type.name()))
+            .collect();
+        if amounts.is_empty() {
+            write!(f, "{}", localization::text("resources_nothing"))
+        } else {
+            write!(f, "{}", amounts.join(", "))
+        }
+    }
+}
+
+pub mod costs {
+    use economy::Resources;
+
+    pub const KNIGHT: Resources = Resources { stone: 0, wood: 0, grain: 1, horses: 1 };
+    pub const VILLAGE: Resources = Resources { stone: 0, wood: 2, grain: 1, horses: 0 };
+    pub const STRONGHOLD: Resources = Resources { stone: 3, wood: 1, grain: 0, horses: 0 };
+    pub const NOBLE_TITLE: Resources = Resources { stone: 2, wood: 2, grain: 2, horses: 2 };
+}
+
+// Every player starts the game with this much.
+pub const STARTING_RESOURCES: Resources = Resources { stone: 2, wood: 2, grain: 2, horses: 2 };
+
+// How much of each nearby resource a settlement collects every turn.
+const CITY_YIELD: u32 = 2;
+const VILLAGE_YIELD: u32 = 1;
+
+// Each settlement collects from its own space and the six spaces around it.
+fn settlement_production(game_board: &GameBoard, position: GameBoardSpacePos, settlement_yield: u32, production: &mut Resources) {
+    let mut spaces = position.all_neighboring_positions();
+    spaces.push(position);
+    for space in spaces {
+        if let Some(resource_type) = ResourceType::produced_by(game_board.get_board_space_type(space)) {
+            production.add(resource_type, settlement_yield);
+        }
+    }
+}
+
+// The resources that a player's settlements produce in one turn.
+pub fn production(game_board: &GameBoard, owner: PlayerColor) -> Resources {
+    let mut production = Resources::none();
+    for city in game_board.cities().filter(|city| city.owner == owner) {
+        settlement_production(game_board, city.position, CITY_YIELD, &mut production);
+    }
+    for village in game_board.villages().filter(|village| village.owner == owner) {
+        settlement_production(game_board, village.position, VILLAGE_YIELD, &mut production);
+    }
+    production
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use economy::costs;
+
+    #[test]
+    fn spend_only_what_can_be_afforded() {
+        let mut resources = Resources { stone: 2, wood: 2, grain: 2, horses: 2 };
+        assert!(resources.spend(&costs::NOBLE_TITLE));
+        assert_eq!(resources, Resources::none());
+
+        // Nothing is taken when even one resource is short.
+        let mut resources = Resources { stone: 3, wood: 0, grain: 5, horses: 0 };
+        assert!(!resources.spend(&costs::VILLAGE));
+        assert_eq!(resources, Resources { stone: 3, wood: 0, grain: 5, horses: 0 });
+        assert!(resources.spend(&Resources::none()));
+    }
+
+    #[test]
+    fn affordable_counts() {
+        let resources = Resources { stone: 7, wood: 3, grain: 4, horses: 1 };
+        assert_eq!(resources.how_many_affordable(&costs::KNIGHT), 1);
+        assert_eq!(resources.how_many_affordable(&costs::STRONGHOLD), 2);
+        assert_eq!(resources.how_many_affordable(&costs::KNIGHT.times(2)), 0);
+        assert_eq!(resources.how_many_affordable(&Resources::none()), std::u32::MAX);
+    }
+
+    #[test]
+    fn limit_to() {
+        let mut resources = Resources { stone: 7, wood: 3, grain: 4, horses: 1 };
+        assert_eq!(resources.limit_to(3), Resources { stone: 4, wood: 0, grain: 1, horses: 0 });
+        assert_eq!(resources, Resources { stone: 3, wood: 3, grain: 3, horses: 1 });
+    }
+
+    #[test]
+    fn settlements_collect_from_the_land_around_them() {
+        let mut game_board = GameBoard::new();
+        let city_pos = GameBoardSpacePos { x_pos: 4, y_pos: 3 };
+        game_board.set_board_space_type(city_pos, GameBoardSpaceType::Plains);
+        let neighbors = city_pos.all_neighboring_positions();
+        game_board.set_board_space_type(neighbors[0], GameBoardSpaceType::Mountain);
+        game_board.set_board_space_type(neighbors[1], GameBoardSpaceType::Water);
+        game_board.add_city(city_pos, PlayerColor::Red).unwrap();
+
+        assert_eq!(production(&game_board, PlayerColor::Red), Resources { stone: CITY_YIELD, wood: 0, grain: 0, horses: CITY_YIELD });
+        assert_eq!(production(&game_board, PlayerColor::Blue), Resources::none());
+    }
+}
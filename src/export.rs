@@ -0,0 +1,278 @@
+use colors::{self,Color,Emblem};
+use drawing::{ColorSpec,PositionSpec};
+use gameboard::gameboard::{GameBoard,GameBoardSpacePos,GameBoardSpaceType,all_game_board_positions,game_board_pos_to_drawing_pos,game_constants};
+use gameboard::gameboard_drawing::{drawing_constants,terrain_pattern_lines};
+use gameboard::gameboard_text::board_text;
+use gl;
+use nsvg;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use PlayerColor;
+
+// Pictures of the game, for sharing positions in issues and docs.
+//
+// Screenshots read back what was drawn in the window.  Board images are drawn from a GameBoard without any window:
+// the board is written out as an SVG, which nsvg can rasterize when a PNG is wanted.  nsvg doesn't draw text,
+// so the number of knights on a space is shown by drawing that many knights.
+
+// Pixels per drawing unit in exported board images.
+const EXPORT_SCALE: f32 = 500.0;
+
+// The most knights drawn side by side on one space.
+const MAX_KNIGHTS_DRAWN: usize = 5;
+
+fn svg_color(color: ColorSpec) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+// The board in SVG pixels, with y going down.
+struct SvgLayout {
+    width: f32,
+    height: f32
+}
+
+impl SvgLayout {
+    fn new() -> SvgLayout {
+        SvgLayout {
+            width: (drawing_constants::HEXAGON_X_SPACING * game_constants::MAX_BOARD_WIDTH as f32 + 0.25 * drawing_constants::HEXAGON_WIDTH) * EXPORT_SCALE,
+            height: (drawing_constants::HEXAGON_Y_SPACING * game_constants::MAX_BOARD_HEIGHT as f32 + 0.5 * drawing_constants::HEXAGON_HEIGHT) * EXPORT_SCALE
+        }
+    }
+
+    fn to_svg(&self, drawing_pos: PositionSpec) -> (f32, f32) {
+        ((drawing_pos.x - drawing_constants::GAME_BOARD_ORIGIN_X) * EXPORT_SCALE,
+         self.height - (drawing_pos.y - drawing_constants::GAME_BOARD_ORIGIN_Y) * EXPORT_SCALE)
+    }
+}
+
+fn svg_points(points: &[(f32, f32)]) -> String {
+    points.iter().map(|&(x, y)| format!("{:.1},{:.1}", x, y)).collect::<Vec<_>>().join(" ")
+}
+
+// The corners of an emblem of the given radius, or None for a circle.
+fn emblem_points(emblem: Emblem, x: f32, y: f32, radius: f32) -> Option<Vec<(f32, f32)>> {
+    match emblem {
+        Emblem::Circle => None,
+        Emblem::Square => Some(vec![(x - radius * 0.8, y - radius * 0.8), (x + radius * 0.8, y - radius * 0.8), (x + radius * 0.8, y + radius * 0.8), (x - radius * 0.8, y + radius * 0.8)]),
+        Emblem::Triangle => Some(vec![(x, y - radius), (x + radius, y + radius * 0.8), (x - radius, y + radius * 0.8)]),
+        Emblem::Diamond => Some(vec![(x, y - radius), (x + radius, y), (x, y + radius), (x - radius, y)])
+    }
+}
+
+// A building, as a house (or a tower, for a stronghold) in the owner's color.
+fn building_points(kind: &str, x: f32, y: f32) -> Vec<(f32, f32)> {
+    let size = drawing_constants::HEXAGON_WIDTH * EXPORT_SCALE;
+    let (half_width, wall_height, roof_height) = match kind {
+        "city" => (0.2 * size, 0.18 * size, 0.12 * size),
+        "stronghold" => (0.1 * size, 0.3 * size, 0.06 * size),
+        _ => (0.12 * size, 0.12 * size, 0.08 * size)
+    };
+    // Standing on a line a little below the middle of the space, to leave room for the knights below it.
+    let ground = y + 0.1 * size;
+    vec![(x - half_width, ground), (x + half_width, ground), (x + half_width, ground - wall_height),
+         (x, ground - wall_height - roof_height), (x - half_width, ground - wall_height)]
+}
+
+// An SVG picture of the board: the terrain (with its patterns), then the buildings, then the knights.
+pub fn board_svg(game_board: &GameBoard) -> String {
+    let layout = SvgLayout::new();
+    let outline = svg_color(GameBoardSpaceType::Void.color());
+    let mut svg = String::new();
+    let _ = writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"0 0 {:.0} {:.0}\">",
+        layout.width, layout.height, layout.width, layout.height);
+    let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", outline);
+
+    let hexagon_radius = drawing_constants::HEXAGON_WIDTH / 2.0 * EXPORT_SCALE;
+    for position in all_game_board_positions() {
+        let space_type = game_board.get_board_space_type(position);
+        if space_type == GameBoardSpaceType::Void {
+            continue;
+        }
+        let (x, y) = layout.to_svg(game_board_pos_to_drawing_pos(position));
+        // Points to the side, like the board in the game.
+        let corners: Vec<(f32, f32)> = (0..6).map(|corner| {
+            let angle = corner as f32 * ::std::f32::consts::PI / 3.0;
+            (x + hexagon_radius * angle.cos(), y + hexagon_radius * angle.sin())
+        }).collect();
+        let _ = writeln!(svg, "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"2\"/>", svg_points(&corners), svg_color(space_type.color()), outline);
+
+        let pattern_color = svg_color(colors::contrasting_shade(space_type.color()));
+        for ((x_from, y_from), (x_to, y_to)) in terrain_pattern_lines(space_type, position) {
+            let (svg_x_from, svg_y_from) = layout.to_svg(PositionSpec { x: x_from, y: y_from });
+            let (svg_x_to, svg_y_to) = layout.to_svg(PositionSpec { x: x_to, y: y_to });
+            let _ = writeln!(svg, "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"2\"/>",
+                svg_x_from, svg_y_from, svg_x_to, svg_y_to, pattern_color);
+        }
+    }
+
+    let buildings = game_board.cities().map(|unit| ("city", unit))
+        .chain(game_board.villages().map(|unit| ("village", unit)))
+        .chain(game_board.strongholds().map(|unit| ("stronghold", unit)));
+    for (kind, unit) in buildings {
+        let (x, y) = layout.to_svg(game_board_pos_to_drawing_pos(unit.position));
+        let _ = writeln!(svg, "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"2\"/>",
+            svg_points(&building_points(kind, x, y)), svg_color(unit.owner.color()), outline);
+    }
+
+    // The knights of each owner on each space, in the order they were added.
+    let mut knight_groups: Vec<(GameBoardSpacePos, PlayerColor, usize)> = Vec::new();
+    let mut group_indices: HashMap<_, usize> = HashMap::new();
+    for knight in game_board.knights() {
+        let key = (knight.position, knight.owner);
+        match group_indices.get(&key).cloned() {
+            Some(index) => { knight_groups[index].2 += 1; }
+            None => {
+                group_indices.insert(key, knight_groups.len());
+                knight_groups.push((knight.position, knight.owner, 1));
+            }
+        }
+    }
+    let knight_radius = 0.06 * drawing_constants::HEXAGON_WIDTH * EXPORT_SCALE;
+    let mut rows_used: HashMap<GameBoardSpacePos, usize> = HashMap::new();
+    for (position, owner, count) in knight_groups {
+        let (x, y) = layout.to_svg(game_board_pos_to_drawing_pos(position));
+        // Each owner on a space gets their own row, below the middle of the space.
+        let row = rows_used.entry(position).or_insert(0);
+        let row_y = y + 0.2 * drawing_constants::HEXAGON_WIDTH * EXPORT_SCALE + *row as f32 * knight_radius * 2.2 - knight_radius * 1.1;
+        *row += 1;
+        let drawn = count.min(MAX_KNIGHTS_DRAWN);
+        for i in 0..drawn {
+            let knight_x = x + (i as f32 - (drawn - 1) as f32 / 2.0) * knight_radius * 2.2;
+            match emblem_points(owner.emblem(), knight_x, row_y, knight_radius) {
+                Some(points) => {
+                    let _ = writeln!(svg, "<polygon points=\"{}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"1.5\"/>", svg_points(&points), svg_color(owner.color()), outline);
+                }
+                None => {
+                    let _ = writeln!(svg, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\" stroke=\"{}\" stroke-width=\"1.5\"/>", knight_x, row_y, knight_radius, svg_color(owner.color()), outline);
+                }
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Save a picture of the board, as an SVG, a PNG or text (see gameboard_text.rs) depending on the file extension.
+pub fn export_board(game_board: &GameBoard, path: &Path) -> Result<(), String> {
+    let svg = board_svg(game_board);
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("svg") => fs::write(path, svg).map_err(|e| e.to_string()),
+        Some("txt") => fs::write(path, board_text(game_board)).map_err(|e| e.to_string()),
+        Some("png") => {
+            let svg_image = nsvg::parse_str(&svg, nsvg::Units::Pixel, 96.0).map_err(|e| format!("{:?}", e))?;
+            let image = svg_image.rasterize(1.0).map_err(|e| format!("{:?}", e))?;
+            image.save(path).map_err(|e| e.to_string())
+        }
+        _ => Err("the file name must end in .svg, .png or .txt".to_string())
+    }
+}
+
+// Save what has been drawn in the window as a PNG.  Call this after drawing a frame and before swapping it onto the screen,
+// because what is left behind after the swap isn't defined.
+pub fn save_screenshot(gl: &gl::Gl, drawable_size: (u32, u32), path: &Path) -> Result<(), String> {
+    let (width, height) = drawable_size;
+    let mut pixels = vec![0_u8; width as usize * height as usize * 4];
+    unsafe {
+        gl.PixelStorei(gl::PACK_ALIGNMENT, 1);
+        gl.ReadPixels(0, 0, width as i32, height as i32, gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_mut_ptr() as *mut gl::types::GLvoid);
+    }
+    // The window has no use for alpha, so it can be anything.
+    for pixel in pixels.chunks_mut(4) {
+        pixel[3] = 255;
+    }
+    let image = nsvg::image::RgbaImage::from_raw(width, height, pixels).ok_or("the pixels don't fit the window size".to_string())?;
+    // OpenGL starts at the bottom row, and PNG starts at the top.
+    nsvg::image::imageops::flip_vertical(&image).save(path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    // Two spaces with a red city, two red knights and more blue knights than are drawn.
+    fn small_board() -> GameBoard {
+        let mut game_board = GameBoard::new();
+        game_board.set_board_space_type(pos(0, 0), GameBoardSpaceType::Plains);
+        game_board.set_board_space_type(pos(1, 0), GameBoardSpaceType::Forest);
+        game_board.add_city(pos(0, 0), PlayerColor::Red).unwrap();
+        for _ in 0..2 {
+            game_board.add_knight(pos(0, 0), PlayerColor::Red).unwrap();
+        }
+        for _ in 0..MAX_KNIGHTS_DRAWN + 2 {
+            game_board.add_knight(pos(1, 0), PlayerColor::Blue).unwrap();
+        }
+        game_board
+    }
+
+    fn temp_path(name: &str) -> ::std::path::PathBuf {
+        ::std::env::temp_dir().join(format!("fast-and-feudalist-{}-{}", process::id(), name))
+    }
+
+    #[test]
+    fn svg_shapes() {
+        let svg = board_svg(&small_board());
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.ends_with("</svg>\n"));
+        // Two spaces, one city and the blue knights (squares), which stop at MAX_KNIGHTS_DRAWN.
+        assert_eq!(svg.matches("<polygon ").count(), 2 + 1 + MAX_KNIGHTS_DRAWN);
+        // The red knights are circles.
+        assert_eq!(svg.matches("<circle ").count(), 2);
+        let pattern_lines = terrain_pattern_lines(GameBoardSpaceType::Plains, pos(0, 0)).len()
+            + terrain_pattern_lines(GameBoardSpaceType::Forest, pos(1, 0)).len();
+        assert_eq!(svg.matches("<line ").count(), pattern_lines);
+    }
+
+    #[test]
+    fn empty_board_svg() {
+        let svg = board_svg(&GameBoard::new());
+        assert_eq!(svg.matches("<polygon ").count(), 0);
+        assert_eq!(svg.matches("<circle ").count(), 0);
+    }
+
+    #[test]
+    fn png_export() {
+        let path = temp_path("board.png");
+        export_board(&small_board(), &path).unwrap();
+        let image = nsvg::image::open(&path).unwrap().to_rgba();
+        fs::remove_file(&path).unwrap();
+        let layout = SvgLayout::new();
+        assert_eq!(image.width(), layout.width.round() as u32);
+        assert_eq!(image.height(), layout.height.round() as u32);
+        // Something was drawn on top of the background.
+        let background = image.get_pixel(0, 0).clone();
+        assert!(image.pixels().any(|pixel| *pixel != background));
+    }
+
+    #[test]
+    fn svg_export() {
+        let game_board = small_board();
+        let svg_path = temp_path("board.svg");
+        export_board(&game_board, &svg_path).unwrap();
+        assert_eq!(fs::read_to_string(&svg_path).unwrap(), board_svg(&game_board));
+        fs::remove_file(&svg_path).unwrap();
+    }
+
+    #[test]
+    fn text_export() {
+        let game_board = small_board();
+        let text_path = temp_path("board.txt");
+        export_board(&game_board, &text_path).unwrap();
+        assert_eq!(fs::read_to_string(&text_path).unwrap(), board_text(&game_board));
+        fs::remove_file(&text_path).unwrap();
+    }
+
+    #[test]
+    fn unknown_extension() {
+        let path = temp_path("board.bmp");
+        assert!(export_board(&small_board(), &path).is_err());
+        assert!(!path.exists());
+    }
+}
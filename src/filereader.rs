@@ -51,6 +51,28 @@ impl FileReader {
 
         Ok(unsafe { ffi::CString::from_vec_unchecked(buffer) })
     }
+
+    pub fn load_string(&self, resource_name: &str) -> Result<String, Error> {
+        let mut file = fs::File::open(
+            resource_name_to_path(&self.root_path, resource_name)
+        )?;
+
+        let mut buffer = String::new();
+        file.read_to_string(&mut buffer)?;
+
+        Ok(buffer)
+    }
+
+    pub fn load_bytes(&self, resource_name: &str) -> Result<Vec<u8>, Error> {
+        let mut file = fs::File::open(
+            resource_name_to_path(&self.root_path, resource_name)
+        )?;
+
+        let mut buffer: Vec<u8> = Vec::new();
+        file.read_to_end(&mut buffer)?;
+
+        Ok(buffer)
+    }
 }
 
 fn resource_name_to_path(root_dir: &Path, location: &str) -> PathBuf {
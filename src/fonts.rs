@@ -1,22 +1,115 @@
-
+use filereader::FileReader;
 use freetype;
-use drawing;
-use std::path::Path;
+use text;
+
+// Fonts are loaded once, by name, from assets/fonts.
+//
+// The decorative Cardinal face is for titles, and a plain sans-serif face is for everything that has to be easy to read.
+// When a face doesn't have a character, the fallback faces are tried in order.
+
+pub const TITLE_FACE: &str = "Cardinal";
+pub const UI_FACE: &str = "Fira Sans";
+
+// (name, file in assets)
+const FONT_FILES: [(&str, &str); 3] = [
+    (TITLE_FACE, "fonts/Cardinal.ttf"),
+    (UI_FACE, "fonts/FiraSans-Regular.ttf"),
+    ("DejaVu Sans", "fonts/DejaVuSans.ttf")
+];
+
+const FALLBACK_FACES: [&str; 2] = ["DejaVu Sans", UI_FACE];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextStyle {
+    Banner,
+    Title,
+    Body,
+    Small
+}
+
+impl TextStyle {
+    pub fn face_name(&self) -> &'static str {
+        match self {
+            TextStyle::Banner | TextStyle::Title => TITLE_FACE,
+            TextStyle::Body | TextStyle::Small => UI_FACE
+        }
+    }
+
+    pub fn font_size(&self) -> u32 {
+        match self {
+            TextStyle::Banner => 48,
+            TextStyle::Title => 24,
+            TextStyle::Body => 18,
+            TextStyle::Small => 16
+        }
+    }
+}
+
+pub struct FontRegistry {
+    faces: Vec<(String, freetype::face::Face)>,
+    // Indices into faces.
+    fallback_faces: Vec<usize>
+}
+
+impl FontRegistry {
+    // The index of a face, for the text cache.
+    // Faces that couldn't be loaded (see FontResources::new) are replaced by the first face that was.
+    pub fn face_index(&self, name: &str) -> usize {
+        self.faces.iter().position(|&(ref face_name, _)| face_name == name).unwrap_or(0)
+    }
+
+    pub fn face(&self, index: usize) -> &freetype::face::Face {
+        &self.faces[index].1
+    }
+
+    // The face to render a character with: the face itself if it has the character, otherwise the first fallback that does.
+    // If none of them have it, the face itself draws its "missing character" glyph.
+    pub fn face_for_character(&self, face_index: usize, character: char) -> usize {
+        if character.is_whitespace() || self.face(face_index).get_char_index(character as usize) != 0 {
+            return face_index;
+        }
+        match self.fallback_faces.iter().find(|&&fallback_index| self.face(fallback_index).get_char_index(character as usize) != 0) {
+            Some(fallback_index) => *fallback_index,
+            None => face_index
+        }
+    }
+}
 
 pub struct FontResources {
     _freetype_lib: freetype::library::Library,
-    pub cardinal_font_face: freetype::face::Face,
-    pub text_cache: drawing::TextCache,
+    pub registry: FontRegistry,
+    pub text_cache: text::TextCache,
 }
 
 impl FontResources {
-    pub fn new() -> FontResources {
+    pub fn new(filereader: &FileReader) -> FontResources {
         let freetype_lib = freetype::Library::init().unwrap();
-        let cardinal_font_face = freetype_lib.new_face(Path::new("assets/fonts/Cardinal.ttf"), 0).unwrap();
+
+        let mut faces = Vec::new();
+        for &(name, resource_name) in FONT_FILES.iter() {
+            let face = filereader.load_bytes(resource_name)
+                .map_err(|error| format!("{:?}", error))
+                .and_then(|bytes| freetype_lib.new_memory_face(bytes, 0).map_err(|error| format!("{:?}", error)));
+            match face {
+                Ok(face) => { faces.push((name.to_string(), face)); }
+                Err(error) => { println!("Could not load the font {} from {}: {}", name, resource_name, error); }
+            }
+        }
+        if faces.is_empty() {
+            panic!("No fonts could be loaded");
+        }
+
+        let fallback_faces = FALLBACK_FACES.iter()
+            .filter_map(|fallback_name| faces.iter().position(|&(ref face_name, _)| face_name == fallback_name))
+            .collect();
+
         FontResources {
             _freetype_lib: freetype_lib,
-            cardinal_font_face: cardinal_font_face,
-            text_cache: drawing::TextCache::new()
+            registry: FontRegistry {
+                faces: faces,
+                fallback_faces: fallback_faces
+            },
+            text_cache: text::TextCache::new()
         }
     }
 }
@@ -0,0 +1,403 @@
+use gameboard::gameboard::{GameBoard,GameBoardSpacePos,GameBoardSpaceType,UnitInfo};
+use localization;
+use PlayerColor;
+use std::fmt;
+
+// Combat between knights.
+//
+// A battle breaks out on a space when a knight moves there and either
+// - there are now three or more knights on the space, or
+// - the space has another player's city, village or stronghold on it.
+// Two lone knights from different players can share a space without fighting.
+//
+// Each player on the space fights as one side.  A side's strength is its number of knights, plus any defence bonuses.
+// The strongest side wins, and every other side loses its knights and buildings on the space.
+// The losers' pieces go back to their owners' inventories.
+
+// What happens when the strongest sides are tied.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TieRule {
+    // Nobody among the tied sides loses anything.
+    Standoff,
+    // The player who moved in loses.  Any other tied sides stay where they are.
+    DefenderWins,
+    // Every side on the space loses.
+    MutualDestruction
+}
+
+#[derive(Clone, Copy)]
+pub struct CombatRules {
+    // Added to the strength of the owner of a stronghold on the space.
+    pub stronghold_defence_bonus: u32,
+    // Added to the strength of the owner of a city on the space.
+    pub city_defence_bonus: u32,
+    // Added to the strength of every side except the attacker, when fighting on a Mountain.
+    pub mountain_defence_bonus: u32,
+    // Whether knights can move onto another player's city.
+    pub allow_city_attacks: bool,
+    // Whether knights can move onto a Mountain where another player has knights.  Without this, only empty Mountains can be entered.
+    pub allow_mountain_attacks: bool,
+    pub tie_rule: TieRule
+}
+
+impl CombatRules {
+    pub fn defaults() -> CombatRules {
+        CombatRules {
+            stronghold_defence_bonus: 1,
+            city_defence_bonus: 1,
+            mountain_defence_bonus: 1,
+            allow_city_attacks: true,
+            allow_mountain_attacks: false,
+            tie_rule: TieRule::Standoff
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BuildingType {
+    City,
+    Village,
+    Stronghold
+}
+
+impl BuildingType {
+    pub fn name(&self) -> String {
+        localization::text(match self {
+            BuildingType::City => "piece_city",
+            BuildingType::Village => "piece_village",
+            BuildingType::Stronghold => "piece_stronghold"
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct BattleSide {
+    pub owner: PlayerColor,
+    pub num_knights: u32,
+    pub defence_bonus: u32
+}
+
+impl BattleSide {
+    pub fn strength(&self) -> u32 {
+        self.num_knights + self.defence_bonus
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum BattleOutcome {
+    Victory(PlayerColor),
+    Standoff,
+    // Nobody won outright, but the attacker lost.  The tied defenders are all still there.
+    AttackerRepelled,
+    MutualDestruction
+}
+
+// Everything that happened in one battle.
+#[derive(Clone)]
+pub struct BattleReport {
+    pub position: GameBoardSpacePos,
+    pub attacker: PlayerColor,
+    pub sides: Vec<BattleSide>,
+    pub outcome: BattleOutcome,
+    // Pieces removed from the board, which should be returned to their owners' inventories.
+    pub killed_knights: Vec<UnitInfo>,
+    pub destroyed_buildings: Vec<(BuildingType, UnitInfo)>,
+    // The attacker's knights went back where they came from, because the city they attacked is still standing.
+    pub attacker_fell_back: bool
+}
+
+impl fmt::Display for BattleReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sides: Vec<String> = self.sides.iter().map(|side| {
+            if side.defence_bonus > 0 {
+                format!("{} {}+{}", side.owner.name(), side.num_knights, side.defence_bonus)
+            } else {
+                format!("{} {}", side.owner.name(), side.num_knights)
+            }
+        }).collect();
+        write!(f, "{}  ", localization::format("battle_attacked", &[&self.attacker.name(), &self.position.x_pos, &self.position.y_pos, &sides.join(&format!(" {} ", localization::text("battle_versus")))]))?;
+
+        match self.outcome {
+            BattleOutcome::Victory(winner) => { write!(f, "{}", localization::format("battle_won", &[&winner.name()]))?; }
+            BattleOutcome::Standoff => { write!(f, "{}", localization::text("battle_standoff"))?; }
+            BattleOutcome::AttackerRepelled => { write!(f, "{}", localization::format("battle_attacker_repelled", &[&self.attacker.name()]))?; }
+            BattleOutcome::MutualDestruction => { write!(f, "{}", localization::text("battle_nobody_survived"))?; }
+        }
+
+        if !self.killed_knights.is_empty() {
+            write!(f, "{}", localization::format("battle_knights_killed", &[&self.killed_knights.len()]))?;
+        }
+        for &(building_type, ref building) in self.destroyed_buildings.iter() {
+            write!(f, "{}", localization::format("battle_building_destroyed", &[&building.owner.name(), &building_type.name()]))?;
+        }
+        if self.attacker_fell_back {
+            write!(f, "{}", localization::format("battle_attacker_fell_back", &[&self.attacker.name()]))?;
+        }
+        write!(f, ".")
+    }
+}
+
+impl GameBoard {
+    fn buildings_at_pos(&self, position: GameBoardSpacePos) -> Vec<(BuildingType, UnitInfo)> {
+        let cities = self.cities().filter(|city| city.position == position).map(|city| (BuildingType::City, city.clone()));
+        let villages = self.villages().filter(|village| village.position == position).map(|village| (BuildingType::Village, village.clone()));
+        let strongholds = self.strongholds().filter(|stronghold| stronghold.position == position).map(|stronghold| (BuildingType::Stronghold, stronghold.clone()));
+        cities.chain(villages).chain(strongholds).collect()
+    }
+
+    // Fight it out after attacker has moved a knight to position.
+    // Returns None if there was no battle.
+    pub fn resolve_combat(&mut self, position: GameBoardSpacePos, attacker: PlayerColor) -> Option<BattleReport> {
+        let rules = self.combat_rules();
+        let buildings = self.buildings_at_pos(position);
+        let num_knights = self.knights().filter(|knight| knight.position == position).count();
+        let attacked_building = buildings.iter().any(|&(_, ref building)| building.owner != attacker);
+
+        if num_knights < 3 && !attacked_building {
+            return None;
+        }
+
+        // One side for every player with knights or buildings here, in the order they got here.
+        let mut sides: Vec<BattleSide> = Vec::new();
+        {
+            let owners = self.knights().filter(|knight| knight.position == position).map(|knight| knight.owner)
+                .chain(buildings.iter().map(|&(_, ref building)| building.owner));
+            for owner in owners {
+                if !sides.iter().any(|side| side.owner == owner) {
+                    sides.push(BattleSide { owner: owner, num_knights: 0, defence_bonus: 0 });
+                }
+            }
+        }
+        if sides.len() < 2 {
+            return None;
+        }
+
+        let on_mountain = self.get_board_space_type(position) == GameBoardSpaceType::Mountain;
+        for side in sides.iter_mut() {
+            side.num_knights = self.knights().filter(|knight| knight.position == position && knight.owner == side.owner).count() as u32;
+            for &(building_type, ref building) in buildings.iter() {
+                if building.owner == side.owner {
+                    side.defence_bonus += match building_type {
+                        BuildingType::City => rules.city_defence_bonus,
+                        BuildingType::Stronghold => rules.stronghold_defence_bonus,
+                        BuildingType::Village => 0
+                    };
+                }
+            }
+            if on_mountain && side.owner != attacker {
+                side.defence_bonus += rules.mountain_defence_bonus;
+            }
+        }
+
+        // Work out who loses.
+        let top_strength = sides.iter().map(|side| side.strength()).max().unwrap();
+        let mut top_sides: Vec<PlayerColor> = sides.iter().filter(|side| side.strength() == top_strength).map(|side| side.owner).collect();
+        let outcome = if top_sides.len() == 1 {
+            BattleOutcome::Victory(top_sides[0])
+        } else {
+            match rules.tie_rule {
+                TieRule::Standoff => BattleOutcome::Standoff,
+                TieRule::DefenderWins => {
+                    top_sides.retain(|&owner| owner != attacker);
+                    if top_sides.len() == 1 { BattleOutcome::Victory(top_sides[0]) } else { BattleOutcome::AttackerRepelled }
+                }
+                TieRule::MutualDestruction => {
+                    top_sides.clear();
+                    BattleOutcome::MutualDestruction
+                }
+            }
+        };
+        let survivors = top_sides;
+
+        // Take the losers' pieces off the board.
+        let killed_knights: Vec<UnitInfo> = self.knights()
+            .filter(|knight| knight.position == position && !survivors.contains(&knight.owner))
+            .cloned()
+            .collect();
+        let destroyed_buildings: Vec<(BuildingType, UnitInfo)> = buildings.into_iter()
+            .filter(|&(_, ref building)| !survivors.contains(&building.owner))
+            .collect();
+        self.remove_pieces_at_pos(position, &survivors);
+
+        Some(BattleReport {
+            position: position,
+            attacker: attacker,
+            sides: sides,
+            outcome: outcome,
+            killed_knights: killed_knights,
+            destroyed_buildings: destroyed_buildings,
+            attacker_fell_back: false
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    // The left column of the board as plains, and nothing anywhere else.
+    fn column_board(tie_rule: TieRule) -> GameBoard {
+        let mut game_board = GameBoard::with_combat_rules(CombatRules { tie_rule: tie_rule, ..CombatRules::defaults() });
+        for y_pos in 0..7 {
+            game_board.set_board_space_type(pos(0, y_pos), GameBoardSpaceType::Plains);
+        }
+        game_board
+    }
+
+    fn add_knights(game_board: &mut GameBoard, position: GameBoardSpacePos, owner: PlayerColor, count: usize) {
+        for _ in 0..count {
+            game_board.add_knight(position, owner).unwrap();
+        }
+    }
+
+    fn knights_at(game_board: &GameBoard, position: GameBoardSpacePos, owner: PlayerColor) -> usize {
+        game_board.knights().filter(|knight| knight.position == position && knight.owner == owner).count()
+    }
+
+    // Two knights each for Blue and Red, after Red has moved in.
+    fn tied_battle(tie_rule: TieRule) -> (GameBoard, BattleReport) {
+        let mut game_board = column_board(tie_rule);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 2);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 2);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        (game_board, battle_report)
+    }
+
+    #[test]
+    fn no_battle_between_two_lone_knights() {
+        let mut game_board = column_board(TieRule::Standoff);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 1);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 1);
+        assert!(game_board.resolve_combat(pos(0, 1), PlayerColor::Red).is_none());
+        assert_eq!(game_board.num_knights(), 2);
+    }
+
+    #[test]
+    fn strongest_side_wins() {
+        let mut game_board = column_board(TieRule::Standoff);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 1);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 2);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        assert!(battle_report.outcome == BattleOutcome::Victory(PlayerColor::Red));
+        assert_eq!(battle_report.killed_knights.len(), 1);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Red), 2);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Blue), 0);
+    }
+
+    #[test]
+    fn tie_standoff() {
+        let (game_board, battle_report) = tied_battle(TieRule::Standoff);
+        assert!(battle_report.outcome == BattleOutcome::Standoff);
+        assert!(battle_report.killed_knights.is_empty());
+        assert_eq!(game_board.num_knights(), 4);
+    }
+
+    #[test]
+    fn tie_defender_wins() {
+        let (game_board, battle_report) = tied_battle(TieRule::DefenderWins);
+        assert!(battle_report.outcome == BattleOutcome::Victory(PlayerColor::Blue));
+        assert_eq!(battle_report.killed_knights.len(), 2);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Blue), 2);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Red), 0);
+    }
+
+    #[test]
+    fn tie_defender_wins_with_several_defenders() {
+        let mut game_board = column_board(TieRule::DefenderWins);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 1);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Green, 1);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 1);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        assert!(battle_report.outcome == BattleOutcome::AttackerRepelled);
+        assert_eq!(battle_report.killed_knights.len(), 1);
+        assert!(battle_report.killed_knights[0].owner == PlayerColor::Red);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Blue), 1);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Green), 1);
+    }
+
+    #[test]
+    fn tie_mutual_destruction() {
+        let (game_board, battle_report) = tied_battle(TieRule::MutualDestruction);
+        assert!(battle_report.outcome == BattleOutcome::MutualDestruction);
+        assert_eq!(battle_report.killed_knights.len(), 4);
+        assert_eq!(game_board.num_knights(), 0);
+    }
+
+    #[test]
+    fn city_defence_bonus() {
+        let mut game_board = column_board(TieRule::Standoff);
+        game_board.add_city(pos(0, 1), PlayerColor::Blue).unwrap();
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 1);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 2);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        assert_eq!(battle_report.sides.iter().find(|side| side.owner == PlayerColor::Blue).unwrap().strength(), 2);
+        assert!(battle_report.outcome == BattleOutcome::Standoff);
+
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 1);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        assert!(battle_report.outcome == BattleOutcome::Victory(PlayerColor::Red));
+        assert_eq!(battle_report.destroyed_buildings.len(), 1);
+        assert!(battle_report.destroyed_buildings[0].0 == BuildingType::City);
+        assert_eq!(game_board.num_cities(), 0);
+    }
+
+    #[test]
+    fn stronghold_defence_bonus() {
+        let mut game_board = column_board(TieRule::Standoff);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 1);
+        game_board.add_stronghold(pos(0, 1), PlayerColor::Blue).unwrap();
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 2);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        assert!(battle_report.outcome == BattleOutcome::Standoff);
+
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 1);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        assert!(battle_report.outcome == BattleOutcome::Victory(PlayerColor::Red));
+        assert!(battle_report.destroyed_buildings[0].0 == BuildingType::Stronghold);
+        assert_eq!(game_board.strongholds().count(), 0);
+    }
+
+    #[test]
+    fn mountain_defence_bonus() {
+        let mut game_board = column_board(TieRule::Standoff);
+        game_board.set_board_space_type(pos(0, 1), GameBoardSpaceType::Mountain);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 1);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 2);
+        let battle_report = game_board.resolve_combat(pos(0, 1), PlayerColor::Red).unwrap();
+        assert!(battle_report.outcome == BattleOutcome::Standoff);
+        // Only the defenders get the bonus.
+        assert_eq!(battle_report.sides.iter().find(|side| side.owner == PlayerColor::Red).unwrap().defence_bonus, 0);
+        assert_eq!(battle_report.sides.iter().find(|side| side.owner == PlayerColor::Blue).unwrap().defence_bonus, 1);
+    }
+
+    #[test]
+    fn lone_knight_falls_back_from_undefended_city() {
+        let mut game_board = column_board(TieRule::Standoff);
+        game_board.add_city(pos(0, 1), PlayerColor::Blue).unwrap();
+        add_knights(&mut game_board, pos(0, 0), PlayerColor::Red, 1);
+        let battle_report = game_board.move_knight(pos(0, 0), pos(0, 1), PlayerColor::Red).unwrap().unwrap();
+        assert!(battle_report.outcome == BattleOutcome::Standoff);
+        assert!(battle_report.attacker_fell_back);
+        assert_eq!(knights_at(&game_board, pos(0, 0), PlayerColor::Red), 1);
+        assert_eq!(game_board.num_cities(), 1);
+    }
+
+    #[test]
+    fn every_attacking_knight_falls_back() {
+        let mut game_board = column_board(TieRule::Standoff);
+        game_board.add_city(pos(0, 1), PlayerColor::Blue).unwrap();
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Blue, 1);
+        add_knights(&mut game_board, pos(0, 1), PlayerColor::Red, 1);
+        add_knights(&mut game_board, pos(0, 0), PlayerColor::Red, 1);
+        let battle_report = game_board.move_knight(pos(0, 0), pos(0, 1), PlayerColor::Red).unwrap().unwrap();
+        assert!(battle_report.outcome == BattleOutcome::Standoff);
+        assert!(battle_report.attacker_fell_back);
+        assert_eq!(knights_at(&game_board, pos(0, 0), PlayerColor::Red), 2);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Red), 0);
+        assert_eq!(knights_at(&game_board, pos(0, 1), PlayerColor::Blue), 1);
+    }
+}
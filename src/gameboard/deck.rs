@@ -0,0 +1,220 @@
+use filereader::{self, FileReader};
+use gameboard::gameboard::{all_board_piece_destinations,BoardPiece,BoardPieceOrientation,GameBoard,GameBoardSpaceType,game_constants};
+use rand;
+use rand::Rng;
+use std::cmp;
+
+#[derive(Debug)]
+pub enum Error {
+    ResourceLoad { name: String, inner: filereader::Error },
+    InvalidLine { name: String, line_number: usize, line: String },
+    Empty { name: String },
+    // A generated map kept running out of room before all of its pieces were down.
+    GenerationFailed { num_placed: usize, num_pieces: usize },
+}
+
+// The set of board pieces that the map is built from.
+#[derive(Clone)]
+pub struct BoardPieceDeck {
+    pieces: Vec<BoardPiece>
+}
+
+impl BoardPieceDeck {
+    // The 36 pieces from the original board game.
+    pub fn standard() -> BoardPieceDeck {
+        BoardPieceDeck { pieces: game_constants::BOARD_PIECES.to_vec() }
+    }
+
+    // Load a deck from a data file in the assets folder.
+    // Each non-empty line lists the three space types of one piece, in clockwise order, e.g. "Water Mountain Mountain".
+    // Anything after a '#' is a comment.
+    pub fn from_file(filereader: &FileReader, name: &str) -> Result<BoardPieceDeck, Error> {
+        let source = filereader.load_string(name).map_err(|e| Error::ResourceLoad {
+            name: name.into(),
+            inner: e
+        })?;
+
+        BoardPieceDeck::from_source(name, &source)
+    }
+
+    pub fn from_source(name: &str, source: &str) -> Result<BoardPieceDeck, Error> {
+        let mut pieces = Vec::new();
+
+        for (line_index, line) in source.lines().enumerate() {
+            let contents = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line
+            };
+            if contents.trim().is_empty() {
+                continue;
+            }
+
+            let space_types: Vec<Option<GameBoardSpaceType>> = contents.split_whitespace().map(GameBoardSpaceType::from_name).collect();
+            match space_types.as_slice() {
+                // Void spaces would leave holes in the map, so they aren't allowed in a piece.
+                &[Some(a), Some(b), Some(c)] if a != GameBoardSpaceType::Void && b != GameBoardSpaceType::Void && c != GameBoardSpaceType::Void => {
+                    pieces.push(BoardPiece { a: a, b: b, c: c });
+                }
+                _ => {
+                    return Err(Error::InvalidLine {
+                        name: name.into(),
+                        line_number: line_index + 1,
+                        line: line.into()
+                    });
+                }
+            }
+        }
+
+        if pieces.is_empty() {
+            return Err(Error::Empty { name: name.into() });
+        }
+
+        Ok(BoardPieceDeck { pieces: pieces })
+    }
+
+    pub fn len(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn pieces(&self) -> &[BoardPiece] {
+        &self.pieces
+    }
+}
+
+// Pick a piece at random, removing it from the pile.
+pub fn draw_random_board_piece(unplaced_board_pieces: &mut Vec<BoardPiece>) -> Option<BoardPiece> {
+    if unplaced_board_pieces.is_empty() {
+        None
+    } else {
+        let old_len = unplaced_board_pieces.len();
+        Some(unplaced_board_pieces.remove(rand::thread_rng().gen_range(0, old_len)))
+    }
+}
+
+pub fn random_orientation() -> BoardPieceOrientation {
+    match rand::thread_rng().gen_range(0, 3) {
+        0 => BoardPieceOrientation::A,
+        1 => BoardPieceOrientation::B,
+        _ => BoardPieceOrientation::C
+    }
+}
+
+// How many times generate_board starts over before giving up.
+const GENERATION_ATTEMPTS: usize = 20;
+
+// Build a whole map by laying down random pieces with random orientations.
+// Every piece after the first is placed next to the pieces that are already down, so the map is one landmass.
+// A random map can box itself in before all of the pieces are down, so it is started over until one fits.
+// The board and the pile are only changed if num_pieces pieces were placed.
+pub fn generate_board(game_board: &mut GameBoard, unplaced_board_pieces: &mut Vec<BoardPiece>, num_pieces: usize) -> Result<(), Error> {
+    let mut most_placed = 0;
+    for _ in 0..GENERATION_ATTEMPTS {
+        let mut attempt_board = game_board.clone();
+        let mut attempt_pieces = unplaced_board_pieces.clone();
+        let num_placed = place_random_pieces(&mut attempt_board, &mut attempt_pieces, num_pieces);
+        if num_placed == num_pieces {
+            *game_board = attempt_board;
+            *unplaced_board_pieces = attempt_pieces;
+            return Ok(());
+        }
+        most_placed = cmp::max(most_placed, num_placed);
+    }
+    Err(Error::GenerationFailed { num_placed: most_placed, num_pieces: num_pieces })
+}
+
+// One try at generate_board.  Returns the number of pieces that were placed, which can be less than num_pieces
+// if the pile runs out or there is nowhere left to put the next piece.
+fn place_random_pieces(game_board: &mut GameBoard, unplaced_board_pieces: &mut Vec<BoardPiece>, num_pieces: usize) -> usize {
+    let destinations = all_board_piece_destinations();
+    let mut num_placed = 0;
+
+    while num_placed < num_pieces {
+        let candidates: Vec<_> = destinations.iter().filter(|&&positions| {
+            if !game_board.space_ok_for_board_piece(positions) {
+                return false;
+            }
+            if num_placed == 0 {
+                // Start the map somewhere near the middle of the board.
+                let (position_a, _, _) = positions;
+                let center_x = (game_constants::MAX_BOARD_WIDTH / 2) as i32;
+                let center_y = (game_constants::MAX_BOARD_HEIGHT / 2) as i32;
+                (position_a.x_pos as i32 - center_x).abs() <= 1 && (position_a.y_pos as i32 - center_y).abs() <= 1
+            } else {
+                let (position_a, position_b, position_c) = positions;
+                [position_a, position_b, position_c].iter().any(|position| {
+                    position.all_neighboring_positions().iter().any(|&neighbor| game_board.get_board_space_type(neighbor) != GameBoardSpaceType::Void)
+                })
+            }
+        }).collect();
+
+        if candidates.is_empty() {
+            break;
+        }
+
+        let board_piece = match draw_random_board_piece(unplaced_board_pieces) {
+            Some(board_piece) => board_piece,
+            None => break
+        };
+
+        let positions = *candidates[rand::thread_rng().gen_range(0, candidates.len())];
+        if game_board.place_board_piece(positions, &board_piece, random_orientation()).is_err() {
+            break;
+        }
+        num_placed += 1;
+    }
+
+    num_placed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gameboard::gameboard::all_game_board_positions;
+
+    fn num_land_spaces(game_board: &GameBoard) -> usize {
+        all_game_board_positions().into_iter().filter(|&position| game_board.get_board_space_type(position) != GameBoardSpaceType::Void).count()
+    }
+
+    #[test]
+    fn generates_every_piece() {
+        let deck = BoardPieceDeck::standard();
+        for &num_pieces in [1, game_constants::BOARD_PIECES_PER_PLAYER, game_constants::BOARD_PIECES_PER_PLAYER * 2].iter() {
+            let mut game_board = GameBoard::new();
+            let mut unplaced_board_pieces = deck.pieces().to_vec();
+            generate_board(&mut game_board, &mut unplaced_board_pieces, num_pieces).unwrap();
+            assert_eq!(num_land_spaces(&game_board), num_pieces * 3);
+            assert_eq!(unplaced_board_pieces.len(), deck.len() - num_pieces);
+            assert_eq!(game_board.landmasses().len(), 1);
+        }
+    }
+
+    #[test]
+    fn failed_generation_changes_nothing() {
+        let deck = BoardPieceDeck::standard();
+        let mut game_board = GameBoard::new();
+        let mut unplaced_board_pieces = deck.pieces()[..4].to_vec();
+        match generate_board(&mut game_board, &mut unplaced_board_pieces, 5) {
+            Err(Error::GenerationFailed { num_placed, num_pieces }) => {
+                assert_eq!(num_placed, 4);
+                assert_eq!(num_pieces, 5);
+            }
+            _ => panic!("five pieces can't be placed from a pile of four")
+        }
+        assert_eq!(num_land_spaces(&game_board), 0);
+        assert_eq!(unplaced_board_pieces.len(), 4);
+    }
+
+    #[test]
+    fn deck_from_source() {
+        let deck = BoardPieceDeck::from_source("test", "# comment\nWater Mountain Forest\n\nPlains Field Field  # the last one\n").unwrap();
+        assert_eq!(deck.len(), 2);
+        let piece = &deck.pieces()[1];
+        assert!(piece.a == GameBoardSpaceType::Plains && piece.b == GameBoardSpaceType::Field && piece.c == GameBoardSpaceType::Field);
+        match BoardPieceDeck::from_source("test", "Water Mountain\n") {
+            Err(Error::InvalidLine { line_number, .. }) => { assert_eq!(line_number, 1); }
+            _ => panic!("a piece needs three spaces")
+        }
+        assert!(BoardPieceDeck::from_source("test", "Water Void Forest\n").is_err());
+        assert!(BoardPieceDeck::from_source("test", "# nothing\n").is_err());
+    }
+}
@@ -1,12 +1,16 @@
 
 use drawing;
 use drawing_constants;
-use std::collections::HashMap;
-use std::error;
+use gameboard::combat::{BattleOutcome,BattleReport,CombatRules};
+use gameboard::hex::{AxialPos,HexDirection};
+use gameboard::pathfinding;
+use localization;
+use rules::{BlockedReason,RuleError};
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use PlayerColor;
 
-#[derive(Copy,Clone,PartialEq)]
+#[derive(Copy,Clone,Debug,PartialEq)]
 pub enum GameBoardSpaceType
 {
     Void,
@@ -17,99 +21,95 @@ pub enum GameBoardSpaceType
     Field
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+impl GameBoardSpaceType {
+    // Look up a space type by the name used in data files (e.g. "Mountain").
+    pub fn from_name(name: &str) -> Option<GameBoardSpaceType> {
+        match name {
+            "Void" => Some(GameBoardSpaceType::Void),
+            "Water" => Some(GameBoardSpaceType::Water),
+            "Mountain" => Some(GameBoardSpaceType::Mountain),
+            "Forest" => Some(GameBoardSpaceType::Forest),
+            "Plains" => Some(GameBoardSpaceType::Plains),
+            "Field" => Some(GameBoardSpaceType::Field),
+            _ => None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameBoardSpaceType::Void => "Void",
+            GameBoardSpaceType::Water => "Water",
+            GameBoardSpaceType::Mountain => "Mountain",
+            GameBoardSpaceType::Forest => "Forest",
+            GameBoardSpaceType::Plains => "Plains",
+            GameBoardSpaceType::Field => "Field"
+        }
+    }
+
+    // The name to show the players, in their language.
+    pub fn display_name(&self) -> String {
+        localization::text(match self {
+            GameBoardSpaceType::Void => "space_void",
+            GameBoardSpaceType::Water => "space_water",
+            GameBoardSpaceType::Mountain => "space_mountain",
+            GameBoardSpaceType::Forest => "space_forest",
+            GameBoardSpaceType::Plains => "space_plains",
+            GameBoardSpaceType::Field => "space_field"
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct GameBoardSpacePos {
     pub x_pos: u8,
     pub y_pos: u8
 }
 
+impl fmt::Display for GameBoardSpacePos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x_pos, self.y_pos)
+    }
+}
+
 impl GameBoardSpacePos {
+    // Return the position of the space in the given direction from this space, or None if that is off the board.
+    pub fn step(&self, direction: HexDirection) -> Option<GameBoardSpacePos> {
+        AxialPos::from_offset(*self).neighbor(direction).to_offset()
+    }
+
     // Return the position of the space which is above this space.
     pub fn up(&self) -> Option<GameBoardSpacePos> {
-        let next_y = self.y_pos + 1;
-        if next_y < game_constants::MAX_BOARD_HEIGHT as u8 {
-            Some(GameBoardSpacePos {
-                x_pos: self.x_pos,
-                y_pos: next_y})
-        } else {
-            None
-        }
+        self.step(HexDirection::Up)
     }
 
     // Return the position of the space which is up and to the right of this space.
     pub fn up_right(&self) -> Option<GameBoardSpacePos> {
-        let next_x = self.x_pos + 1;
-        let next_y = if self.x_pos % 2 == 1 {self.y_pos + 1} else {self.y_pos};
-        if next_x < game_constants::MAX_BOARD_WIDTH as u8 && next_y < game_constants::MAX_BOARD_HEIGHT as u8 {
-            Some(GameBoardSpacePos {
-                x_pos: next_x,
-                y_pos: next_y})
-        } else {
-            None
-        }
+        self.step(HexDirection::UpRight)
     }
 
     // Return the position of the space which is down and to the right of this space.
     pub fn down_right(&self) -> Option<GameBoardSpacePos> {
-        let next_x = self.x_pos + 1;
-        let next_y = if self.x_pos % 2 == 1 {self.y_pos as i8} else {self.y_pos as i8 - 1};
-        if next_x < game_constants::MAX_BOARD_WIDTH as u8 && next_y >= 0 {
-            Some(GameBoardSpacePos {
-                x_pos: next_x,
-                y_pos: next_y as u8})
-        } else {
-            None
-        }
+        self.step(HexDirection::DownRight)
     }
 
     // Return the position of the space which is below this space.
     pub fn down(&self) -> Option<GameBoardSpacePos> {
-        let next_y = self.y_pos as i8 - 1;
-        if next_y >= 0 {
-            Some(GameBoardSpacePos {
-                x_pos: self.x_pos,
-                y_pos: next_y as u8})
-        } else {
-            None
-        }
+        self.step(HexDirection::Down)
     }
 
     // Return the position of the space which is down and to the left of this space.
     pub fn down_left(&self) -> Option<GameBoardSpacePos> {
-        let next_x = self.x_pos as i8 - 1;
-        let next_y = if self.x_pos % 2 == 1 {self.y_pos as i8} else {self.y_pos as i8 - 1};
-        if next_x >= 0 && next_y >= 0 {
-            Some(GameBoardSpacePos {
-                x_pos: next_x as u8,
-                y_pos: next_y as u8})
-        } else {
-            None
-        }
+        self.step(HexDirection::DownLeft)
     }
 
     // Return the position of the space which is up and to the left of this space.
     pub fn up_left(&self) -> Option<GameBoardSpacePos> {
-        let next_x = self.x_pos as i8 - 1;
-        let next_y = if self.x_pos % 2 == 1 {self.y_pos + 1} else {self.y_pos};
-        if next_x >= 0 && next_y < game_constants::MAX_BOARD_HEIGHT as u8 {
-            Some(GameBoardSpacePos {
-                x_pos: next_x as u8,
-                y_pos: next_y})
-        } else {
-            None
-        }
+        self.step(HexDirection::UpLeft)
     }
 
     // Return a vector of all neighboring positions.
     pub fn all_neighboring_positions(&self) -> Vec<GameBoardSpacePos> {
-        let mut ret_val = Vec::<GameBoardSpacePos>::new();
-        if let Some(game_board_pos) = self.up()         { ret_val.push(game_board_pos) }
-        if let Some(game_board_pos) = self.up_right()   { ret_val.push(game_board_pos) }
-        if let Some(game_board_pos) = self.down_right() { ret_val.push(game_board_pos) }
-        if let Some(game_board_pos) = self.down()       { ret_val.push(game_board_pos) }
-        if let Some(game_board_pos) = self.down_left()  { ret_val.push(game_board_pos) }
-        if let Some(game_board_pos) = self.up_left()    { ret_val.push(game_board_pos) }
-        ret_val
+        HexDirection::ALL.iter().filter_map(|&direction| self.step(direction)).collect()
     }
 
     pub fn is_neighbor(&self, other_pos: GameBoardSpacePos) -> bool {
@@ -147,6 +147,77 @@ pub struct BoardPiece {
     pub c: GameBoardSpaceType
 }
 
+// Which of the piece's spaces lands on the first of the three destination spaces.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BoardPieceOrientation {
+    A,
+    B,
+    C
+}
+
+impl BoardPieceOrientation {
+    // Turn the piece one step clockwise.
+    pub fn next(&self) -> BoardPieceOrientation {
+        match self {
+            BoardPieceOrientation::A => BoardPieceOrientation::C,
+            BoardPieceOrientation::B => BoardPieceOrientation::A,
+            BoardPieceOrientation::C => BoardPieceOrientation::B
+        }
+    }
+
+    // Turn the piece one step counter-clockwise.
+    pub fn previous(&self) -> BoardPieceOrientation {
+        match self {
+            BoardPieceOrientation::A => BoardPieceOrientation::B,
+            BoardPieceOrientation::B => BoardPieceOrientation::C,
+            BoardPieceOrientation::C => BoardPieceOrientation::A
+        }
+    }
+}
+
+impl BoardPiece {
+    // Return the space types of this piece in clockwise order, rotated to the given orientation.
+    pub fn oriented(&self, orientation: BoardPieceOrientation) -> (GameBoardSpaceType, GameBoardSpaceType, GameBoardSpaceType) {
+        match orientation {
+            BoardPieceOrientation::A => (self.a, self.b, self.c),
+            BoardPieceOrientation::B => (self.b, self.c, self.a),
+            BoardPieceOrientation::C => (self.c, self.a, self.b)
+        }
+    }
+}
+
+pub fn all_game_board_positions() -> Vec<GameBoardSpacePos> {
+    let mut ret_val = Vec::new();
+    for x in 0..game_constants::MAX_BOARD_WIDTH {
+        for y in 0..game_constants::MAX_BOARD_HEIGHT {
+            ret_val.push(GameBoardSpacePos { x_pos: x as u8, y_pos: y as u8 });
+        }
+    }
+    ret_val
+}
+
+// Return every group of three spaces that a board piece could be dropped on, whether or not the spaces are empty.
+// The positions in each group are in clockwise order, the same as the spaces of a BoardPiece.
+pub fn all_board_piece_destinations() -> Vec<(GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)> {
+    let mut ret_val = Vec::new();
+    for x in 0..game_constants::MAX_BOARD_WIDTH {
+        for y in 0..game_constants::MAX_BOARD_HEIGHT {
+            let position = GameBoardSpacePos { x_pos: x as u8, y_pos: y as u8 };
+
+            // two spaces on the left, one on the right
+            if let (Some(upper_left_pos), Some(right_pos)) = (position.up(), position.up_right()) {
+                ret_val.push((position, upper_left_pos, right_pos));
+            }
+
+            // one space on the left, two on the right
+            if let (Some(upper_right_pos), Some(lower_right_pos)) = (position.up_right(), position.down_right()) {
+                ret_val.push((position, upper_right_pos, lower_right_pos));
+            }
+        }
+    }
+    ret_val
+}
+
 pub mod game_constants {
     use GameBoardSpaceType;
     use BoardPiece;
@@ -195,6 +266,9 @@ pub mod game_constants {
         BoardPiece { a: GameBoardSpaceType::Field, b: GameBoardSpaceType::Forest, c: GameBoardSpaceType::Water },
     ];
 
+    // Number of board pieces each player lays down during board setup.
+    pub const BOARD_PIECES_PER_PLAYER: usize = 9;
+
     pub const MAX_BOARD_HEIGHT: usize = 7;
     pub const MAX_BOARD_WIDTH: usize = 13;
 }
@@ -206,40 +280,103 @@ pub struct UnitInfo {
     pub owner: PlayerColor
 }
 
-#[derive(Debug)]
-pub struct KnightMoveError;
-impl fmt::Display for KnightMoveError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Invalid knight movement attempted")
-    }
-}
-impl error::Error for KnightMoveError {
-    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        None
-    }
+// Every change to the terrain of any board gets a new number, so that drawing code can tell when its copy of the terrain is out of date.
+static NEXT_TERRAIN_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+fn new_terrain_generation() -> usize {
+    NEXT_TERRAIN_GENERATION.fetch_add(1, Ordering::Relaxed)
 }
 
+#[derive(Clone)]
 pub struct GameBoard {
     board_state: [[GameBoardSpaceType; game_constants::MAX_BOARD_WIDTH]; game_constants::MAX_BOARD_HEIGHT],
+    terrain_generation: usize,
     cities: std::vec::Vec<UnitInfo>,
-    knights: std::vec::Vec<UnitInfo>
+    villages: std::vec::Vec<UnitInfo>,
+    strongholds: std::vec::Vec<UnitInfo>,
+    knights: std::vec::Vec<UnitInfo>,
+    combat_rules: CombatRules
 }
 
 impl GameBoard {
     pub fn new() -> GameBoard {
+        GameBoard::with_combat_rules(CombatRules::defaults())
+    }
+
+    pub fn with_combat_rules(combat_rules: CombatRules) -> GameBoard {
         GameBoard {
             board_state: [[GameBoardSpaceType::Void; game_constants::MAX_BOARD_WIDTH]; game_constants::MAX_BOARD_HEIGHT],
+            terrain_generation: new_terrain_generation(),
             cities: std::vec::Vec::<UnitInfo>::new(),
-            knights: std::vec::Vec::<UnitInfo>::new()
+            villages: std::vec::Vec::<UnitInfo>::new(),
+            strongholds: std::vec::Vec::<UnitInfo>::new(),
+            knights: std::vec::Vec::<UnitInfo>::new(),
+            combat_rules: combat_rules
         }
     }
 
+    pub fn combat_rules(&self) -> CombatRules {
+        self.combat_rules
+    }
+
     pub fn get_board_space_type(&self, position: GameBoardSpacePos) -> GameBoardSpaceType {
         self.board_state[position.y_pos as usize][position.x_pos as usize]
     }
 
     pub fn set_board_space_type(&mut self, position: GameBoardSpacePos, space_type: GameBoardSpaceType) {
         self.board_state[position.y_pos as usize][position.x_pos as usize] = space_type;
+        self.terrain_generation = new_terrain_generation();
+    }
+
+    // Changes whenever the terrain changes.
+    pub fn terrain_generation(&self) -> usize {
+        self.terrain_generation
+    }
+
+    pub fn check_board_piece_placement(&self, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)) -> Result<(), RuleError> {
+        let (position_a, position_b, position_c) = positions;
+        if self.get_board_space_type(position_a) == GameBoardSpaceType::Void &&
+           self.get_board_space_type(position_b) == GameBoardSpaceType::Void &&
+           self.get_board_space_type(position_c) == GameBoardSpaceType::Void {
+            Ok(())
+        } else {
+            Err(RuleError::DestinationBlocked { reason: BlockedReason::AlreadyOnMap })
+        }
+    }
+
+    pub fn space_ok_for_board_piece(&self, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)) -> bool {
+        self.check_board_piece_placement(positions).is_ok()
+    }
+
+    pub fn place_board_piece(&mut self, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos), board_piece: &BoardPiece, orientation: BoardPieceOrientation) -> Result<(), RuleError> {
+        self.check_board_piece_placement(positions)?;
+        let (position_a, position_b, position_c) = positions;
+        let (space_type_a, space_type_b, space_type_c) = board_piece.oriented(orientation);
+        self.set_board_space_type(position_a, space_type_a);
+        self.set_board_space_type(position_b, space_type_b);
+        self.set_board_space_type(position_c, space_type_c);
+        Ok(())
+    }
+
+    // Returns true if any of the positions borders a space that already has a board piece on it.
+    pub fn board_piece_touches_land(&self, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)) -> bool {
+        let (position_a, position_b, position_c) = positions;
+        [position_a, position_b, position_c].iter().any(|position| {
+            position.all_neighboring_positions().iter().any(|&neighbor| self.get_board_space_type(neighbor) != GameBoardSpaceType::Void)
+        })
+    }
+
+    // Returns each separate group of connected spaces that have a board piece on them (water counts as part of the map).
+    pub fn landmasses(&self) -> std::vec::Vec<std::vec::Vec<GameBoardSpacePos>> {
+        let mut land_positions = std::vec::Vec::new();
+        for x in 0..game_constants::MAX_BOARD_WIDTH {
+            for y in 0..game_constants::MAX_BOARD_HEIGHT {
+                if self.board_state[y][x] != GameBoardSpaceType::Void {
+                    land_positions.push(GameBoardSpacePos { x_pos: x as u8, y_pos: y as u8 });
+                }
+            }
+        }
+        pathfinding::connected_regions(&land_positions)
     }
 
     pub fn cities(&self) -> std::slice::Iter<UnitInfo> {
@@ -250,34 +387,91 @@ impl GameBoard {
         self.cities.len()
     }
 
-    pub fn add_city(&mut self, position: GameBoardSpacePos, owner: PlayerColor) {
+    pub fn add_city(&mut self, position: GameBoardSpacePos, owner: PlayerColor) -> Result<(), RuleError> {
+        self.check_city_placement(position)?;
         self.cities.push(UnitInfo{ position: position, owner: owner });
+        Ok(())
     }
 
-    pub fn space_ok_for_city(&self, position: GameBoardSpacePos) -> bool {
+    pub fn check_city_placement(&self, position: GameBoardSpacePos) -> Result<(), RuleError> {
         match self.get_board_space_type(position) {
-            GameBoardSpaceType::Void | GameBoardSpaceType::Water | GameBoardSpaceType::Forest => {
-                false
+            GameBoardSpaceType::Void => {
+                Err(RuleError::DestinationBlocked { reason: BlockedReason::NotOnMap })
+            }
+            GameBoardSpaceType::Water => {
+                Err(RuleError::DestinationBlocked { reason: BlockedReason::Water })
+            }
+            GameBoardSpaceType::Forest => {
+                Err(RuleError::DestinationBlocked { reason: BlockedReason::WrongTerrain(GameBoardSpaceType::Forest) })
             }
             _ => {
                 for city in self.cities() {
                     if city.position == position {
-                        return false;
+                        return Err(RuleError::DestinationBlocked { reason: BlockedReason::AlreadyBuiltOn });
                     }
                 }
                 let neighbor_positions = [position.up(), position.down(), position.up_right(), position.up_left(), position.down_right(), position.down_left()];
                 for position in &neighbor_positions {
                     for city in self.cities() {
                         if position.is_some() && city.position == position.unwrap() {
-                            return false;
+                            return Err(RuleError::DestinationBlocked { reason: BlockedReason::TooCloseToCity });
                         }
                     }
                 }
-                true
+                Ok(())
             }
         }
     }
 
+    pub fn space_ok_for_city(&self, position: GameBoardSpacePos) -> bool {
+        self.check_city_placement(position).is_ok()
+    }
+
+    pub fn villages(&self) -> std::slice::Iter<UnitInfo> {
+        self.villages.iter()
+    }
+
+    pub fn add_village(&mut self, position: GameBoardSpacePos, owner: PlayerColor) -> Result<(), RuleError> {
+        self.check_building_placement(position, owner)?;
+        self.villages.push(UnitInfo{ position: position, owner: owner });
+        Ok(())
+    }
+
+    pub fn strongholds(&self) -> std::slice::Iter<UnitInfo> {
+        self.strongholds.iter()
+    }
+
+    pub fn add_stronghold(&mut self, position: GameBoardSpacePos, owner: PlayerColor) -> Result<(), RuleError> {
+        self.check_building_placement(position, owner)?;
+        self.strongholds.push(UnitInfo{ position: position, owner: owner });
+        Ok(())
+    }
+
+    // A village or stronghold can be built on a land space where the owner has a knight, if nothing has been built there yet.
+    pub fn check_building_placement(&self, position: GameBoardSpacePos, owner: PlayerColor) -> Result<(), RuleError> {
+        match self.get_board_space_type(position) {
+            GameBoardSpaceType::Void => {
+                Err(RuleError::DestinationBlocked { reason: BlockedReason::NotOnMap })
+            }
+            GameBoardSpaceType::Water => {
+                Err(RuleError::DestinationBlocked { reason: BlockedReason::Water })
+            }
+            _ => {
+                if !self.knights().any(|knight| knight.position == position && knight.owner == owner) {
+                    return Err(RuleError::DestinationBlocked { reason: BlockedReason::NoKnightThere });
+                }
+                if self.cities().chain(self.villages()).chain(self.strongholds()).any(|building| building.position == position) {
+                    return Err(RuleError::DestinationBlocked { reason: BlockedReason::AlreadyBuiltOn });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn space_ok_for_building(&self, position: GameBoardSpacePos, owner: PlayerColor) -> bool {
+        self.check_building_placement(position, owner).is_ok()
+    }
+
     pub fn knights(&self) -> std::slice::Iter<UnitInfo> {
         self.knights.iter()
     }
@@ -286,32 +480,62 @@ impl GameBoard {
         self.knights.len()
     }
 
-    pub fn add_knight(&mut self, position: GameBoardSpacePos, owner: PlayerColor) {
+    // New knights can go on any land space.  Knights that arrive this way don't start a battle.
+    pub fn add_knight(&mut self, position: GameBoardSpacePos, owner: PlayerColor) -> Result<(), RuleError> {
+        self.check_knight_placement(position)?;
         self.knights.push(UnitInfo{ position: position, owner: owner });
+        Ok(())
     }
 
-    // Returns any knights that were "killed" as part of the move (should be returned to player's inventory).
-    pub fn move_knight(&mut self, from_pos: GameBoardSpacePos, to_pos: GameBoardSpacePos, owner: PlayerColor) -> Result<std::vec::Vec<UnitInfo>, KnightMoveError> {
-        // Return KnightMoveError if there isn't a knight at from_pos, or if the to_pos is not ok to move a knight into.
+    pub fn check_knight_placement(&self, position: GameBoardSpacePos) -> Result<(), RuleError> {
+        match self.get_board_space_type(position) {
+            GameBoardSpaceType::Void => { Err(RuleError::DestinationBlocked { reason: BlockedReason::NotOnMap }) }
+            GameBoardSpaceType::Water => { Err(RuleError::DestinationBlocked { reason: BlockedReason::Water }) }
+            _ => { Ok(()) }
+        }
+    }
+
+    // Take one of the owner's knights off the board.  Returns the knight that was removed.
+    pub fn disband_knight(&mut self, owner: PlayerColor) -> Result<UnitInfo, RuleError> {
+        match self.knights.iter().rposition(|knight| knight.owner == owner) {
+            Some(knight_index) => Ok(self.knights.remove(knight_index)),
+            None => Err(RuleError::NoKnightAtSource)
+        }
+    }
+
+    // Returns a report of the battle, if moving the knight started one (see combat.rs).
+    pub fn move_knight(&mut self, from_pos: GameBoardSpacePos, to_pos: GameBoardSpacePos, owner: PlayerColor) -> Result<Option<BattleReport>, RuleError> {
         // simply iterating over the vector knights... Think about tracking the knights at each position a different way.
         if let Some(knight_index) = self.knights.iter().position(|ref knight| knight.position == from_pos && knight.owner == owner) {
-            if self.space_ok_for_knight(to_pos, owner) {
-                // reassign position
-                self.knights[knight_index].position = to_pos;
-                Ok(self.resolve_coexistence(to_pos))
+            if !to_pos.is_neighbor(from_pos) {
+                return Err(RuleError::DestinationBlocked { reason: BlockedReason::NotNextToKnight });
             }
-            else {
-                Err(KnightMoveError{})
+            self.check_knight_destination(to_pos, owner)?;
+            // reassign position
+            self.knights[knight_index].position = to_pos;
+            let mut battle_report = self.resolve_combat(to_pos, owner);
+            if let Some(ref mut battle_report) = battle_report {
+                // Knights can't share a space with another player's city, so after a standoff the attackers go back.
+                if battle_report.outcome == BattleOutcome::Standoff && self.cities.iter().any(|city| city.position == to_pos && city.owner != owner) {
+                    for knight in self.knights.iter_mut().filter(|knight| knight.position == to_pos && knight.owner == owner) {
+                        knight.position = from_pos;
+                    }
+                    battle_report.attacker_fell_back = true;
+                }
             }
+            Ok(battle_report)
+        }
+        else if let Some(other_knight) = self.knights.iter().find(|ref knight| knight.position == from_pos) {
+            Err(RuleError::NotYourKnight { owner: other_knight.owner })
         }
         else {
-            Err(KnightMoveError{})
+            Err(RuleError::NoKnightAtSource)
         }
     }
 
-    pub fn space_ok_for_knight(&self, position: GameBoardSpacePos, owner: PlayerColor) -> bool {
-        if self.cities.iter().any(|ref city| city.position == position && city.owner != owner) {
-            return false;
+    pub fn check_knight_destination(&self, position: GameBoardSpacePos, owner: PlayerColor) -> Result<(), RuleError> {
+        if !self.combat_rules.allow_city_attacks && self.cities.iter().any(|ref city| city.position == position && city.owner != owner) {
+            return Err(RuleError::DestinationBlocked { reason: BlockedReason::EnemyCity });
         }
 
         let opposing_unit_count = self.opposing_unit_count_at_pos(position, owner);
@@ -319,45 +543,99 @@ impl GameBoard {
 
         use GameBoardSpaceType::*;
         match space_type {
-            Void | Water => {false}
-            Mountain => { opposing_unit_count == 0 }
-            Forest | Plains | Field => { opposing_unit_count < 2 }
+            Void => { Err(RuleError::DestinationBlocked { reason: BlockedReason::NotOnMap }) }
+            Water => { Err(RuleError::DestinationBlocked { reason: BlockedReason::Water }) }
+            Mountain if !self.combat_rules.allow_mountain_attacks => {
+                if opposing_unit_count == 0 {
+                    Ok(())
+                } else {
+                    Err(RuleError::DestinationBlocked { reason: BlockedReason::TooManyOpposingKnights })
+                }
+            }
+            Mountain | Forest | Plains | Field => {
+                if opposing_unit_count < 2 {
+                    Ok(())
+                } else {
+                    Err(RuleError::DestinationBlocked { reason: BlockedReason::TooManyOpposingKnights })
+                }
+            }
         }
     }
 
-    // Returns any knights that were "killed" as part of the move (should be returned to player's inventory).
-    pub fn resolve_coexistence(&mut self, position: GameBoardSpacePos) -> std::vec::Vec<UnitInfo> {
-        let units_at_pos: std::vec::Vec<UnitInfo> = self.knights.iter().filter(|ref knight| knight.position == position).map(|x| x.clone()).collect();
+    pub fn space_ok_for_knight(&self, position: GameBoardSpacePos, owner: PlayerColor) -> bool {
+        self.check_knight_destination(position, owner).is_ok()
+    }
+
+    // Take every knight and building off this space, except for the ones that belong to the survivors.
+    pub fn remove_pieces_at_pos(&mut self, position: GameBoardSpacePos, survivors: &[PlayerColor]) {
+        let keep = |unit: &UnitInfo| unit.position != position || survivors.contains(&unit.owner);
+        self.knights.retain(&keep);
+        self.cities.retain(&keep);
+        self.villages.retain(&keep);
+        self.strongholds.retain(&keep);
+    }
 
-        // never any units killed if there are 0, 1, or 2 total units in the space.
-        if units_at_pos.iter().count() < 3 { return Vec::new(); }
+    fn opposing_unit_count_at_pos(&self, position: GameBoardSpacePos, owner: PlayerColor) -> usize {
+        self.knights.iter().filter(|ref knight| knight.position == position && knight.owner != owner).count()
+    }
+}
 
-        let mut player_unit_count = HashMap::new();
-        for unit in units_at_pos.iter() {
-            *player_unit_count.entry(unit.owner).or_insert(0) += 1
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Assumption here is that there can only ever be one player with multiple units on the same space,
-        // because the function space_ok_for_knight() enforces that.
-        assert!(player_unit_count.keys().filter(|x| *player_unit_count.get(x).unwrap() > 1).count() <= 1);
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
 
-        let winning_owner = player_unit_count.keys().find(|x| *player_unit_count.get(x).unwrap() > 1);
-        match winning_owner {
-            Some(winning_owner) => {
-                let dead_units: std::vec::Vec<UnitInfo> = units_at_pos.iter().filter(|ref knight| knight.owner != *winning_owner).map(|x| x.clone()).collect();
-                self.knights.retain(|ref knight| knight.position != position || knight.owner == *winning_owner);
-                dead_units
-            }
-            None => {
-                // Nobody has more than 1 unit at this space.
-                // Should only hit this case if there are 3 or 4 players all occupying the same space, each with one unit.
-                Vec::new()
-            }
+    // The left column of the board as plains, and nothing anywhere else.
+    fn column_board() -> GameBoard {
+        let mut game_board = GameBoard::new();
+        for y_pos in 0..7 {
+            game_board.set_board_space_type(pos(0, y_pos), GameBoardSpaceType::Plains);
         }
+        game_board
     }
 
-    fn opposing_unit_count_at_pos(&self, position: GameBoardSpacePos, owner: PlayerColor) -> usize {
-        self.knights.iter().filter(|ref knight| knight.position == position && knight.owner != owner).count()
+    fn blocked(reason: BlockedReason) -> RuleError {
+        RuleError::DestinationBlocked { reason: reason }
+    }
+
+    #[test]
+    fn moving_other_knights() {
+        let mut game_board = column_board();
+        game_board.add_knight(pos(0, 1), PlayerColor::Blue).unwrap();
+        assert_eq!(game_board.move_knight(pos(0, 1), pos(0, 2), PlayerColor::Red).err(), Some(RuleError::NotYourKnight { owner: PlayerColor::Blue }));
+        assert_eq!(game_board.move_knight(pos(0, 3), pos(0, 2), PlayerColor::Red).err(), Some(RuleError::NoKnightAtSource));
+        assert!(game_board.knights().all(|knight| knight.position == pos(0, 1)));
+    }
+
+    #[test]
+    fn blocked_knight_moves() {
+        let mut game_board = column_board();
+        game_board.set_board_space_type(pos(0, 3), GameBoardSpaceType::Water);
+        game_board.add_knight(pos(0, 2), PlayerColor::Red).unwrap();
+        game_board.add_knight(pos(0, 1), PlayerColor::Blue).unwrap();
+        game_board.add_knight(pos(0, 1), PlayerColor::Green).unwrap();
+        assert_eq!(game_board.move_knight(pos(0, 2), pos(0, 4), PlayerColor::Red).err(), Some(blocked(BlockedReason::NotNextToKnight)));
+        assert_eq!(game_board.move_knight(pos(0, 2), pos(0, 3), PlayerColor::Red).err(), Some(blocked(BlockedReason::Water)));
+        assert_eq!(game_board.move_knight(pos(0, 2), pos(0, 1), PlayerColor::Red).err(), Some(blocked(BlockedReason::TooManyOpposingKnights)));
+        assert_eq!(game_board.move_knight(pos(1, 2), pos(0, 2), PlayerColor::Red).err(), Some(RuleError::NoKnightAtSource));
+        assert!(game_board.knights().any(|knight| knight.position == pos(0, 2) && knight.owner == PlayerColor::Red));
     }
 
+    #[test]
+    fn blocked_placements() {
+        let mut game_board = column_board();
+        game_board.set_board_space_type(pos(0, 5), GameBoardSpaceType::Forest);
+        assert_eq!(game_board.add_knight(pos(3, 3), PlayerColor::Red).err(), Some(blocked(BlockedReason::NotOnMap)));
+        assert_eq!(game_board.add_city(pos(0, 5), PlayerColor::Red).err(), Some(blocked(BlockedReason::WrongTerrain(GameBoardSpaceType::Forest))));
+        game_board.add_city(pos(0, 1), PlayerColor::Red).unwrap();
+        assert_eq!(game_board.add_city(pos(0, 1), PlayerColor::Blue).err(), Some(blocked(BlockedReason::AlreadyBuiltOn)));
+        assert_eq!(game_board.add_city(pos(0, 2), PlayerColor::Blue).err(), Some(blocked(BlockedReason::TooCloseToCity)));
+        assert_eq!(game_board.add_village(pos(0, 3), PlayerColor::Red).err(), Some(blocked(BlockedReason::NoKnightThere)));
+        assert_eq!(game_board.num_cities(), 1);
+        assert_eq!(game_board.num_knights(), 0);
+        assert_eq!(game_board.villages().count(), 0);
+    }
 }
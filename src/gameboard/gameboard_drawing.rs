@@ -1,9 +1,15 @@
-use colors::Color;
+use animation::{AnimationKind,Animations};
+use batch::{self,ShapeBatch};
+use camera::Camera;
+use colors::{self,Color,Palette};
 use drawing;
+use fonts;
 use gameboard::gameboard::{GameBoard,GameBoardSpaceType,GameBoardSpacePos,game_board_pos_to_drawing_pos,game_constants};
 use gl;
 use images::SVGImages;
 use render_gl;
+use rules::PieceType;
+use std::cmp;
 use std::collections::HashMap;
 use PlayerColor;
 
@@ -39,11 +45,48 @@ pub fn scaling_for_board(drawable_size: (u32, u32)) -> (f32, f32) {
     (x_scale, y_scale)
 }
 
-pub fn draw_game_board_space(gl: &gl::Gl, shader_program: &render_gl::Program, space_type: GameBoardSpaceType, position: GameBoardSpacePos) {
+// The rectangle that the board is seen through, in drawing units without the camera.
+pub fn board_area() -> drawing::RectangleSpec {
+    drawing::RectangleSpec {
+        color: drawing::ColorSpec { r: 0xFF, g: 0xFF, b: 0xFF },
+        pos: drawing::PositionSpec {
+            x: drawing_constants::GAME_BOARD_ORIGIN_X,
+            y: drawing_constants::GAME_BOARD_ORIGIN_Y },
+        size: drawing::SizeSpec {
+            x: drawing_constants::HEXAGON_X_SPACING * game_constants::MAX_BOARD_WIDTH as f32 + 0.25 * drawing_constants::HEXAGON_WIDTH,
+            y: drawing_constants::HEXAGON_Y_SPACING * game_constants::MAX_BOARD_HEIGHT as f32 + 0.5 * drawing_constants::HEXAGON_HEIGHT}}
+}
+
+// Keep what is drawn next inside the board area, so that a moved or zoomed board doesn't cover the rest of the UI.
+// Call with enabled = false once the board and its pieces are drawn.
+pub fn clip_to_board_area(gl: &gl::Gl, drawable_size: (u32, u32), enabled: bool) {
+    if !enabled {
+        unsafe {
+            gl.Disable(gl::SCISSOR_TEST);
+        }
+        return;
+    }
+
+    let (window_width, window_height) = drawable_size;
+    let (x_scale, y_scale) = scaling_for_board(drawable_size);
+    let area = board_area();
+    // From drawing units to pixels, with (0, 0) in the bottom left corner of the window like glScissor wants.
+    let to_pixels = |drawing_pos: f32, scale: f32, window_size: u32| ((drawing_pos * scale + 1.0) / 2.0 * window_size as f32).round().max(0.0) as i32;
+    let left = to_pixels(area.pos.x, x_scale, window_width);
+    let bottom = to_pixels(area.pos.y, y_scale, window_height);
+    let right = to_pixels(area.pos.x + area.size.x, x_scale, window_width);
+    let top = to_pixels(area.pos.y + area.size.y, y_scale, window_height);
+    unsafe {
+        gl.Enable(gl::SCISSOR_TEST);
+        gl.Scissor(left, bottom, right - left, top - bottom);
+    }
+}
+
+pub fn add_game_board_space(shapes: &mut ShapeBatch, space_type: GameBoardSpaceType, position: GameBoardSpacePos) {
     match space_type {
         GameBoardSpaceType::Void => {},
         _ => {
-            drawing::draw_hexagon(&gl, &shader_program, drawing::HexagonSpec {
+            shapes.add_hexagon(&drawing::HexagonSpec {
             color: space_type.color(),
             pos: game_board_pos_to_drawing_pos(position),
             width: drawing_constants::HEXAGON_WIDTH } );
@@ -51,18 +94,121 @@ pub fn draw_game_board_space(gl: &gl::Gl, shader_program: &render_gl::Program, s
     }
 }
 
-pub fn highlight_space_for_board_setup(gl: &gl::Gl, shader_program: &render_gl::Program, space_type: GameBoardSpaceType, position: GameBoardSpacePos) {
+// Line segments that mark each kind of terrain, so that the terrain can be told apart without the colors.
+// Measured in hexagon widths from the middle of the space.
+fn terrain_pattern(space_type: GameBoardSpaceType) -> Vec<((f32, f32), (f32, f32))> {
+    match space_type {
+        GameBoardSpaceType::Void => vec![],
+        // Waves
+        GameBoardSpaceType::Water => {
+            let mut segments = Vec::new();
+            for &y in [0.07, -0.07].iter() {
+                for i in 0..4 {
+                    let x = -0.2 + 0.1 * i as f32;
+                    let (y_from, y_to) = if i % 2 == 0 { (y - 0.025, y + 0.025) } else { (y + 0.025, y - 0.025) };
+                    segments.push(((x, y_from), (x + 0.1, y_to)));
+                }
+            }
+            segments
+        }
+        // Two peaks
+        GameBoardSpaceType::Mountain => vec![
+            ((-0.22, -0.1), (-0.08, 0.1)), ((-0.08, 0.1), (0.06, -0.1)),
+            ((-0.02, -0.1), (0.1, 0.06)), ((0.1, 0.06), (0.22, -0.1))
+        ],
+        // Two trees
+        GameBoardSpaceType::Forest => {
+            let mut segments = Vec::new();
+            for &x in [-0.1, 0.1].iter() {
+                segments.push(((x - 0.07, -0.02), (x, 0.12)));
+                segments.push(((x, 0.12), (x + 0.07, -0.02)));
+                segments.push(((x + 0.07, -0.02), (x - 0.07, -0.02)));
+                segments.push(((x, -0.02), (x, -0.1)));
+            }
+            segments
+        }
+        // Grass
+        GameBoardSpaceType::Plains => vec![
+            ((-0.2, 0.06), (-0.06, 0.06)), ((0.06, 0.06), (0.2, 0.06)),
+            ((-0.07, -0.06), (0.07, -0.06))
+        ],
+        // Furrows
+        GameBoardSpaceType::Field => vec![
+            ((-0.2, -0.1), (-0.08, 0.1)), ((-0.06, -0.1), (0.06, 0.1)), ((0.08, -0.1), (0.2, 0.1))
+        ]
+    }
+}
+
+pub fn terrain_pattern_lines(space_type: GameBoardSpaceType, position: GameBoardSpacePos) -> Vec<((f32, f32), (f32, f32))> {
+    let center = game_board_pos_to_drawing_pos(position);
+    let scale = drawing_constants::HEXAGON_WIDTH;
+    terrain_pattern(space_type).into_iter().map(|((x_from, y_from), (x_to, y_to))| {
+        ((center.x + x_from * scale, center.y + y_from * scale), (center.x + x_to * scale, center.y + y_to * scale))
+    }).collect()
+}
+
+pub fn add_terrain_pattern(shapes: &mut ShapeBatch, space_type: GameBoardSpaceType, position: GameBoardSpacePos) {
+    let color = colors::contrasting_shade(space_type.color());
+    for (from, to) in terrain_pattern_lines(space_type, position) {
+        shapes.add_line(from, to, &color);
+    }
+}
+
+fn draw_terrain_pattern(gl: &gl::Gl, shader_program: &render_gl::Program, space_type: GameBoardSpaceType, position: GameBoardSpacePos) {
+    let color = colors::contrasting_shade(space_type.color());
+    let mut vertices = Vec::new();
+    for (from, to) in terrain_pattern_lines(space_type, position) {
+        drawing::push_line(&mut vertices, from, to, &color);
+    }
+    shader_program.set_used();
+    unsafe {
+        gl.LineWidth(2.0);
+    }
+    batch::draw_vertices(gl, batch::VertexFormat::Colored, gl::LINES, &vertices);
+}
+
+// The terrain of the whole board, kept on the GPU and drawn all at once.
+// It is only built again when the terrain or the palette changes (see GameBoard::terrain_generation).
+pub struct BoardMesh {
+    shapes: ShapeBatch,
+    terrain_generation: Option<usize>,
+    palette: Palette
+}
+
+impl BoardMesh {
+    pub fn new(gl: &gl::Gl) -> BoardMesh {
+        BoardMesh {
+            shapes: ShapeBatch::new(gl),
+            terrain_generation: None,
+            palette: colors::current_palette()
+        }
+    }
+}
+
+// preview_space_type is the terrain that will be dropped on this space, if the space is empty.
+// placement_ok is false if dropping the piece here would break the board setup rules.
+pub fn highlight_space_for_board_setup(gl: &gl::Gl, shader_program: &render_gl::Program, space_type: GameBoardSpaceType, preview_space_type: Option<GameBoardSpaceType>, placement_ok: bool, position: GameBoardSpacePos) {
     match space_type {
         GameBoardSpaceType::Void => {
+            let preview_color = match preview_space_type {
+                Some(preview_space_type) => preview_space_type.color(),
+                None => drawing::ColorSpec { r: 0xFF, g: 0xFF, b: 0xFF }
+            };
             drawing::draw_hexagon(&gl, &shader_program, drawing::HexagonSpec {
-                color: drawing::ColorSpec { r: 0xFF, g: 0xFF, b: 0xFF },
+                color: preview_color,
                 pos: game_board_pos_to_drawing_pos(position),
                 width: drawing_constants::HEXAGON_WIDTH } );
+            if let Some(preview_space_type) = preview_space_type {
+                draw_terrain_pattern(gl, shader_program, preview_space_type, position);
+            }
+            let outline_color =
+                if placement_ok { drawing::ColorSpec { r: 0xFF, g: 0xFF, b: 0xFF } }
+                else { drawing::ColorSpec { r: 0xFF, g: 0x00, b: 0x00 } };
             drawing::draw_hexagon_outline(
                 &gl,
                 &shader_program,
                 drawing::HexagonSpec {
-                    color: drawing::ColorSpec { r: 0x00, g: 0x00, b: 0x00 },
+                    color: outline_color,
                     pos: game_board_pos_to_drawing_pos(position),
                     width: drawing_constants::HEXAGON_WIDTH },
                 3.0);
@@ -81,43 +227,44 @@ pub fn highlight_space_for_board_setup(gl: &gl::Gl, shader_program: &render_gl::
 }
 
 pub trait Draw {
-    fn draw_board(&self, gl: &gl::Gl, shader_program: &render_gl::Program);
+    fn draw_board(&self, shader_program: &render_gl::Program, board_mesh: &mut BoardMesh);
     fn draw_border(gl: &gl::Gl, shader_program: &render_gl::Program);
-    fn draw_cities(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), images: &SVGImages);
+    fn draw_cities(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations);
+    fn draw_villages(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations);
+    fn draw_strongholds(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations);
     fn draw_knight(gl: &gl::Gl, shader_program: &render_gl::Program, images: &SVGImages, owner: &PlayerColor, drawing_pos: &drawing::PositionSpec, scaling_for_board: (f32, f32), margin_and_offset: (f32, f32, f32, f32));
-    fn draw_knights(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), images: &SVGImages, baggage: &mut drawing::TextDrawingBaggage);
+    fn draw_knights(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations, baggage: &mut drawing::TextDrawingBaggage);
 }
 
 impl Draw for GameBoard {
-    fn draw_board(&self, gl: &gl::Gl, shader_program: &render_gl::Program) {
-        for x in 0..game_constants::MAX_BOARD_WIDTH {
-            for y in 0..game_constants::MAX_BOARD_HEIGHT {
-                let position = GameBoardSpacePos {x_pos: x as u8, y_pos: y as u8};
-                let space_type = self.get_board_space_type(position);
-                draw_game_board_space(&gl, &shader_program, space_type, position);
+    fn draw_board(&self, shader_program: &render_gl::Program, board_mesh: &mut BoardMesh) {
+        if board_mesh.terrain_generation != Some(self.terrain_generation()) || board_mesh.palette != colors::current_palette() {
+            board_mesh.shapes.clear();
+            for x in 0..game_constants::MAX_BOARD_WIDTH {
+                for y in 0..game_constants::MAX_BOARD_HEIGHT {
+                    let position = GameBoardSpacePos {x_pos: x as u8, y_pos: y as u8};
+                    let space_type = self.get_board_space_type(position);
+                    add_game_board_space(&mut board_mesh.shapes, space_type, position);
+                    add_terrain_pattern(&mut board_mesh.shapes, space_type, position);
+                }
             }
+            board_mesh.terrain_generation = Some(self.terrain_generation());
+            board_mesh.palette = colors::current_palette();
         }
+        board_mesh.shapes.draw(shader_program, 2.0);
     }
 
     fn draw_border(gl: &gl::Gl, shader_program: &render_gl::Program) {
-        drawing::draw_rectangle_outline(
-            &gl,
-            &shader_program,
-            drawing::RectangleSpec {
-                color: drawing::ColorSpec { r: 0xFF, g: 0xFF, b: 0xFF },
-                pos: drawing::PositionSpec {
-                    x: drawing_constants::GAME_BOARD_ORIGIN_X,
-                    y: drawing_constants::GAME_BOARD_ORIGIN_Y },
-                size: drawing::SizeSpec {
-                    x: drawing_constants::HEXAGON_X_SPACING * game_constants::MAX_BOARD_WIDTH as f32 + 0.25 * drawing_constants::HEXAGON_WIDTH,
-                    y: drawing_constants::HEXAGON_Y_SPACING * game_constants::MAX_BOARD_HEIGHT as f32 + 0.5 * drawing_constants::HEXAGON_HEIGHT}},
-            3.0);
+        drawing::draw_rectangle_outline(&gl, &shader_program, board_area(), 3.0);
     }
 
-    fn draw_cities(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), images: &SVGImages) {
+    fn draw_cities(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations) {
         for city in self.cities() {
-            let (x_scale, y_scale) = scaling_for_board(drawable_size);
-            let drawing_pos = game_board_pos_to_drawing_pos(city.position);
+            if animations.hides(PieceType::City, city.owner, city.position) > 0 {
+                continue;
+            }
+            let (x_scale, y_scale) = camera.scaling(drawable_size);
+            let drawing_pos = camera.to_view_pos(&game_board_pos_to_drawing_pos(city.position));
             {
                 let x_margin = 0.25;
                 let y_margin = 0.25;
@@ -138,6 +285,35 @@ impl Draw for GameBoard {
         }
     }
 
+    fn draw_villages(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations) {
+        let (x_scale, y_scale) = camera.scaling(drawable_size);
+        for village in self.villages() {
+            if animations.hides(PieceType::Village, village.owner, village.position) > 0 {
+                continue;
+            }
+            // Villages are smaller than cities, so they leave room for the knights underneath.
+            let x_margin = 0.3;
+            let y_margin = 0.3;
+            let x_offset = 0.0;
+            let y_offset = 0.45;
+            draw_building_image(gl, shader_program, images.get_village_image(&village.owner), camera.to_view_pos(&game_board_pos_to_drawing_pos(village.position)), (x_scale, y_scale), (x_margin, y_margin, x_offset, y_offset));
+        }
+    }
+
+    fn draw_strongholds(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations) {
+        let (x_scale, y_scale) = camera.scaling(drawable_size);
+        for stronghold in self.strongholds() {
+            if animations.hides(PieceType::Stronghold, stronghold.owner, stronghold.position) > 0 {
+                continue;
+            }
+            let x_margin = 0.25;
+            let y_margin = 0.25;
+            let x_offset = 0.0;
+            let y_offset = 0.5;
+            draw_building_image(gl, shader_program, images.get_stronghold_image(&stronghold.owner), camera.to_view_pos(&game_board_pos_to_drawing_pos(stronghold.position)), (x_scale, y_scale), (x_margin, y_margin, x_offset, y_offset));
+        }
+    }
+
     fn draw_knight(gl: &gl::Gl, shader_program: &render_gl::Program, images: &SVGImages, owner: &PlayerColor, drawing_pos: &drawing::PositionSpec, scaling_for_board: (f32, f32), margin_and_offset: (f32, f32, f32, f32)) {
         let (x_scale, y_scale) = scaling_for_board;
         let (x_margin, y_margin, x_offset, y_offset) = margin_and_offset;
@@ -153,7 +329,7 @@ impl Draw for GameBoard {
                 y: drawing_constants::HEXAGON_HEIGHT * y_scale * (1.0 - y_margin * 2.0)});
     }
 
-    fn draw_knights(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), images: &SVGImages, baggage: &mut drawing::TextDrawingBaggage) {
+    fn draw_knights(&self, gl: &gl::Gl, shader_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations, baggage: &mut drawing::TextDrawingBaggage) {
         if self.knights().len() == 0 { return; }
 
         // TODO Don't calculate this on the fly each time this gets called to render each frame.
@@ -163,10 +339,19 @@ impl Draw for GameBoard {
             *positions.entry(knight.position).or_insert(HashMap::new()).entry(knight.owner).or_insert(0) += 1
         }
 
-        let (x_scale, y_scale) = scaling_for_board(drawable_size);
+        // Knights that are still on their way are drawn by draw_piece_animations instead.
+        for (&position, counts) in positions.iter_mut() {
+            for (&owner, count) in counts.iter_mut() {
+                *count -= cmp::min(*count, animations.hides(PieceType::Knight, owner, position));
+            }
+            counts.retain(|_, count| *count > 0);
+        }
+        positions.retain(|_, counts| !counts.is_empty());
+
+        let (x_scale, y_scale) = camera.scaling(drawable_size);
 
         for (position, counts) in positions {
-            let drawing_pos = game_board_pos_to_drawing_pos(position);
+            let drawing_pos = camera.to_view_pos(&game_board_pos_to_drawing_pos(position));
             assert!(!counts.is_empty());
             match counts.len() {
                 1 => {
@@ -201,7 +386,7 @@ impl Draw for GameBoard {
                                 x: drawing_pos.x * x_scale - 0.5 * drawing_constants::HEXAGON_WIDTH * x_scale + drawing_constants::HEXAGON_WIDTH * x_scale * (x_margin + x_offset),
                                 y: drawing_pos.y * y_scale - 0.5 * drawing_constants::HEXAGON_HEIGHT * y_scale + drawing_constants::HEXAGON_WIDTH * y_scale * (y_margin + y_offset)},
                             drawing::ObjectOriginLocation::Center,
-                            24,
+                            fonts::TextStyle::Title,
                             player_color,
                             format!("x{}", count));
                     }
@@ -235,7 +420,7 @@ impl Draw for GameBoard {
                                 x: drawing_pos.x * x_scale,
                                 y: drawing_pos.y * y_scale - 0.25 * drawing_constants::HEXAGON_HEIGHT * y_scale},
                             drawing::ObjectOriginLocation::Center,
-                            18,
+                            fonts::TextStyle::Body,
                             PlayerColor::Blue.color(),
                             format!("TODO"));
                     }
@@ -245,6 +430,37 @@ impl Draw for GameBoard {
     }
 }
 
+// drawing_pos is the middle of the space, as seen by the camera (see Camera::to_view_pos).
+fn draw_building_image(gl: &gl::Gl, shader_program: &render_gl::Program, image: &drawing::Texture, drawing_pos: drawing::PositionSpec, scaling_for_board: (f32, f32), margin_and_offset: (f32, f32, f32, f32)) {
+    let (x_scale, y_scale) = scaling_for_board;
+    let (x_margin, y_margin, x_offset, y_offset) = margin_and_offset;
+    drawing::draw_image(
+        &gl,
+        &shader_program,
+        image,
+        drawing::PositionSpec{
+            x: drawing_pos.x * x_scale - 0.5 * drawing_constants::HEXAGON_WIDTH * x_scale + drawing_constants::HEXAGON_WIDTH * x_scale * (x_margin + x_offset),
+            y: drawing_pos.y * y_scale - 0.5 * drawing_constants::HEXAGON_HEIGHT * y_scale + drawing_constants::HEXAGON_WIDTH * x_scale * (y_margin + y_offset)},
+        drawing::SizeSpec{
+            x: drawing_constants::HEXAGON_WIDTH * x_scale * (1.0 - x_margin * 2.0),
+            y: drawing_constants::HEXAGON_HEIGHT * y_scale * (1.0 - y_margin * 2.0)});
+}
+
+// Tint each controlled space with the color of the player that controls it.
+pub fn draw_territory_overlay(gl: &gl::Gl, shader_program: &render_gl::Program, game_board: &GameBoard) {
+    drawing::write_opacity_data(&gl, &shader_program, 0.35);
+    shader_program.set_used();
+    let mut vertices: Vec<f32> = Vec::new();
+    for (position, owner) in game_board.territory() {
+        drawing::push_hexagon_triangles(&mut vertices, &drawing::HexagonSpec {
+            color: owner.color(),
+            pos: game_board_pos_to_drawing_pos(position),
+            width: drawing_constants::HEXAGON_WIDTH } );
+    }
+    batch::draw_vertices(&gl, batch::VertexFormat::Colored, gl::TRIANGLES, &vertices);
+    drawing::write_opacity_data(&gl, &shader_program, 1.0);
+}
+
 pub fn highlight_space_ok(
     gl: &gl::Gl,
     shader_program: &render_gl::Program,
@@ -283,18 +499,20 @@ pub fn highlight_space_for_city_setup(
     gl: &gl::Gl,
     shader_program: &render_gl::Program,
     image_program: &render_gl::Program,
-    city_image: &nsvg::image::RgbaImage,
+    city_image: &drawing::Texture,
     position: GameBoardSpacePos,
     game_board: &GameBoard,
-    drawable_size: (u32, u32))
+    drawable_size: (u32, u32),
+    camera: &Camera)
 {
     match game_board.get_board_space_type(position) {
         GameBoardSpaceType::Void => {},
         _ => {
-            let (x_scale, y_scale) = scaling_for_board(drawable_size);
+            let (x_scale, y_scale) = camera.scaling(drawable_size);
 
             if game_board.space_ok_for_city(position) {
                 let drawing_pos = game_board_pos_to_drawing_pos(position);
+                let view_pos = camera.to_view_pos(&drawing_pos);
                 let x_margin = 0.25;
                 let y_margin = 0.25;
                 let x_offset = 0.0;
@@ -305,8 +523,8 @@ pub fn highlight_space_for_city_setup(
                     &image_program,
                     &city_image,
                     drawing::PositionSpec{
-                        x: drawing_pos.x * x_scale - 0.5 * drawing_constants::HEXAGON_WIDTH * x_scale + drawing_constants::HEXAGON_WIDTH * x_scale * (x_margin + x_offset),
-                        y: drawing_pos.y * y_scale - 0.5 * drawing_constants::HEXAGON_HEIGHT * y_scale + drawing_constants::HEXAGON_WIDTH * x_scale * (y_margin + y_offset)},
+                        x: view_pos.x * x_scale - 0.5 * drawing_constants::HEXAGON_WIDTH * x_scale + drawing_constants::HEXAGON_WIDTH * x_scale * (x_margin + x_offset),
+                        y: view_pos.y * y_scale - 0.5 * drawing_constants::HEXAGON_HEIGHT * y_scale + drawing_constants::HEXAGON_WIDTH * x_scale * (y_margin + y_offset)},
                     drawing::SizeSpec{
                         x: drawing_constants::HEXAGON_WIDTH * x_scale * (1.0 - x_margin * 2.0),
                         y: drawing_constants::HEXAGON_HEIGHT * y_scale * (1.0 - y_margin * 2.0)});
@@ -334,13 +552,120 @@ pub fn highlight_space_for_city_setup(
     }
 }
 
-pub fn highlight_spaces_for_board_setup(gl: &gl::Gl, shader_program: &render_gl::Program, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos), game_board: &GameBoard) {
+// board_piece is the next piece to be dropped, turned the way it will be placed.
+// The piece is only previewed if all three spaces are empty, otherwise the spaces that are in the way are marked.
+// If placement_ok is false, the preview is outlined in red.
+pub fn highlight_spaces_for_board_setup(
+    gl: &gl::Gl,
+    shader_program: &render_gl::Program,
+    positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos),
+    game_board: &GameBoard,
+    board_piece: Option<(GameBoardSpaceType, GameBoardSpaceType, GameBoardSpaceType)>,
+    placement_ok: bool)
+{
     let (pos_under_mouse_a, pos_under_mouse_b, pos_under_mouse_c) = positions;
     let space_type_a = game_board.get_board_space_type(pos_under_mouse_a);
     let space_type_b = game_board.get_board_space_type(pos_under_mouse_b);
     let space_type_c = game_board.get_board_space_type(pos_under_mouse_c);
 
-    highlight_space_for_board_setup(&gl, &shader_program, space_type_a, pos_under_mouse_a);
-    highlight_space_for_board_setup(&gl, &shader_program, space_type_b, pos_under_mouse_b);
-    highlight_space_for_board_setup(&gl, &shader_program, space_type_c, pos_under_mouse_c);
+    let (preview_space_type_a, preview_space_type_b, preview_space_type_c) =
+        match board_piece {
+            Some((piece_space_type_a, piece_space_type_b, piece_space_type_c)) if game_board.space_ok_for_board_piece(positions) => {
+                (Some(piece_space_type_a), Some(piece_space_type_b), Some(piece_space_type_c))
+            }
+            _ => (None, None, None)
+        };
+
+    highlight_space_for_board_setup(&gl, &shader_program, space_type_a, preview_space_type_a, placement_ok, pos_under_mouse_a);
+    highlight_space_for_board_setup(&gl, &shader_program, space_type_b, preview_space_type_b, placement_ok, pos_under_mouse_b);
+    highlight_space_for_board_setup(&gl, &shader_program, space_type_c, preview_space_type_c, placement_ok, pos_under_mouse_c);
+}
+
+// Where each kind of piece goes on its space, as (x_margin, y_margin, x_offset, y_offset).
+// These match draw_cities, draw_villages, draw_strongholds and a knight on its own in draw_knights.
+fn piece_margin_and_offset(piece: PieceType) -> (f32, f32, f32, f32) {
+    match piece {
+        PieceType::City | PieceType::Stronghold => (0.25, 0.25, 0.0, 0.5),
+        PieceType::Village => (0.3, 0.3, 0.0, 0.45),
+        PieceType::Knight => (3.0 / 8.0, 3.0 / 8.0, -0.2, -0.2)
+    }
+}
+
+// view_pos is the middle of the space, as seen by the camera.
+fn draw_piece(gl: &gl::Gl, image_program: &render_gl::Program, images: &SVGImages, piece: PieceType, owner: PlayerColor, view_pos: drawing::PositionSpec, scaling: (f32, f32), opacity: f32) {
+    drawing::write_opacity_data(gl, image_program, opacity);
+    match piece {
+        PieceType::Knight => GameBoard::draw_knight(gl, image_program, images, &owner, &view_pos, scaling, piece_margin_and_offset(piece)),
+        PieceType::City => draw_building_image(gl, image_program, images.get_city_image(&owner), view_pos, scaling, piece_margin_and_offset(piece)),
+        PieceType::Village => draw_building_image(gl, image_program, images.get_village_image(&owner), view_pos, scaling, piece_margin_and_offset(piece)),
+        PieceType::Stronghold => draw_building_image(gl, image_program, images.get_stronghold_image(&owner), view_pos, scaling, piece_margin_and_offset(piece))
+    }
+    drawing::write_opacity_data(gl, image_program, 1.0);
+}
+
+// Knights on the move, pieces dropping onto the board, and pieces fading away (see animation.rs).
+pub fn draw_piece_animations(gl: &gl::Gl, image_program: &render_gl::Program, drawable_size: (u32, u32), camera: &Camera, images: &SVGImages, animations: &Animations) {
+    let scaling = camera.scaling(drawable_size);
+    for (kind, progress) in animations.playing() {
+        match *kind {
+            AnimationKind::KnightMove { owner, ref path } => {
+                if path.len() < 2 {
+                    continue;
+                }
+                // The same amount of time for each step.
+                let steps_taken = progress * (path.len() - 1) as f32;
+                let step = cmp::min(steps_taken as usize, path.len() - 2);
+                let step_progress = steps_taken - step as f32;
+                let from = game_board_pos_to_drawing_pos(path[step]);
+                let to = game_board_pos_to_drawing_pos(path[step + 1]);
+                let drawing_pos = drawing::PositionSpec {
+                    x: from.x + (to.x - from.x) * step_progress,
+                    y: from.y + (to.y - from.y) * step_progress };
+                draw_piece(gl, image_program, images, PieceType::Knight, owner, camera.to_view_pos(&drawing_pos), scaling, 1.0);
+            }
+            AnimationKind::PieceDrop { piece, owner, position } => {
+                // Fall from half a space above, slowing down as it lands.
+                let mut drawing_pos = game_board_pos_to_drawing_pos(position);
+                drawing_pos.y += 0.5 * drawing_constants::HEXAGON_HEIGHT * (1.0 - progress) * (1.0 - progress);
+                draw_piece(gl, image_program, images, piece, owner, camera.to_view_pos(&drawing_pos), scaling, progress);
+            }
+            AnimationKind::FadeOut { piece, owner, position } => {
+                let drawing_pos = game_board_pos_to_drawing_pos(position);
+                draw_piece(gl, image_program, images, piece, owner, camera.to_view_pos(&drawing_pos), scaling, 1.0 - progress);
+            }
+            AnimationKind::BoardPieceDrop { .. } => {}
+        }
+    }
+}
+
+// Board pieces dropping onto the board.  The board mesh already has them, so their spaces are covered up until they land.
+pub fn draw_board_animations(gl: &gl::Gl, shader_program: &render_gl::Program, animations: &Animations) {
+    shader_program.set_used();
+    let mut vertices: Vec<f32> = Vec::new();
+    for position in animations.hidden_spaces() {
+        drawing::push_hexagon_triangles(&mut vertices, &drawing::HexagonSpec {
+            color: GameBoardSpaceType::Void.color(),
+            pos: game_board_pos_to_drawing_pos(position),
+            width: drawing_constants::HEXAGON_WIDTH } );
+    }
+    batch::draw_vertices(&gl, batch::VertexFormat::Colored, gl::TRIANGLES, &vertices);
+
+    for (kind, progress) in animations.playing() {
+        if let AnimationKind::BoardPieceDrop { ref spaces } = *kind {
+            // Fall from a space above, slowing down as it lands.
+            let height = drawing_constants::HEXAGON_HEIGHT * (1.0 - progress) * (1.0 - progress);
+            let mut vertices: Vec<f32> = Vec::new();
+            for &(position, space_type) in spaces.iter() {
+                let mut drawing_pos = game_board_pos_to_drawing_pos(position);
+                drawing_pos.y += height;
+                drawing::push_hexagon_triangles(&mut vertices, &drawing::HexagonSpec {
+                    color: space_type.color(),
+                    pos: drawing_pos,
+                    width: drawing_constants::HEXAGON_WIDTH } );
+            }
+            drawing::write_opacity_data(&gl, &shader_program, progress);
+            batch::draw_vertices(&gl, batch::VertexFormat::Colored, gl::TRIANGLES, &vertices);
+            drawing::write_opacity_data(&gl, &shader_program, 1.0);
+        }
+    }
 }
@@ -0,0 +1,202 @@
+use gameboard::gameboard::{GameBoard,GameBoardSpacePos,GameBoardSpaceType,game_constants};
+use std::fmt;
+use PlayerColor;
+
+// The game board as text, for terminals, logs and test failures.
+//
+// Each space is a terrain letter, then its building (if any) and the knights on it, e.g. "PCr 2r" is a plains space
+// with a red city and two red knights.  The odd columns are half a space higher than the even columns, like on the screen,
+// so each row of the board takes two lines: the odd columns on the first and the even columns on the second.
+//
+//   W water   M mountain   F forest   P plains   A field (arable land)   . nothing
+//   C city   V village   S stronghold, followed by the owner: r red, b blue, g green, y yellow
+//   a number of knights, followed by their owner
+
+pub fn terrain_letter(space_type: GameBoardSpaceType) -> char {
+    match space_type {
+        GameBoardSpaceType::Void => '.',
+        GameBoardSpaceType::Water => 'W',
+        GameBoardSpaceType::Mountain => 'M',
+        GameBoardSpaceType::Forest => 'F',
+        GameBoardSpaceType::Plains => 'P',
+        GameBoardSpaceType::Field => 'A'
+    }
+}
+
+pub fn owner_letter(owner: PlayerColor) -> char {
+    match owner {
+        PlayerColor::Red => 'r',
+        PlayerColor::Blue => 'b',
+        PlayerColor::Green => 'g',
+        PlayerColor::Yellow => 'y'
+    }
+}
+
+// What is on one space, without any padding.
+fn space_text(game_board: &GameBoard, position: GameBoardSpacePos) -> String {
+    let mut text = terrain_letter(game_board.get_board_space_type(position)).to_string();
+
+    let buildings = game_board.cities().map(|unit| ('C', unit))
+        .chain(game_board.villages().map(|unit| ('V', unit)))
+        .chain(game_board.strongholds().map(|unit| ('S', unit)));
+    for (building_letter, unit) in buildings.filter(|&(_, unit)| unit.position == position) {
+        text.push(building_letter);
+        text.push(owner_letter(unit.owner));
+    }
+
+    // Counted per owner, in the order the owners' knights were added.
+    let mut knight_counts: Vec<(PlayerColor, usize)> = Vec::new();
+    for knight in game_board.knights().filter(|knight| knight.position == position) {
+        match knight_counts.iter().position(|&(owner, _)| owner == knight.owner) {
+            Some(index) => { knight_counts[index].1 += 1; }
+            None => { knight_counts.push((knight.owner, 1)); }
+        }
+    }
+    for (owner, count) in knight_counts {
+        text.push_str(&format!(" {}{}", count, owner_letter(owner)));
+    }
+    text
+}
+
+// The whole board, as described at the top of this file.  Every space is padded to the same width so the columns line up.
+pub fn board_text(game_board: &GameBoard) -> String {
+    let mut spaces: Vec<Vec<String>> = Vec::new();
+    for y_pos in 0..game_constants::MAX_BOARD_HEIGHT {
+        spaces.push((0..game_constants::MAX_BOARD_WIDTH).map(|x_pos| {
+            space_text(game_board, GameBoardSpacePos { x_pos: x_pos as u8, y_pos: y_pos as u8 })
+        }).collect());
+    }
+    let width = spaces.iter().flat_map(|row| row.iter()).map(|space| space.chars().count()).max().unwrap_or(1);
+
+    let mut text = String::new();
+    // y goes up on the board, so the last row is printed first.
+    for row in spaces.iter().rev() {
+        for &odd_columns in [true, false].iter() {
+            let mut line = String::new();
+            for (x_pos, space) in row.iter().enumerate() {
+                let shown = if (x_pos % 2 == 1) == odd_columns { space.as_str() } else { "" };
+                line.push_str(&format!("{:width$} ", shown, width = width));
+            }
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+    }
+    text
+}
+
+impl fmt::Display for GameBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", board_text(self))
+    }
+}
+
+// The board, followed by where each piece is, which is easier to search through in a log.
+impl fmt::Debug for GameBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "GameBoard")?;
+        write!(f, "{}", board_text(self))?;
+        let pieces = self.cities().map(|unit| ("cities", unit))
+            .chain(self.villages().map(|unit| ("villages", unit)))
+            .chain(self.strongholds().map(|unit| ("strongholds", unit)))
+            .chain(self.knights().map(|unit| ("knights", unit)));
+        let mut last_kind = "";
+        for (kind, unit) in pieces {
+            if kind != last_kind {
+                if last_kind != "" {
+                    writeln!(f)?;
+                }
+                write!(f, "{}:", kind)?;
+                last_kind = kind;
+            }
+            write!(f, " {:?} {}", unit.owner, unit.position)?;
+        }
+        if last_kind != "" {
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    // Plains in the bottom left corner with water next to it, and nothing anywhere else.
+    fn small_board() -> GameBoard {
+        let mut game_board = GameBoard::new();
+        game_board.set_board_space_type(pos(0, 0), GameBoardSpaceType::Plains);
+        game_board.set_board_space_type(pos(1, 0), GameBoardSpaceType::Water);
+        game_board
+    }
+
+    #[test]
+    fn letters() {
+        assert_eq!(terrain_letter(GameBoardSpaceType::Void), '.');
+        assert_eq!(terrain_letter(GameBoardSpaceType::Field), 'A');
+        assert_eq!(owner_letter(PlayerColor::Red), 'r');
+        assert_eq!(owner_letter(PlayerColor::Yellow), 'y');
+    }
+
+    #[test]
+    fn staggered_rows() {
+        // Each row is the odd columns, then the even columns, starting with the top row.
+        let mut expected = String::new();
+        for _ in 0..game_constants::MAX_BOARD_HEIGHT - 1 {
+            expected.push_str("  .   .   .   .   .   .\n");
+            expected.push_str(".   .   .   .   .   .   .\n");
+        }
+        expected.push_str("  W   .   .   .   .   .\n");
+        expected.push_str("P   .   .   .   .   .   .\n");
+        assert_eq!(board_text(&small_board()), expected);
+    }
+
+    #[test]
+    fn buildings_and_knights() {
+        let mut game_board = small_board();
+        game_board.set_board_space_type(pos(2, 0), GameBoardSpaceType::Forest);
+        game_board.add_city(pos(0, 0), PlayerColor::Red).unwrap();
+        game_board.add_knight(pos(0, 0), PlayerColor::Red).unwrap();
+        game_board.add_knight(pos(0, 0), PlayerColor::Red).unwrap();
+        assert_eq!(space_text(&game_board, pos(0, 0)), "PCr 2r");
+
+        // Owners are listed in the order their first knight arrived.
+        game_board.add_knight(pos(0, 0), PlayerColor::Blue).unwrap();
+        game_board.add_knight(pos(0, 0), PlayerColor::Red).unwrap();
+        assert_eq!(space_text(&game_board, pos(0, 0)), "PCr 3r 1b");
+
+        game_board.add_knight(pos(2, 0), PlayerColor::Yellow).unwrap();
+        game_board.add_stronghold(pos(2, 0), PlayerColor::Yellow).unwrap();
+        assert_eq!(space_text(&game_board, pos(2, 0)), "FSy 1y");
+        assert_eq!(space_text(&game_board, pos(1, 0)), "W");
+
+        // Every space is padded to the widest one, so the columns still line up.
+        let text = board_text(&game_board);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[lines.len() - 2], "          W                   .                   .                   .                   .                   .");
+        assert_eq!(lines[lines.len() - 1], "PCr 3r 1b           FSy 1y              .                   .                   .                   .                   .");
+        assert_eq!(lines[0], "          .                   .                   .                   .                   .                   .");
+    }
+
+    #[test]
+    fn display_and_debug() {
+        let mut game_board = small_board();
+        assert_eq!(format!("{}", game_board), board_text(&game_board));
+        assert_eq!(format!("{:?}", game_board), format!("GameBoard\n{}", board_text(&game_board)));
+
+        game_board.add_city(pos(0, 0), PlayerColor::Green).unwrap();
+        game_board.add_knight(pos(0, 0), PlayerColor::Green).unwrap();
+        game_board.add_knight(pos(0, 0), PlayerColor::Blue).unwrap();
+        assert_eq!(format!("{:?}", game_board),
+            format!("GameBoard\n{}cities: Green (0, 0)\nknights: Green (0, 0) Blue (0, 0)\n", board_text(&game_board)));
+    }
+
+    #[test]
+    fn position_display() {
+        assert_eq!(format!("{}", pos(3, 5)), "(3, 5)");
+        assert_eq!(format!("{:?}", pos(3, 5)), "GameBoardSpacePos { x_pos: 3, y_pos: 5 }");
+    }
+}
@@ -0,0 +1,390 @@
+use gameboard::gameboard::{GameBoardSpacePos,game_constants};
+use std::ops::{Add,Mul,Sub};
+
+// Axial hex coordinates.
+//
+// GameBoardSpacePos uses "offset" coordinates: columns and rows, with the odd columns shifted up by half a space.
+// That's convenient for storing the board in an array, but every neighbor calculation has to check whether the column is odd.
+// Axial coordinates skew the rows instead, so that each of the six directions is always the same (q, r) step.
+//
+//   q is the column, same as x_pos.
+//   r is the row, skewed down by half a space for every column to the right: r = y_pos - x_pos / 2
+//
+// The third "cube" coordinate s = -q - r is implied, and makes the distance and rotation math symmetrical.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AxialPos {
+    pub q: i32,
+    pub r: i32
+}
+
+// The six neighboring directions, in clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexDirection {
+    Up,
+    UpRight,
+    DownRight,
+    Down,
+    DownLeft,
+    UpLeft
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::Up,
+        HexDirection::UpRight,
+        HexDirection::DownRight,
+        HexDirection::Down,
+        HexDirection::DownLeft,
+        HexDirection::UpLeft
+    ];
+
+    pub fn step(&self) -> AxialPos {
+        match self {
+            HexDirection::Up => AxialPos { q: 0, r: 1 },
+            HexDirection::UpRight => AxialPos { q: 1, r: 0 },
+            HexDirection::DownRight => AxialPos { q: 1, r: -1 },
+            HexDirection::Down => AxialPos { q: 0, r: -1 },
+            HexDirection::DownLeft => AxialPos { q: -1, r: 0 },
+            HexDirection::UpLeft => AxialPos { q: -1, r: 1 }
+        }
+    }
+}
+
+impl AxialPos {
+    pub fn from_offset(position: GameBoardSpacePos) -> AxialPos {
+        AxialPos {
+            q: position.x_pos as i32,
+            r: position.y_pos as i32 - position.x_pos as i32 / 2
+        }
+    }
+
+    // Returns None if the position is off the board.
+    pub fn to_offset(&self) -> Option<GameBoardSpacePos> {
+        let x_pos = self.q;
+        // q can be negative here, so use floor division rather than rounding towards zero.
+        let y_pos = self.r + self.q.div_euclid(2);
+        if x_pos < 0 || y_pos < 0 || x_pos >= game_constants::MAX_BOARD_WIDTH as i32 || y_pos >= game_constants::MAX_BOARD_HEIGHT as i32 {
+            None
+        } else {
+            Some(GameBoardSpacePos { x_pos: x_pos as u8, y_pos: y_pos as u8 })
+        }
+    }
+
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn neighbor(&self, direction: HexDirection) -> AxialPos {
+        *self + direction.step()
+    }
+
+    pub fn distance_to(&self, other_pos: AxialPos) -> u32 {
+        let delta = other_pos - *self;
+        ((delta.q.abs() + delta.r.abs() + delta.s().abs()) / 2) as u32
+    }
+
+    // Rotate this position 60 degrees clockwise around center.
+    pub fn rotate_clockwise(&self, center: AxialPos) -> AxialPos {
+        let relative = *self - center;
+        center + AxialPos { q: relative.q + relative.r, r: -relative.q }
+    }
+
+    // Rotate this position 60 degrees counter-clockwise around center.
+    pub fn rotate_counter_clockwise(&self, center: AxialPos) -> AxialPos {
+        let relative = *self - center;
+        center + AxialPos { q: -relative.r, r: relative.q + relative.r }
+    }
+
+    // All positions exactly radius steps away, going clockwise around the ring.
+    pub fn ring(&self, radius: u32) -> Ring {
+        Ring {
+            current: *self + HexDirection::DownLeft.step() * radius as i32,
+            radius: radius,
+            side: 0,
+            steps_along_side: 0,
+            center_returned: false
+        }
+    }
+
+    // All positions up to radius steps away, starting at this position and going outwards one ring at a time.
+    pub fn spiral(&self, radius: u32) -> Spiral {
+        Spiral {
+            center: *self,
+            max_radius: radius,
+            current_ring: self.ring(0),
+            current_radius: 0
+        }
+    }
+
+    // The positions on a straight line from this position to other_pos, including both ends.
+    pub fn line_to(&self, other_pos: AxialPos) -> Vec<AxialPos> {
+        let num_steps = self.distance_to(other_pos);
+        if num_steps == 0 {
+            return vec![*self];
+        }
+
+        // Nudge the start point slightly, so a line that runs exactly along the edge between two spaces picks the same side every time.
+        let start_q = self.q as f32 + 1e-6;
+        let start_r = self.r as f32 + 1e-6;
+        let end_q = other_pos.q as f32 + 1e-6;
+        let end_r = other_pos.r as f32 + 1e-6;
+
+        (0..num_steps + 1).map(|step| {
+            let t = step as f32 / num_steps as f32;
+            AxialPos::round(start_q + (end_q - start_q) * t, start_r + (end_r - start_r) * t)
+        }).collect()
+    }
+
+    // Find the position whose center is nearest to a point given in fractional axial coordinates.
+    pub fn round(fractional_q: f32, fractional_r: f32) -> AxialPos {
+        let fractional_s = -fractional_q - fractional_r;
+
+        let mut q = fractional_q.round();
+        let mut r = fractional_r.round();
+        let s = fractional_s.round();
+
+        // Rounding each coordinate separately can break q + r + s == 0.
+        // Fix up whichever coordinate was rounded the furthest.
+        let q_diff = (q - fractional_q).abs();
+        let r_diff = (r - fractional_r).abs();
+        let s_diff = (s - fractional_s).abs();
+        if q_diff > r_diff && q_diff > s_diff {
+            q = -r - s;
+        } else if r_diff > s_diff {
+            r = -q - s;
+        }
+
+        AxialPos { q: q as i32, r: r as i32 }
+    }
+}
+
+impl Add for AxialPos {
+    type Output = AxialPos;
+
+    fn add(self, other: AxialPos) -> AxialPos {
+        AxialPos { q: self.q + other.q, r: self.r + other.r }
+    }
+}
+
+impl Sub for AxialPos {
+    type Output = AxialPos;
+
+    fn sub(self, other: AxialPos) -> AxialPos {
+        AxialPos { q: self.q - other.q, r: self.r - other.r }
+    }
+}
+
+impl Mul<i32> for AxialPos {
+    type Output = AxialPos;
+
+    fn mul(self, factor: i32) -> AxialPos {
+        AxialPos { q: self.q * factor, r: self.r * factor }
+    }
+}
+
+pub struct Ring {
+    current: AxialPos,
+    radius: u32,
+    side: usize,
+    steps_along_side: u32,
+    center_returned: bool
+}
+
+impl Iterator for Ring {
+    type Item = AxialPos;
+
+    fn next(&mut self) -> Option<AxialPos> {
+        if self.radius == 0 {
+            // A ring of radius 0 is just the center.
+            if self.center_returned {
+                return None;
+            }
+            self.center_returned = true;
+            return Some(self.current);
+        }
+
+        if self.side >= 6 {
+            return None;
+        }
+
+        // Starting from the down-left corner, walk radius steps along each of the six sides.
+        let ret_val = self.current;
+        self.current = self.current.neighbor(HexDirection::ALL[self.side]);
+        self.steps_along_side += 1;
+        if self.steps_along_side == self.radius {
+            self.steps_along_side = 0;
+            self.side += 1;
+        }
+        Some(ret_val)
+    }
+}
+
+pub struct Spiral {
+    center: AxialPos,
+    max_radius: u32,
+    current_ring: Ring,
+    current_radius: u32
+}
+
+impl Iterator for Spiral {
+    type Item = AxialPos;
+
+    fn next(&mut self) -> Option<AxialPos> {
+        loop {
+            if let Some(position) = self.current_ring.next() {
+                return Some(position);
+            }
+            if self.current_radius >= self.max_radius {
+                return None;
+            }
+            self.current_radius += 1;
+            self.current_ring = self.center.ring(self.current_radius);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn axial(q: i32, r: i32) -> AxialPos {
+        AxialPos { q: q, r: r }
+    }
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    #[test]
+    fn offset_round_trip() {
+        for x_pos in 0..game_constants::MAX_BOARD_WIDTH {
+            for y_pos in 0..game_constants::MAX_BOARD_HEIGHT {
+                let position = pos(x_pos as u8, y_pos as u8);
+                assert!(AxialPos::from_offset(position).to_offset() == Some(position));
+            }
+        }
+        assert_eq!(AxialPos::from_offset(pos(3, 4)), axial(3, 3));
+        assert!(axial(-1, 0).to_offset().is_none());
+        assert!(axial(0, -1).to_offset().is_none());
+        assert!(axial(game_constants::MAX_BOARD_WIDTH as i32, 0).to_offset().is_none());
+    }
+
+    // The odd columns are half a space higher than the even ones, so the spaces to the side are one row up from an odd column.
+    #[test]
+    fn offset_neighbors() {
+        let even_column = pos(2, 3);
+        assert!(even_column.up() == Some(pos(2, 4)));
+        assert!(even_column.up_right() == Some(pos(3, 3)));
+        assert!(even_column.down_right() == Some(pos(3, 2)));
+        assert!(even_column.down() == Some(pos(2, 2)));
+        assert!(even_column.down_left() == Some(pos(1, 2)));
+        assert!(even_column.up_left() == Some(pos(1, 3)));
+
+        let odd_column = pos(3, 3);
+        assert!(odd_column.up() == Some(pos(3, 4)));
+        assert!(odd_column.up_right() == Some(pos(4, 4)));
+        assert!(odd_column.down_right() == Some(pos(4, 3)));
+        assert!(odd_column.down() == Some(pos(3, 2)));
+        assert!(odd_column.down_left() == Some(pos(2, 3)));
+        assert!(odd_column.up_left() == Some(pos(2, 4)));
+
+        // Nothing off the edges of the board.
+        assert!(pos(0, 0).down_left().is_none());
+        assert!(pos(0, 0).down().is_none());
+        assert!(pos(0, 0).up_right() == Some(pos(1, 0)));
+        assert!(pos(1, 6).up_right().is_none());
+        assert!(pos(12, 0).down_right().is_none());
+    }
+
+    #[test]
+    fn distance() {
+        let center = axial(2, -1);
+        assert_eq!(center.distance_to(center), 0);
+        for &direction in HexDirection::ALL.iter() {
+            assert_eq!(center.distance_to(center.neighbor(direction)), 1);
+            assert_eq!(center.distance_to(center + direction.step() * 4), 4);
+        }
+        assert_eq!(axial(0, 0).distance_to(axial(3, -1)), 3);
+        assert_eq!(axial(0, 0).distance_to(axial(2, 2)), 4);
+        assert_eq!(axial(2, 2).distance_to(axial(0, 0)), 4);
+    }
+
+    #[test]
+    fn ring() {
+        let center = axial(1, 2);
+        assert_eq!(center.ring(0).collect::<Vec<_>>(), vec![center]);
+        for radius in 1..5 {
+            let ring: Vec<AxialPos> = center.ring(radius).collect();
+            assert_eq!(ring.len(), 6 * radius as usize);
+            assert_eq!(ring.iter().cloned().collect::<HashSet<_>>().len(), ring.len());
+            assert!(ring.iter().all(|position| center.distance_to(*position) == radius));
+            // Going around the ring, each position is next to the last one, and the last is next to the first.
+            for (index, position) in ring.iter().enumerate() {
+                assert_eq!(position.distance_to(ring[(index + 1) % ring.len()]), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn spiral() {
+        let center = axial(-2, 3);
+        let spiral: Vec<AxialPos> = center.spiral(3).collect();
+        // 1 + 6 + 12 + 18 positions, starting at the center and never getting closer again.
+        assert_eq!(spiral.len(), 37);
+        assert_eq!(spiral[0], center);
+        assert_eq!(spiral.iter().cloned().collect::<HashSet<_>>().len(), spiral.len());
+        for pair in spiral.windows(2) {
+            assert!(center.distance_to(pair[0]) <= center.distance_to(pair[1]));
+        }
+        assert_eq!(center.spiral(0).collect::<Vec<_>>(), vec![center]);
+    }
+
+    #[test]
+    fn line_to() {
+        let start = axial(0, 0);
+        assert_eq!(start.line_to(start), vec![start]);
+        for &end in [axial(5, -2), axial(-3, 4), axial(0, 6), axial(2, 2)].iter() {
+            let line = start.line_to(end);
+            assert_eq!(line.len() as u32, start.distance_to(end) + 1);
+            assert_eq!(line[0], start);
+            assert_eq!(*line.last().unwrap(), end);
+            for pair in line.windows(2) {
+                assert_eq!(pair[0].distance_to(pair[1]), 1);
+            }
+        }
+        // Straight along one direction.
+        assert_eq!(start.line_to(axial(3, 0)), vec![axial(0, 0), axial(1, 0), axial(2, 0), axial(3, 0)]);
+    }
+
+    #[test]
+    fn rotate() {
+        let center = axial(1, -1);
+        let position = axial(4, 0);
+        let mut rotated = position;
+        for _ in 0..6 {
+            let next = rotated.rotate_clockwise(center);
+            assert_eq!(center.distance_to(next), center.distance_to(position));
+            assert_eq!(next.rotate_counter_clockwise(center), rotated);
+            rotated = next;
+        }
+        assert_eq!(rotated, position);
+
+        // Clockwise turns each direction into the next one.
+        for (index, &direction) in HexDirection::ALL.iter().enumerate() {
+            let next_direction = HexDirection::ALL[(index + 1) % 6];
+            assert_eq!(center.neighbor(direction).rotate_clockwise(center), center.neighbor(next_direction));
+        }
+    }
+
+    #[test]
+    fn round() {
+        assert_eq!(AxialPos::round(2.0, -1.0), axial(2, -1));
+        assert_eq!(AxialPos::round(2.2, -0.9), axial(2, -1));
+        assert_eq!(AxialPos::round(-0.4, 0.45), axial(0, 0));
+        // Rounding q and r separately would give (1, 1), which is further away than (0, 1).
+        assert_eq!(AxialPos::round(0.45, 0.45), axial(0, 1));
+        assert_eq!(AxialPos::round(-0.3, -1.4), axial(0, -2));
+    }
+}
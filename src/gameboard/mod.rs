@@ -1,2 +1,8 @@
+pub mod combat;
+pub mod deck;
 pub mod gameboard;
 pub mod gameboard_drawing;
+pub mod gameboard_text;
+pub mod hex;
+pub mod pathfinding;
+pub mod territory;
@@ -0,0 +1,264 @@
+use gameboard::gameboard::{GameBoard,GameBoardSpacePos};
+use gameboard::hex::AxialPos;
+use PlayerColor;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap,HashMap,HashSet,VecDeque};
+
+// Hex grid queries: distances, paths, and reachable or connected groups of spaces.
+// The search functions take a "passable" closure, so the same searches work for knights, board setup, territory, etc.
+
+impl GameBoardSpacePos {
+    // Number of steps between two spaces, ignoring what is on the board.
+    pub fn distance_to(&self, other_pos: GameBoardSpacePos) -> u32 {
+        hex_distance(*self, other_pos)
+    }
+}
+
+pub fn hex_distance(from_pos: GameBoardSpacePos, to_pos: GameBoardSpacePos) -> u32 {
+    AxialPos::from_offset(from_pos).distance_to(AxialPos::from_offset(to_pos))
+}
+
+// Find a shortest path from from_pos to to_pos, stepping only onto spaces where passable returns true.
+// The returned path starts with from_pos and ends with to_pos.  from_pos itself doesn't need to be passable.
+pub fn find_path<F>(from_pos: GameBoardSpacePos, to_pos: GameBoardSpacePos, passable: F) -> Option<Vec<GameBoardSpacePos>>
+    where F: Fn(GameBoardSpacePos) -> bool
+{
+    #[derive(PartialEq, Eq)]
+    struct SearchNode {
+        estimated_cost: u32,
+        cost_so_far: u32,
+        position: GameBoardSpacePos
+    }
+
+    // BinaryHeap is a max-heap, so order the nodes backwards to pop the cheapest one first.
+    impl Ord for SearchNode {
+        fn cmp(&self, other: &SearchNode) -> Ordering {
+            other.estimated_cost.cmp(&self.estimated_cost)
+                .then_with(|| self.cost_so_far.cmp(&other.cost_so_far))
+        }
+    }
+    impl PartialOrd for SearchNode {
+        fn partial_cmp(&self, other: &SearchNode) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    // A* search, using the hex distance as the (never too high) estimate of the remaining cost.
+    let mut came_from: HashMap<GameBoardSpacePos, GameBoardSpacePos> = HashMap::new();
+    let mut best_cost: HashMap<GameBoardSpacePos, u32> = HashMap::new();
+    let mut frontier = BinaryHeap::new();
+
+    best_cost.insert(from_pos, 0);
+    frontier.push(SearchNode { estimated_cost: hex_distance(from_pos, to_pos), cost_so_far: 0, position: from_pos });
+
+    while let Some(SearchNode { estimated_cost: _, cost_so_far, position }) = frontier.pop() {
+        if position == to_pos {
+            let mut path = vec![to_pos];
+            let mut current_pos = to_pos;
+            while let Some(&previous_pos) = came_from.get(&current_pos) {
+                path.push(previous_pos);
+                current_pos = previous_pos;
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        // Skip stale entries for spaces that have since been reached more cheaply.
+        if cost_so_far > best_cost[&position] {
+            continue;
+        }
+
+        for neighbor in position.all_neighboring_positions() {
+            if !passable(neighbor) {
+                continue;
+            }
+            let neighbor_cost = cost_so_far + 1;
+            if best_cost.get(&neighbor).map_or(true, |&cost| neighbor_cost < cost) {
+                best_cost.insert(neighbor, neighbor_cost);
+                came_from.insert(neighbor, position);
+                frontier.push(SearchNode {
+                    estimated_cost: neighbor_cost + hex_distance(neighbor, to_pos),
+                    cost_so_far: neighbor_cost,
+                    position: neighbor
+                });
+            }
+        }
+    }
+
+    None
+}
+
+// Breadth-first search outwards from from_pos, up to max_steps steps, stepping only onto passable spaces.
+// Returns every space that was reached, with the number of steps it takes to get there.  from_pos is included, at 0 steps.
+pub fn reachable_within<F>(from_pos: GameBoardSpacePos, max_steps: u32, passable: F) -> HashMap<GameBoardSpacePos, u32>
+    where F: Fn(GameBoardSpacePos) -> bool
+{
+    let mut steps_to_reach = HashMap::new();
+    let mut frontier = VecDeque::new();
+
+    steps_to_reach.insert(from_pos, 0);
+    frontier.push_back(from_pos);
+
+    while let Some(position) = frontier.pop_front() {
+        let steps = steps_to_reach[&position];
+        if steps >= max_steps {
+            continue;
+        }
+        for neighbor in position.all_neighboring_positions() {
+            if !steps_to_reach.contains_key(&neighbor) && passable(neighbor) {
+                steps_to_reach.insert(neighbor, steps + 1);
+                frontier.push_back(neighbor);
+            }
+        }
+    }
+
+    steps_to_reach
+}
+
+// Flood fill from start_pos over the spaces where belongs returns true.
+// Returns an empty group if start_pos itself doesn't belong.
+pub fn connected_territory<F>(start_pos: GameBoardSpacePos, belongs: F) -> Vec<GameBoardSpacePos>
+    where F: Fn(GameBoardSpacePos) -> bool
+{
+    if !belongs(start_pos) {
+        return Vec::new();
+    }
+
+    let mut visited = HashSet::new();
+    let mut territory = Vec::new();
+    let mut unexplored = vec![start_pos];
+    visited.insert(start_pos);
+
+    while let Some(position) = unexplored.pop() {
+        territory.push(position);
+        for neighbor in position.all_neighboring_positions() {
+            if !visited.contains(&neighbor) && belongs(neighbor) {
+                visited.insert(neighbor);
+                unexplored.push(neighbor);
+            }
+        }
+    }
+
+    territory
+}
+
+// Split a set of spaces up into groups of spaces that are connected to each other.
+pub fn connected_regions(positions: &[GameBoardSpacePos]) -> Vec<Vec<GameBoardSpacePos>> {
+    let members: HashSet<GameBoardSpacePos> = positions.iter().cloned().collect();
+    let mut assigned: HashSet<GameBoardSpacePos> = HashSet::new();
+    let mut regions = Vec::new();
+
+    for &position in positions {
+        if assigned.contains(&position) {
+            continue;
+        }
+        let region = connected_territory(position, |other_pos| members.contains(&other_pos));
+        assigned.extend(region.iter().cloned());
+        regions.push(region);
+    }
+
+    regions
+}
+
+impl GameBoard {
+    // Shortest path for a knight, where every space stepped onto must be ok for the knight (see space_ok_for_knight).
+    pub fn knight_path(&self, from_pos: GameBoardSpacePos, to_pos: GameBoardSpacePos, owner: PlayerColor) -> Option<Vec<GameBoardSpacePos>> {
+        find_path(from_pos, to_pos, |position| self.space_ok_for_knight(position, owner))
+    }
+
+    // Every space that a knight at from_pos could get to in max_steps steps or fewer, not counting from_pos.
+    pub fn knight_reachable_spaces(&self, from_pos: GameBoardSpacePos, owner: PlayerColor, max_steps: u32) -> HashSet<GameBoardSpacePos> {
+        reachable_within(from_pos, max_steps, |position| self.space_ok_for_knight(position, owner))
+            .into_iter()
+            .filter(|&(position, _)| position != from_pos)
+            .map(|(position, _)| position)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gameboard::gameboard::GameBoardSpaceType;
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    // The left column of the board as plains, and nothing anywhere else.
+    fn column_board() -> GameBoard {
+        let mut game_board = GameBoard::new();
+        for y_pos in 0..7 {
+            game_board.set_board_space_type(pos(0, y_pos), GameBoardSpaceType::Plains);
+        }
+        game_board
+    }
+
+    fn positions(list: &[(u8, u8)]) -> HashSet<GameBoardSpacePos> {
+        list.iter().map(|&(x_pos, y_pos)| pos(x_pos, y_pos)).collect()
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(pos(2, 3).distance_to(pos(2, 3)), 0);
+        assert_eq!(pos(0, 0).distance_to(pos(0, 3)), 3);
+        assert_eq!(pos(0, 0).distance_to(pos(4, 0)), 4);
+        for neighbor in pos(5, 3).all_neighboring_positions() {
+            assert_eq!(hex_distance(pos(5, 3), neighbor), 1);
+        }
+    }
+
+    #[test]
+    fn paths_are_shortest() {
+        let path = find_path(pos(0, 0), pos(8, 5), |_| true).unwrap();
+        assert!(path.first() == Some(&pos(0, 0)));
+        assert!(path.last() == Some(&pos(8, 5)));
+        assert_eq!(path.len() as u32, hex_distance(pos(0, 0), pos(8, 5)) + 1);
+        for step in path.windows(2) {
+            assert!(step[0].is_neighbor(step[1]));
+        }
+        assert!(find_path(pos(3, 3), pos(3, 3), |_| false) == Some(vec![pos(3, 3)]));
+    }
+
+    #[test]
+    fn move_range() {
+        let game_board = column_board();
+        // The start space isn't counted, and nothing off the plains is reached.
+        assert!(game_board.knight_reachable_spaces(pos(0, 0), PlayerColor::Red, 2) == positions(&[(0, 1), (0, 2)]));
+        assert!(game_board.knight_reachable_spaces(pos(0, 3), PlayerColor::Red, 1) == positions(&[(0, 2), (0, 4)]));
+        assert!(game_board.knight_reachable_spaces(pos(0, 3), PlayerColor::Red, 0).is_empty());
+        assert_eq!(game_board.knight_path(pos(0, 0), pos(0, 6), PlayerColor::Red).map(|path| path.len()), Some(7));
+    }
+
+    #[test]
+    fn water_blocks_knights() {
+        let mut game_board = column_board();
+        game_board.set_board_space_type(pos(0, 2), GameBoardSpaceType::Water);
+        assert!(game_board.knight_reachable_spaces(pos(0, 0), PlayerColor::Red, 6) == positions(&[(0, 1)]));
+        assert!(game_board.knight_path(pos(0, 0), pos(0, 4), PlayerColor::Red).is_none());
+    }
+
+    #[test]
+    fn defended_mountains_block_knights() {
+        let mut game_board = column_board();
+        game_board.set_board_space_type(pos(0, 2), GameBoardSpaceType::Mountain);
+        assert!(game_board.knight_reachable_spaces(pos(0, 0), PlayerColor::Red, 3) == positions(&[(0, 1), (0, 2), (0, 3)]));
+
+        // Two defenders are enough to hold any space.
+        game_board.add_knight(pos(0, 2), PlayerColor::Blue).unwrap();
+        game_board.add_knight(pos(0, 2), PlayerColor::Blue).unwrap();
+        assert!(game_board.knight_reachable_spaces(pos(0, 0), PlayerColor::Red, 3) == positions(&[(0, 1)]));
+        assert!(game_board.knight_path(pos(0, 0), pos(0, 3), PlayerColor::Red).is_none());
+        // The mountain's owner can still go through.
+        assert!(game_board.knight_path(pos(0, 0), pos(0, 3), PlayerColor::Blue).is_some());
+    }
+
+    #[test]
+    fn regions() {
+        let regions = connected_regions(&[pos(0, 0), pos(0, 1), pos(5, 5), pos(0, 2)]);
+        assert_eq!(regions.len(), 2);
+        assert!(regions[0].iter().cloned().collect::<HashSet<_>>() == positions(&[(0, 0), (0, 1), (0, 2)]));
+        assert!(regions[1] == vec![pos(5, 5)]);
+        assert!(connected_territory(pos(0, 0), |_| false).is_empty());
+    }
+}
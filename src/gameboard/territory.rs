@@ -0,0 +1,124 @@
+use gameboard::gameboard::{GameBoard,GameBoardSpacePos};
+use gameboard::pathfinding;
+use PlayerColor;
+use std::collections::HashMap;
+
+// Who controls which spaces on the board.
+//
+// A space with a city, village or stronghold is controlled by the owner of that building.
+// Otherwise, a space is controlled by the owner of the knights on it, as long as nobody else has knights there too.
+// Empty and contested spaces aren't controlled by anyone.
+
+impl GameBoard {
+    // Work out the controller of every controlled space on the board.
+    // Prefer this over calling space_controller() for every space, if you need to know about the whole board.
+    pub fn territory(&self) -> HashMap<GameBoardSpacePos, PlayerColor> {
+        let mut territory = HashMap::new();
+
+        // Knights first, so that buildings take priority over them below.
+        let mut knight_owners: HashMap<GameBoardSpacePos, Option<PlayerColor>> = HashMap::new();
+        for knight in self.knights() {
+            let entry = knight_owners.entry(knight.position).or_insert(Some(knight.owner));
+            if *entry != Some(knight.owner) {
+                // More than one player has knights here, so it's contested.
+                *entry = None;
+            }
+        }
+        for (position, owner) in knight_owners {
+            if let Some(owner) = owner {
+                territory.insert(position, owner);
+            }
+        }
+
+        for building in self.cities().chain(self.villages()).chain(self.strongholds()) {
+            territory.insert(building.position, building.owner);
+        }
+
+        territory
+    }
+
+    // Returns the player that controls this space, or None if it is empty or contested.
+    pub fn space_controller(&self, position: GameBoardSpacePos) -> Option<PlayerColor> {
+        self.territory().get(&position).cloned()
+    }
+
+    pub fn controlled_spaces(&self, owner: PlayerColor) -> Vec<GameBoardSpacePos> {
+        self.territory().into_iter()
+            .filter(|&(_, controller)| controller == owner)
+            .map(|(position, _)| position)
+            .collect()
+    }
+
+    // The player's controlled spaces, split up into groups of spaces that are connected to each other.
+    pub fn controlled_regions(&self, owner: PlayerColor) -> Vec<Vec<GameBoardSpacePos>> {
+        pathfinding::connected_regions(&self.controlled_spaces(owner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gameboard::gameboard::GameBoardSpaceType;
+
+    fn pos(x_pos: u8, y_pos: u8) -> GameBoardSpacePos {
+        GameBoardSpacePos { x_pos: x_pos, y_pos: y_pos }
+    }
+
+    // The left column of the board as plains, and nothing anywhere else.
+    fn column_board() -> GameBoard {
+        let mut game_board = GameBoard::new();
+        for y_pos in 0..7 {
+            game_board.set_board_space_type(pos(0, y_pos), GameBoardSpaceType::Plains);
+        }
+        game_board
+    }
+
+    fn sorted(mut positions: Vec<GameBoardSpacePos>) -> Vec<GameBoardSpacePos> {
+        positions.sort_by_key(|position| (position.x_pos, position.y_pos));
+        positions
+    }
+
+    #[test]
+    fn knights_and_contested_spaces() {
+        let mut game_board = column_board();
+        game_board.add_knight(pos(0, 0), PlayerColor::Red).unwrap();
+        game_board.add_knight(pos(0, 0), PlayerColor::Red).unwrap();
+        game_board.add_knight(pos(0, 2), PlayerColor::Red).unwrap();
+        game_board.add_knight(pos(0, 2), PlayerColor::Blue).unwrap();
+        game_board.add_knight(pos(0, 4), PlayerColor::Blue).unwrap();
+        assert_eq!(game_board.space_controller(pos(0, 0)), Some(PlayerColor::Red));
+        assert_eq!(game_board.space_controller(pos(0, 1)), None);
+        assert_eq!(game_board.space_controller(pos(0, 2)), None);
+        assert_eq!(sorted(game_board.controlled_spaces(PlayerColor::Red)), vec![pos(0, 0)]);
+        assert_eq!(sorted(game_board.controlled_spaces(PlayerColor::Blue)), vec![pos(0, 4)]);
+        assert!(game_board.controlled_spaces(PlayerColor::Green).is_empty());
+    }
+
+    #[test]
+    fn buildings_override_knights() {
+        let mut game_board = column_board();
+        game_board.add_city(pos(0, 1), PlayerColor::Red).unwrap();
+        game_board.add_knight(pos(0, 1), PlayerColor::Blue).unwrap();
+        game_board.add_knight(pos(0, 3), PlayerColor::Blue).unwrap();
+        game_board.add_knight(pos(0, 3), PlayerColor::Red).unwrap();
+        game_board.add_village(pos(0, 3), PlayerColor::Blue).unwrap();
+        assert_eq!(game_board.space_controller(pos(0, 1)), Some(PlayerColor::Red));
+        assert_eq!(game_board.space_controller(pos(0, 3)), Some(PlayerColor::Blue));
+        assert_eq!(sorted(game_board.controlled_spaces(PlayerColor::Red)), vec![pos(0, 1)]);
+        assert_eq!(sorted(game_board.controlled_spaces(PlayerColor::Blue)), vec![pos(0, 3)]);
+    }
+
+    #[test]
+    fn regions() {
+        let mut game_board = column_board();
+        for &y_pos in [0, 1, 2, 4, 5].iter() {
+            game_board.add_knight(pos(0, y_pos), PlayerColor::Red).unwrap();
+        }
+        // A contested space doesn't join the regions on either side of it.
+        game_board.add_knight(pos(0, 1), PlayerColor::Blue).unwrap();
+        let mut regions: Vec<Vec<GameBoardSpacePos>> = game_board.controlled_regions(PlayerColor::Red).into_iter().map(sorted).collect();
+        regions.sort_by_key(|region| region[0].y_pos);
+        assert_eq!(regions, vec![vec![pos(0, 0)], vec![pos(0, 2)], vec![pos(0, 4), pos(0, 5)]]);
+        assert!(game_board.controlled_regions(PlayerColor::Blue).is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+use actions::PlayerActionType;
+use colors::Color;
+use drawing;
+use fonts;
+use gl;
+use localization;
+use render_gl;
+use PlayerColor;
+
+// Hotseat play, where the players share one screen and pass the mouse around.
+// Between turns, the screen is covered up until the next player clicks, so nobody sees another player's inventory.
+// The cover shows what the other players did since the next player's last turn.
+
+// What one player did on one turn.
+#[derive(Clone)]
+pub struct TurnSummary {
+    pub player: PlayerColor,
+    pub actions: Vec<PlayerActionType>,
+    // Things that happened on the board, like battles and new buildings.
+    pub events: Vec<String>
+}
+
+impl TurnSummary {
+    pub fn new(player: PlayerColor) -> TurnSummary {
+        TurnSummary {
+            player: player,
+            actions: Vec::new(),
+            events: Vec::new()
+        }
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.actions.is_empty() {
+            lines.push(localization::format("handoff_no_actions", &[&self.player.name()]));
+        } else {
+            let action_names: Vec<String> = self.actions.iter().map(|action_type| action_type.name()).collect();
+            lines.push(format!("{}: {}", self.player.name(), action_names.join(", ")));
+        }
+        for event in self.events.iter() {
+            lines.push(format!("    {}", event));
+        }
+        lines
+    }
+}
+
+pub struct Handoff {
+    pub next_player: PlayerColor,
+    // The other players' turns since next_player's last turn, oldest first.
+    pub summaries: Vec<TurnSummary>
+}
+
+const MAX_SUMMARY_LINES: usize = 14;
+
+pub fn draw_handoff_screen(
+    gl: &gl::Gl,
+    shader_program: &render_gl::Program,
+    baggage: &mut drawing::TextDrawingBaggage,
+    handoff: &Handoff,
+    aspect_ratio: f32)
+{
+    // Cover the whole window, in screen coordinates.
+    drawing::write_scale_data(gl, shader_program, 1.0);
+    drawing::draw_rectangle(
+        gl,
+        shader_program,
+        drawing::RectangleSpec {
+            color: drawing::ColorSpec { r: 0x10, g: 0x10, b: 0x10 },
+            pos: drawing::PositionSpec { x: -1.0, y: -1.0 },
+            size: drawing::SizeSpec { x: 2.0, y: 2.0 }});
+    drawing::write_scale_data(gl, shader_program, aspect_ratio);
+
+    drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.6 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Banner, handoff.next_player.color(),
+        localization::format("handoff_pass_to", &[&handoff.next_player.name()]));
+    drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.48 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+        localization::format("handoff_click_when_ready", &[&handoff.next_player.name()]));
+
+    let mut lines: Vec<String> = handoff.summaries.iter().flat_map(|summary| summary.lines()).collect();
+    if lines.len() > MAX_SUMMARY_LINES {
+        // Keep the latest lines.
+        let num_hidden_lines = lines.len() - MAX_SUMMARY_LINES;
+        lines.drain(0..num_hidden_lines);
+    }
+
+    if !lines.is_empty() {
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.32 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("handoff_since_last_turn"));
+        let mut y_pos = 0.24;
+        for line in lines {
+            drawing::draw_text(baggage, drawing::PositionSpec{ x: -0.6, y: y_pos }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Small, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                line);
+            y_pos -= 0.056;
+        }
+    }
+}
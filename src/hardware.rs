@@ -10,7 +10,7 @@ pub struct HardwareResources
     _gl_context: sdl2::video::GLContext,
     pub gl: gl::Gl,
     pub timer_subsystem: sdl2::TimerSubsystem,
-    _audio_subsystem: sdl2::AudioSubsystem
+    pub audio_subsystem: Option<sdl2::AudioSubsystem>
 }
 
 impl HardwareResources {
@@ -97,39 +97,15 @@ impl HardwareResources {
         // SDL_GetTicks
         let timer_subsystem = sdl.timer().unwrap();
 
-        struct AudioEngine {
-            sample_number: u128
-        }
-
-        impl sdl2::audio::AudioCallback for AudioEngine {
-            type Channel = f32;
-
-            fn callback(&mut self, out: &mut [f32]) {
-                for x in out.iter_mut() {
-                    // This is where audio output will go.
-                    *x = 0.0;
-                    self.sample_number = self.sample_number + 1;
-                }
-            }
-        }
-
         // SDL_AudioInit
-        let audio_subsystem = sdl.audio().unwrap();
-        let desired_spec = sdl2::audio::AudioSpecDesired {
-            freq: Some(44100),
-            channels: Some(1), //mono
-            samples: None // device default sample buffer size
-        };
-
-        let audio_device = audio_subsystem.open_playback(None, &desired_spec, |_spec| {
-            AudioEngine {
-                sample_number: 0
+        // The audio device is opened by the mixer (see audio.rs).  The game still runs without sound if this fails.
+        let audio_subsystem = match sdl.audio() {
+            Ok(audio_subsystem) => Some(audio_subsystem),
+            Err(e) => {
+                println!("Could not start SDL audio: {}", e);
+                None
             }
-        }).unwrap();
-
-        println!("Audio device buffer size: {} samples", audio_device.spec().samples);
-
-        audio_device.resume();
+        };
 
         HardwareResources {
             sdl: sdl,
@@ -140,7 +116,7 @@ impl HardwareResources {
             _gl_context: gl_context,
             gl: gl,
             timer_subsystem: timer_subsystem,
-            _audio_subsystem: audio_subsystem
+            audio_subsystem: audio_subsystem
         }
     }
 }
@@ -1,89 +1,83 @@
-use colors::Color;
+use colors::{self,Color,Emblem,Palette};
+use drawing::Texture;
+use gl;
+use std;
 use std::collections::HashMap;
 use std::path::Path;
 use PlayerColor;
 
+// The SVG images are rasterized and uploaded to the GPU once, when the game starts.
+// Each piece gets a texture per player, tinted with the player's color and marked with the player's emblem.
+// The textures are tinted again when the palette changes.
 
 pub struct SVGImages {
-    city_images: HashMap<PlayerColor, nsvg::image::RgbaImage>,
-    village_images: HashMap<PlayerColor, nsvg::image::RgbaImage>,
-    stronghold_images: HashMap<PlayerColor, nsvg::image::RgbaImage>,
-    knight_images: HashMap<PlayerColor, nsvg::image::RgbaImage>,
-    pub scroll_image: nsvg::image::RgbaImage
+    // The untinted pieces, kept for when the palette changes.
+    city_image: nsvg::image::RgbaImage,
+    village_image: nsvg::image::RgbaImage,
+    stronghold_image: nsvg::image::RgbaImage,
+    knight_image: nsvg::image::RgbaImage,
+    palette: Palette,
+    city_images: HashMap<PlayerColor, Texture>,
+    village_images: HashMap<PlayerColor, Texture>,
+    stronghold_images: HashMap<PlayerColor, Texture>,
+    knight_images: HashMap<PlayerColor, Texture>,
+    pub scroll_image: Texture
 }
 
-impl SVGImages {
-    pub fn new(ddpi: f32, window_width: u32) -> SVGImages {
-        const PLAYER_COLORS: [PlayerColor; 4] = [PlayerColor::Red, PlayerColor::Blue, PlayerColor::Green, PlayerColor::Yellow];
-        let mut city_images = HashMap::new();
-        let city_image = {
-            let svg = nsvg::parse_file(Path::new("assets/svg/city.svg"), nsvg::Units::Pixel, ddpi).unwrap();
-            let svg_scaling = svg.width() * 2.0 / window_width as f32;
-            svg.rasterize(svg_scaling).unwrap()
-        };
-        for player_color in PLAYER_COLORS.iter() {
-            let player_color_spec = player_color.color();
-            let mut new_city_image = city_image.clone();
-            for pixel in new_city_image.pixels_mut() {
-                let old_pixel = pixel.clone();
-                pixel.data = [player_color_spec.r, player_color_spec.g, player_color_spec.b, old_pixel.data[3]];
-            }
-            city_images.insert(*player_color, new_city_image);
-        }
+fn rasterize_svg(path: &str, ddpi: f32, window_width: u32) -> nsvg::image::RgbaImage {
+    let svg = nsvg::parse_file(Path::new(path), nsvg::Units::Pixel, ddpi).unwrap();
+    let svg_scaling = svg.width() * 2.0 / window_width as f32;
+    svg.rasterize(svg_scaling).unwrap()
+}
 
-        let mut village_images = HashMap::new();
-        let village_image = {
-            let svg = nsvg::parse_file(Path::new("assets/svg/village.svg"), nsvg::Units::Pixel, ddpi).unwrap();
-            let svg_scaling = svg.width() * 2.0 / window_width as f32;
-            svg.rasterize(svg_scaling).unwrap()
-        };
-        for player_color in PLAYER_COLORS.iter() {
-            let player_color_spec = player_color.color();
-            let mut new_village_image = village_image.clone();
-            for pixel in new_village_image.pixels_mut() {
-                let old_pixel = pixel.clone();
-                pixel.data = [player_color_spec.r, player_color_spec.g, player_color_spec.b, old_pixel.data[3]];
-            }
-            village_images.insert(*player_color, new_village_image);
+fn player_textures(gl: &gl::Gl, image: &nsvg::image::RgbaImage) -> HashMap<PlayerColor, Texture> {
+    const PLAYER_COLORS: [PlayerColor; 4] = [PlayerColor::Red, PlayerColor::Blue, PlayerColor::Green, PlayerColor::Yellow];
+    let mut textures = HashMap::new();
+    for player_color in PLAYER_COLORS.iter() {
+        let player_color_spec = player_color.color();
+        let mut new_image = image.clone();
+        for pixel in new_image.pixels_mut() {
+            let old_pixel = pixel.clone();
+            pixel.data = [player_color_spec.r, player_color_spec.g, player_color_spec.b, old_pixel.data[3]];
         }
+        stamp_emblem(&mut new_image, player_color.emblem());
+        textures.insert(*player_color, Texture::from_image(gl, &new_image));
+    }
+    textures
+}
 
-        let mut stronghold_images = HashMap::new();
-        let stronghold_image = {
-            let svg = nsvg::parse_file(Path::new("assets/svg/stronghold.svg"), nsvg::Units::Pixel, ddpi).unwrap();
-            let svg_scaling = svg.width() * 2.0 / window_width as f32;
-            svg.rasterize(svg_scaling).unwrap()
-        };
-        for player_color in PLAYER_COLORS.iter() {
-            let player_color_spec = player_color.color();
-            let mut new_stronghold_image = stronghold_image.clone();
-            for pixel in new_stronghold_image.pixels_mut() {
-                let old_pixel = pixel.clone();
-                pixel.data = [player_color_spec.r, player_color_spec.g, player_color_spec.b, old_pixel.data[3]];
+// Draw an emblem in the bottom right corner of an image, light with a dark outline so that it shows on any color.
+fn stamp_emblem(image: &mut nsvg::image::RgbaImage, emblem: Emblem) {
+    let size = (std::cmp::min(image.width(), image.height()) as f32 * 0.4) as u32;
+    if size < 4 {
+        return;
+    }
+    let outline_width = 2.0 / size as f32 * 2.0;
+    let left = image.width() - size;
+    let top = image.height() - size;
+    for y in 0..size {
+        for x in 0..size {
+            // Emblem coordinates, from -1 to 1, with y going up.
+            let emblem_x = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+            let emblem_y = 1.0 - (y as f32 + 0.5) / size as f32 * 2.0;
+            if !emblem.contains(emblem_x, emblem_y) {
+                continue;
             }
-            stronghold_images.insert(*player_color, new_stronghold_image);
+            let inner = emblem.contains(emblem_x / (1.0 - outline_width), emblem_y / (1.0 - outline_width));
+            let pixel = image.get_pixel_mut(left + x, top + y);
+            pixel.data = if inner { [0xF0, 0xF0, 0xF0, 0xFF] } else { [0x10, 0x10, 0x10, 0xFF] };
         }
+    }
+}
 
-        let mut knight_images = HashMap::new();
-        let knight_image = {
-            let svg = nsvg::parse_file(Path::new("assets/svg/knight.svg"), nsvg::Units::Pixel, ddpi).unwrap();
-            let svg_scaling = svg.width() * 2.0 / window_width as f32;
-            svg.rasterize(svg_scaling).unwrap()
-        };
-        for player_color in PLAYER_COLORS.iter() {
-            let player_color_spec = player_color.color();
-            let mut new_knight_image = knight_image.clone();
-            for pixel in new_knight_image.pixels_mut() {
-                let old_pixel = pixel.clone();
-                pixel.data = [player_color_spec.r, player_color_spec.g, player_color_spec.b, old_pixel.data[3]];
-            }
-            knight_images.insert(*player_color, new_knight_image);
-        }
+impl SVGImages {
+    pub fn new(gl: &gl::Gl, ddpi: f32, window_width: u32) -> SVGImages {
+        let city_image = rasterize_svg("assets/svg/city.svg", ddpi, window_width);
+        let village_image = rasterize_svg("assets/svg/village.svg", ddpi, window_width);
+        let stronghold_image = rasterize_svg("assets/svg/stronghold.svg", ddpi, window_width);
+        let knight_image = rasterize_svg("assets/svg/knight.svg", ddpi, window_width);
 
-        let mut scroll_image = {
-            let svg = nsvg::parse_file(Path::new("assets/svg/paper-scroll.svg"), nsvg::Units::Pixel, ddpi).unwrap();
-            let svg_scaling = svg.width() * 2.0 / window_width as f32;
-            svg.rasterize(svg_scaling).unwrap()
-        };
+        let mut scroll_image = rasterize_svg("assets/svg/paper-scroll.svg", ddpi, window_width);
         for pixel in scroll_image.pixels_mut() {
             let old_pixel = pixel.clone();
             pixel.data = [
@@ -94,27 +88,44 @@ impl SVGImages {
         }
 
         SVGImages {
-            city_images: city_images,
-            village_images: village_images,
-            stronghold_images: stronghold_images,
-            knight_images: knight_images,
-            scroll_image: scroll_image
+            city_images: player_textures(gl, &city_image),
+            village_images: player_textures(gl, &village_image),
+            stronghold_images: player_textures(gl, &stronghold_image),
+            knight_images: player_textures(gl, &knight_image),
+            city_image: city_image,
+            village_image: village_image,
+            stronghold_image: stronghold_image,
+            knight_image: knight_image,
+            palette: colors::current_palette(),
+            scroll_image: Texture::from_image(gl, &scroll_image)
+        }
+    }
+
+    // Tint the pieces again if the palette has changed since they were last tinted.  Call once a frame.
+    pub fn update_palette(&mut self, gl: &gl::Gl) {
+        if self.palette == colors::current_palette() {
+            return;
         }
+        self.city_images = player_textures(gl, &self.city_image);
+        self.village_images = player_textures(gl, &self.village_image);
+        self.stronghold_images = player_textures(gl, &self.stronghold_image);
+        self.knight_images = player_textures(gl, &self.knight_image);
+        self.palette = colors::current_palette();
     }
 
-    pub fn get_city_image(&self, player_color_spec: &PlayerColor) -> &nsvg::image::RgbaImage {
+    pub fn get_city_image(&self, player_color_spec: &PlayerColor) -> &Texture {
         &self.city_images[player_color_spec]
     }
 
-    pub fn get_village_image(&self, player_color_spec: &PlayerColor) -> &nsvg::image::RgbaImage {
+    pub fn get_village_image(&self, player_color_spec: &PlayerColor) -> &Texture {
         &self.village_images[player_color_spec]
     }
 
-    pub fn get_stronghold_image(&self, player_color_spec: &PlayerColor) -> &nsvg::image::RgbaImage {
+    pub fn get_stronghold_image(&self, player_color_spec: &PlayerColor) -> &Texture {
         &self.stronghold_images[player_color_spec]
     }
 
-    pub fn get_knight_image(&self, player_color_spec: &PlayerColor) -> &nsvg::image::RgbaImage {
+    pub fn get_knight_image(&self, player_color_spec: &PlayerColor) -> &Texture {
         &self.knight_images[player_color_spec]
     }
 }
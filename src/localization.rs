@@ -0,0 +1,149 @@
+use filereader::FileReader;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+
+// Translations of the text the players see.
+//
+// Every string has an id, and each language has a file in assets/lang (e.g. lang/en.txt) with lines like
+//     recruit_how_many = Enter the number of knights to recruit.  Max: {}
+// Each {} is replaced by the next argument.  {0}, {1}, ... pick an argument by position instead, for languages that
+// need the words in a different order.
+// Ids missing from a language fall back to English, and then to the id itself, so a missing string is easy to spot.
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+struct Strings {
+    messages: HashMap<String, String>,
+    english_messages: HashMap<String, String>
+}
+
+thread_local! {
+    static STRINGS: RefCell<Strings> = RefCell::new(Strings {
+        messages: HashMap::new(),
+        english_messages: HashMap::new()
+    });
+}
+
+fn load_messages(filereader: &FileReader, language: &str) -> Option<HashMap<String, String>> {
+    let name = format!("lang/{}.txt", language);
+    let source = match filereader.load_string(&name) {
+        Ok(source) => source,
+        Err(e) => {
+            println!("Could not read {}: {:?}", name, e);
+            return None;
+        }
+    };
+
+    let mut messages = HashMap::new();
+    for line in source.lines() {
+        // Only whole-line comments, so that translations can use '#'.
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let mut id_and_text = line.splitn(2, '=');
+        let id = id_and_text.next().unwrap().trim();
+        match id_and_text.next() {
+            Some(text) => { messages.insert(id.to_string(), text.trim().to_string()); }
+            None => { println!("{}: ignoring line without a value: {}", name, line); }
+        }
+    }
+    Some(messages)
+}
+
+// Load a language (e.g. "en" or "de").  If it can't be loaded, the game stays in English.
+pub fn load_language(filereader: &FileReader, language: &str) {
+    let english_messages = load_messages(filereader, DEFAULT_LANGUAGE).unwrap_or(HashMap::new());
+    let messages = if language == DEFAULT_LANGUAGE {
+        english_messages.clone()
+    } else {
+        load_messages(filereader, language).unwrap_or(english_messages.clone())
+    };
+
+    STRINGS.with(|strings| {
+        *strings.borrow_mut() = Strings {
+            messages: messages,
+            english_messages: english_messages
+        };
+    });
+}
+
+pub fn text(id: &str) -> String {
+    STRINGS.with(|strings| {
+        let strings = strings.borrow();
+        match strings.messages.get(id).or_else(|| strings.english_messages.get(id)) {
+            Some(text) => text.clone(),
+            None => id.to_string()
+        }
+    })
+}
+
+// Look up a message and fill in its arguments.
+pub fn format(id: &str, args: &[&fmt::Display]) -> String {
+    fill_in(&text(id), args)
+}
+
+fn fill_in(template: &str, args: &[&fmt::Display]) -> String {
+    let mut result = String::new();
+    let mut next_arg = 0;
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        let close = match rest[open..].find('}') {
+            Some(close) => open + close,
+            None => {
+                result.push_str(&rest[open..]);
+                rest = "";
+                break;
+            }
+        };
+        let position = &rest[open + 1..close];
+        let arg_index = if position.is_empty() {
+            next_arg += 1;
+            Some(next_arg - 1)
+        } else {
+            position.parse::<usize>().ok()
+        };
+        match arg_index.and_then(|arg_index| args.get(arg_index)) {
+            Some(arg) => { result.push_str(&arg.to_string()); }
+            // Not a placeholder, or not enough arguments: leave it as it is.
+            None => { result.push_str(&rest[open..close + 1]); }
+        }
+        rest = &rest[close + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_in_order() {
+        assert_eq!(fill_in("{} has {} knights", &[&"Red", &3]), "Red has 3 knights");
+        assert_eq!(fill_in("No placeholders", &[&"Red"]), "No placeholders");
+    }
+
+    #[test]
+    fn numbered_placeholders() {
+        assert_eq!(fill_in("{1} before {0}", &[&"a", &"b"]), "b before a");
+        assert_eq!(fill_in("{0} and {0}", &[&"a"]), "a and a");
+        // Numbered placeholders don't use up the ones in order.
+        assert_eq!(fill_in("{1} {} {}", &[&"a", &"b"]), "b a b");
+    }
+
+    #[test]
+    fn left_alone() {
+        assert_eq!(fill_in("Unmatched { brace", &[&"a"]), "Unmatched { brace");
+        assert_eq!(fill_in("{} ends with {", &[&"a"]), "a ends with {");
+        assert_eq!(fill_in("{name} isn't a placeholder", &[&"a"]), "{name} isn't a placeholder");
+    }
+
+    #[test]
+    fn too_few_arguments() {
+        assert_eq!(fill_in("{} and {}", &[&"a"]), "a and {}");
+        assert_eq!(fill_in("{2}", &[&"a"]), "{2}");
+        assert_eq!(fill_in("{}", &[]), "{}");
+    }
+}
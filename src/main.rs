@@ -17,6 +17,7 @@ extern crate sdl2;
 
 extern crate freetype;
 extern crate glm;
+extern crate lewton;
 extern crate nsvg;
 extern crate rand;
 
@@ -37,26 +38,54 @@ pub mod filereader;
 
 // And some more
 pub mod actions;
+pub mod animation;
+pub mod audio;
+pub mod batch;
+pub mod camera;
 pub mod drawing;
+pub mod export;
 pub mod colors;
+pub mod economy;
 pub mod fonts;
 pub mod gameboard;
+pub mod handoff;
 pub mod hardware;
 pub mod images;
+pub mod localization;
 pub mod mouse_position;
-
+pub mod music;
+pub mod notifications;
+pub mod rules;
+pub mod settings;
+pub mod text;
+pub mod turn_timer;
+pub mod turns;
+
+use animation::Animations;
+use audio::{Audio,SoundEffect};
+use camera::Camera;
 use colors::Color;
-use mouse_position::{MousePos, mouse_pos_to_game_board_pos, mouse_pos_to_board_piece_destination};
-use rand::Rng;
+use economy::{ResourceType,Resources};
+use mouse_position::{MousePos, mouse_pos_to_drawing_pos, mouse_pos_to_game_board_pos, mouse_pos_to_board_piece_destination};
 use filereader::FileReader;
-use gameboard::gameboard::{BoardPiece,GameBoard,GameBoardSpaceType,GameBoardSpacePos,game_constants};
-use gameboard::gameboard_drawing::{drawing_constants,highlight_space_for_city_setup,highlight_spaces_for_board_setup,scaling_for_board,Draw};
+use gameboard::combat::{BattleReport,BuildingType};
+use gameboard::deck::{self,BoardPieceDeck};
+use gameboard::gameboard::{BoardPiece,BoardPieceOrientation,GameBoard,GameBoardSpaceType,GameBoardSpacePos,game_constants};
+use gameboard::gameboard_drawing::{clip_to_board_area,draw_board_animations,draw_piece_animations,draw_territory_overlay,BoardMesh,drawing_constants,highlight_space_for_city_setup,highlight_spaces_for_board_setup,scaling_for_board,Draw};
+use handoff::{Handoff,TurnSummary};
 use hardware::HardwareResources;
 use images::SVGImages;
+use music::MusicPhase;
+use notifications::{NotificationKind,NotificationLog};
+use rules::{BlockedReason,PieceType,RuleError};
+use settings::{BoardSetupMode,GameSettings};
+use std::cmp;
 use std::path::Path;
 use std::collections::HashMap;
+use turn_timer::{TimeoutAction,TurnTimer};
+use turns::TurnState;
 
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PlayerColor
 {
     Red = 0,
@@ -65,12 +94,58 @@ pub enum PlayerColor
     Yellow = 3
 }
 
+impl PlayerColor {
+    pub fn name(&self) -> String {
+        localization::text(match self {
+            PlayerColor::Red => "player_red",
+            PlayerColor::Blue => "player_blue",
+            PlayerColor::Green => "player_green",
+            PlayerColor::Yellow => "player_yellow"
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum NobleRank
+{
+    Lord,
+    Baron,
+    Count,
+    Marquis,
+    Duke
+}
+
+impl NobleRank {
+    fn name(&self) -> String {
+        localization::text(match self {
+            NobleRank::Lord => "rank_lord",
+            NobleRank::Baron => "rank_baron",
+            NobleRank::Count => "rank_count",
+            NobleRank::Marquis => "rank_marquis",
+            NobleRank::Duke => "rank_duke"
+        })
+    }
+
+    // The title above this one, or None if this is the highest title.
+    fn next(&self) -> Option<NobleRank> {
+        match self {
+            NobleRank::Lord => Some(NobleRank::Baron),
+            NobleRank::Baron => Some(NobleRank::Count),
+            NobleRank::Count => Some(NobleRank::Marquis),
+            NobleRank::Marquis => Some(NobleRank::Duke),
+            NobleRank::Duke => None
+        }
+    }
+}
+
 struct PlayerInventory
 {
     num_cities: u8,
     num_strongholds: u8,
     num_villages: u8,
-    num_knights: u8
+    num_knights: u8,
+    resources: Resources,
+    noble_rank: NobleRank
 }
 
 impl PlayerInventory {
@@ -80,38 +155,116 @@ impl PlayerInventory {
             num_strongholds: 2,
             num_villages: 14,
             num_knights: 7,
+            resources: economy::STARTING_RESOURCES,
+            noble_rank: NobleRank::Lord
+        }
+    }
+
+    fn count(&self, piece: PieceType) -> u8 {
+        match piece {
+            PieceType::Knight => self.num_knights,
+            PieceType::City => self.num_cities,
+            PieceType::Village => self.num_villages,
+            PieceType::Stronghold => self.num_strongholds
+        }
+    }
+
+    fn count_mut(&mut self, piece: PieceType) -> &mut u8 {
+        match piece {
+            PieceType::Knight => &mut self.num_knights,
+            PieceType::City => &mut self.num_cities,
+            PieceType::Village => &mut self.num_villages,
+            PieceType::Stronghold => &mut self.num_strongholds
         }
     }
+
+    // Returns the number of pieces that would be left after taking count of them.
+    fn check_available(&self, piece: PieceType, count: u8) -> Result<u8, RuleError> {
+        self.count(piece).checked_sub(count).ok_or(RuleError::InventoryExhausted { piece: piece })
+    }
+
+    // Take pieces out of the inventory to put them on the board, and pay for them.
+    // Nothing is taken if there aren't enough pieces or resources.
+    fn take(&mut self, piece: PieceType, count: u8, cost: &Resources) -> Result<(), RuleError> {
+        let remaining = self.check_available(piece, count)?;
+        if !self.resources.spend(cost) {
+            return Err(RuleError::CannotAfford { cost: *cost });
+        }
+        *self.count_mut(piece) = remaining;
+        Ok(())
+    }
+
+    // Pieces that have been removed from the board go back to the inventory.
+    fn put_back(&mut self, piece: PieceType, count: u8) {
+        *self.count_mut(piece) += count;
+    }
 }
 
 
 // UI data, for now, will be constructed in the main function, and passed by reference where needed.
 pub struct GameUIData {
     num_players: u8,
+    settings: GameSettings,
     game_board: GameBoard,
+    board_piece_deck: BoardPieceDeck,
     unplaced_board_pieces: std::vec::Vec<BoardPiece>,
+    board_piece_hand: std::vec::Vec<BoardPiece>,
+    selected_board_piece: usize,
+    board_piece_orientation: BoardPieceOrientation,
     player_inventories: HashMap<PlayerColor, PlayerInventory>,
     player_color: PlayerColor,
     three_pos_under_mouse: Option<(GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)>,
     one_pos_under_mouse: Option<GameBoardSpacePos>,
+    show_territory: bool,
+    notification_log: NotificationLog,
+    show_history: bool,
+    turn_state: TurnState,
+    // What the active player has done so far this turn, and the turns before it.
+    turn_summary: TurnSummary,
+    previous_turn_summaries: std::vec::Vec<TurnSummary>,
+    // Set while the screen is covered between turns.
+    handoff: Option<Handoff>,
+    turn_timer: TurnTimer,
+    camera: Camera,
+    animations: Animations,
+    audio: Audio,
 }
 
 impl GameUIData {
-    fn defaults() -> GameUIData {
+    fn new(settings: GameSettings, board_piece_deck: BoardPieceDeck, audio: Audio) -> GameUIData {
         let num_players = 2;
         let mut initial_player_inventories = HashMap::new();
         initial_player_inventories.insert(PlayerColor::Red, PlayerInventory::new());
         initial_player_inventories.insert(PlayerColor::Blue, PlayerInventory::new());
 
-        GameUIData {
+        let mut game_ui_data = GameUIData {
             num_players: num_players,
-            game_board: GameBoard::new(),
-            unplaced_board_pieces: game_constants::BOARD_PIECES.to_vec(),
+            game_board: GameBoard::with_combat_rules(settings.combat_rules),
+            settings: settings,
+            unplaced_board_pieces: board_piece_deck.pieces().to_vec(),
+            board_piece_deck: board_piece_deck,
+            board_piece_hand: std::vec::Vec::new(),
+            selected_board_piece: 0,
+            board_piece_orientation: BoardPieceOrientation::A,
             player_inventories: initial_player_inventories,
             player_color: PlayerColor::Red,
             three_pos_under_mouse: None,
-            one_pos_under_mouse: None
-        }
+            one_pos_under_mouse: None,
+            show_territory: false,
+            notification_log: NotificationLog::new(),
+            show_history: false,
+            turn_state: TurnState::new(),
+            turn_summary: TurnSummary::new(PlayerColor::Red),
+            previous_turn_summaries: std::vec::Vec::new(),
+            handoff: None,
+            turn_timer: TurnTimer::new(),
+            camera: Camera::new(),
+            animations: Animations::new(),
+            audio: audio
+        };
+        game_ui_data.setup_board_pieces();
+        game_ui_data.turn_timer.start_turn();
+        game_ui_data
     }
 
     fn reset(&mut self) {
@@ -120,10 +273,91 @@ impl GameUIData {
         initial_player_inventories.insert(PlayerColor::Red, PlayerInventory::new());
         initial_player_inventories.insert(PlayerColor::Blue, PlayerInventory::new());
 
-        self.game_board = GameBoard::new();
+        self.game_board = GameBoard::with_combat_rules(self.settings.combat_rules);
         self.player_inventories = initial_player_inventories;
-        self.unplaced_board_pieces = game_constants::BOARD_PIECES.to_vec();
+        self.turn_state = TurnState::new();
+        self.turn_summary = TurnSummary::new(PlayerColor::Red);
+        self.previous_turn_summaries.clear();
+        self.handoff = None;
+        self.animations.clear();
+        self.audio.stop_all();
+        self.turn_timer = TurnTimer::new();
+        self.turn_timer.start_turn();
+        self.notification_log.post(NotificationKind::Turn, localization::text("game_reset"));
         self.player_color = PlayerColor::Red;
+        self.setup_board_pieces();
+    }
+
+    // Shuffle the deck back together and get ready for board setup, depending on the setup mode.
+    fn setup_board_pieces(&mut self) {
+        self.unplaced_board_pieces = self.board_piece_deck.pieces().to_vec();
+        self.board_piece_hand.clear();
+        self.selected_board_piece = 0;
+
+        match self.settings.board_setup_mode {
+            BoardSetupMode::Random | BoardSetupMode::Draft { .. } => {
+                self.refill_board_piece_hand();
+            }
+            BoardSetupMode::Generated => {
+                let num_pieces = self.num_board_pieces_to_place();
+                if let Err(e) = deck::generate_board(&mut self.game_board, &mut self.unplaced_board_pieces, num_pieces) {
+                    if let deck::Error::GenerationFailed { num_placed, num_pieces } = e {
+                        self.notification_log.post(NotificationKind::Turn, localization::format("board_generation_failed", &[&num_placed, &num_pieces]));
+                    }
+                    // The board is still empty, so the pieces are placed by hand instead, as in Random mode.
+                    self.refill_board_piece_hand();
+                }
+            }
+        }
+    }
+
+    fn refill_board_piece_hand(&mut self) {
+        // In random mode, the hand is just the next piece to be dropped, so the player can see it before placing it.
+        let hand_size = match self.settings.board_setup_mode {
+            BoardSetupMode::Random => 1,
+            BoardSetupMode::Draft { hand_size } => hand_size,
+            // Only used when the board couldn't be generated (see setup_board_pieces).
+            BoardSetupMode::Generated => 1
+        };
+
+        // Don't draw more pieces than are left to be placed.
+        let num_pieces_left = self.num_board_pieces_to_place() - self.num_board_pieces_placed();
+        while self.board_piece_hand.len() < cmp::min(hand_size, num_pieces_left) {
+            match deck::draw_random_board_piece(&mut self.unplaced_board_pieces) {
+                Some(board_piece) => { self.board_piece_hand.push(board_piece); }
+                None => { break; }
+            }
+        }
+        if self.selected_board_piece >= self.board_piece_hand.len() {
+            self.selected_board_piece = 0;
+        }
+
+        self.board_piece_orientation = match self.settings.board_setup_mode {
+            BoardSetupMode::Random => deck::random_orientation(),
+            _ => BoardPieceOrientation::A
+        };
+    }
+
+    // The piece that will be dropped on the next click, turned the way it will be placed.
+    fn next_board_piece(&self) -> Option<(GameBoardSpaceType, GameBoardSpaceType, GameBoardSpaceType)> {
+        self.board_piece_hand.get(self.selected_board_piece).map(|board_piece| board_piece.oriented(self.board_piece_orientation))
+    }
+
+    // The players in this game, in turn order.
+    fn players(&self) -> Vec<PlayerColor> {
+        [PlayerColor::Red, PlayerColor::Blue, PlayerColor::Green, PlayerColor::Yellow].iter().cloned().take(self.num_players as usize).collect()
+    }
+
+    fn num_board_pieces_to_place(&self) -> usize {
+        cmp::min(game_constants::BOARD_PIECES_PER_PLAYER * self.num_players as usize, self.board_piece_deck.len())
+    }
+
+    fn num_board_pieces_placed(&self) -> usize {
+        self.board_piece_deck.len() - self.unplaced_board_pieces.len() - self.board_piece_hand.len()
+    }
+
+    fn board_setup_completed(&self) -> bool {
+        self.num_board_pieces_placed() >= self.num_board_pieces_to_place()
     }
 
     fn get_mut_active_player_inventory(&mut self) -> &mut PlayerInventory {
@@ -134,63 +368,187 @@ impl GameUIData {
         self.player_inventories.get(&self.player_color).unwrap()
     }
 
-    fn drop_board_piece(&mut self) -> bool {
-        if self.three_pos_under_mouse.is_some() {
-            let (pos_under_mouse_a, pos_under_mouse_b, pos_under_mouse_c) = self.three_pos_under_mouse.unwrap();
-            let game_board = &mut self.game_board;
+    // Checks the board setup rules for dropping the next piece on these positions.
+    fn check_board_piece_placement(&self, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)) -> Result<(), RuleError> {
+        self.game_board.check_board_piece_placement(positions)?;
 
-            if
-                game_board.get_board_space_type(pos_under_mouse_a) == GameBoardSpaceType::Void &&
-                game_board.get_board_space_type(pos_under_mouse_b) == GameBoardSpaceType::Void &&
-                game_board.get_board_space_type(pos_under_mouse_c) == GameBoardSpaceType::Void
-            {
-                // pick a card, any card.
-                let old_len = self.unplaced_board_pieces.len();
-                let new_game_piece = self.unplaced_board_pieces.remove(rand::thread_rng().gen_range(0, old_len));
-
-                // randomize the orientation
-                let (new_a, new_b, new_c) =
-                    match rand::thread_rng().gen_range(0, 3) {
-                        0 => (new_game_piece.a, new_game_piece.b, new_game_piece.c),
-                        1 => (new_game_piece.b, new_game_piece.c, new_game_piece.a),
-                        _ => (new_game_piece.c, new_game_piece.a, new_game_piece.b)
-                    };
-                game_board.set_board_space_type(pos_under_mouse_a, new_a);
-                game_board.set_board_space_type(pos_under_mouse_b, new_b);
-                game_board.set_board_space_type(pos_under_mouse_c, new_c);
-                true
-            } else {
-                false
+        // A connected map is only guaranteed by building it outwards from the first piece.  Pieces dropped apart might never
+        // be joined up, if the pieces left over can't fit anywhere that bridges the gaps, and then board setup could never finish.
+        let must_touch_land = self.settings.require_adjacent_board_pieces || self.settings.require_connected_map;
+        let first_piece = self.num_board_pieces_placed() == 0;
+        if must_touch_land && !first_piece && !self.game_board.board_piece_touches_land(positions) {
+            return Err(RuleError::DestinationBlocked { reason: BlockedReason::NotNextToLand });
+        }
+
+        Ok(())
+    }
+
+    fn board_piece_placement_ok(&self, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)) -> bool {
+        self.check_board_piece_placement(positions).is_ok()
+    }
+
+    fn drop_board_piece(&mut self, positions: (GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)) -> Result<(), RuleError> {
+        if self.selected_board_piece >= self.board_piece_hand.len() {
+            return Err(RuleError::WrongPhase);
+        }
+        self.check_board_piece_placement(positions)?;
+
+        // play the chosen piece, in the chosen orientation.  It only leaves the hand once it is on the board.
+        self.game_board.place_board_piece(positions, &self.board_piece_hand[self.selected_board_piece], self.board_piece_orientation)?;
+        let new_game_piece = self.board_piece_hand.remove(self.selected_board_piece);
+        let (position_a, position_b, position_c) = positions;
+        let (space_type_a, space_type_b, space_type_c) = new_game_piece.oriented(self.board_piece_orientation);
+        self.animations.drop_board_piece(vec![(position_a, space_type_a), (position_b, space_type_b), (position_c, space_type_c)]);
+        self.audio.play(SoundEffect::PiecePlaced);
+        self.selected_board_piece = 0;
+        self.refill_board_piece_hand();
+        Ok(())
+    }
+
+    fn drop_city(&mut self, position: GameBoardSpacePos) -> Result<(), RuleError> {
+        if !self.board_setup_completed() || self.city_setup_completed() {
+            return Err(RuleError::WrongPhase);
+        }
+        // Each starting city comes with a free knight.  Check that both can be placed before changing anything.
+        self.get_active_player_inventory().check_available(PieceType::City, 1)?;
+        self.get_active_player_inventory().check_available(PieceType::Knight, 1)?;
+        self.game_board.check_city_placement(position)?;
+        self.game_board.check_knight_placement(position)?;
+
+        let player_color = self.player_color;
+        self.game_board.add_city(position, player_color)?;
+        self.game_board.add_knight(position, player_color)?;
+        self.animations.drop_piece(PieceType::City, player_color, position);
+        self.animations.drop_piece(PieceType::Knight, player_color, position);
+        self.audio.play(SoundEffect::PiecePlaced);
+        {
+            let player_inventory = self.get_mut_active_player_inventory();
+            player_inventory.take(PieceType::City, 1, &Resources::none())?;
+            player_inventory.take(PieceType::Knight, 1, &Resources::none())?;
+        }
+        self.post_game_event(NotificationKind::Construction, localization::format("event_city_founded", &[&player_color.name()]));
+        Ok(())
+    }
+
+    // Post something that happened on the board.  It also goes in the summary of this turn, for the other players.
+    fn post_game_event(&mut self, kind: NotificationKind, text: String) {
+        self.turn_summary.events.push(text.clone());
+        self.notification_log.post(kind, text);
+    }
+
+    // Show the player why they can't do what they tried to do.
+    fn report_rule_error(&mut self, rule_error: RuleError) {
+        self.audio.play(SoundEffect::InvalidMove);
+        self.notification_log.post(NotificationKind::RuleError, rule_error.to_string());
+    }
+
+    // Give the pieces lost in a battle back to their owners, and keep the report for the battle log.
+    fn record_battle(&mut self, battle_report: BattleReport) {
+        for knight in battle_report.killed_knights.iter() {
+            self.player_inventories.get_mut(&knight.owner).unwrap().put_back(PieceType::Knight, 1);
+            self.animations.fade_out(PieceType::Knight, knight.owner, knight.position);
+        }
+        for &(building_type, ref building) in battle_report.destroyed_buildings.iter() {
+            let piece = match building_type {
+                BuildingType::City => PieceType::City,
+                BuildingType::Village => PieceType::Village,
+                BuildingType::Stronghold => PieceType::Stronghold
+            };
+            self.player_inventories.get_mut(&building.owner).unwrap().put_back(piece, 1);
+            self.animations.fade_out(piece, building.owner, building.position);
+        }
+        self.audio.play(SoundEffect::Battle);
+        self.post_game_event(NotificationKind::Battle, battle_report.to_string());
+    }
+
+    fn city_setup_completed(&self) -> bool {
+        self.game_board.num_cities() >= 3 * self.num_players as usize
+    }
+
+    // The active player's settlements produce resources.
+    fn collect_income(&mut self) {
+        let income = economy::production(&self.game_board, self.player_color);
+        self.get_mut_active_player_inventory().resources.add_all(&income);
+        self.notification_log.post(NotificationKind::Income, localization::format("event_income", &[&self.player_color.name(), &income]));
+    }
+
+    // Called when the active player finishes an action.  Returns true if that was the end of their turn.
+    fn action_completed(&mut self, action_type: actions::PlayerActionType) -> bool {
+        self.turn_summary.actions.push(action_type);
+        match action_type {
+            // During setup, every placement is a whole turn.
+            actions::PlayerActionType::SetupBoard | actions::PlayerActionType::SetupCities => { true }
+            _ => {
+                let turn_rules = self.settings.turn_rules;
+                self.turn_state.record_action(action_type, &turn_rules)
             }
-        } else {
-            false
         }
     }
 
-    fn drop_city(&mut self) -> bool {
-        if self.one_pos_under_mouse.is_some() {
-            let pos_under_mouse = self.one_pos_under_mouse.unwrap();
-            match self.game_board.get_board_space_type(pos_under_mouse) {
-                GameBoardSpaceType::Void => {false}
-                _ => {
-                    if self.game_board.space_ok_for_city(pos_under_mouse) {
-                        self.game_board.add_city(pos_under_mouse, self.player_color);
-                        self.game_board.add_knight(pos_under_mouse, self.player_color);
-                        let mut player_inventory = self.get_mut_active_player_inventory();
-                        player_inventory.num_cities -= 1;
-                        player_inventory.num_knights -= 1;
-                        true
-                    } else {
-                        false
-                    }
-                }
+    // Knights cost Grain to keep on the board.  Any knights that can't be paid for leave, and go back to the inventory.
+    fn pay_upkeep(&mut self) {
+        let knights_per_grain = self.settings.turn_rules.knights_per_upkeep_grain;
+        if knights_per_grain == 0 {
+            return;
+        }
+        let player_color = self.player_color;
+        let num_knights = self.game_board.knights().filter(|knight| knight.owner == player_color).count() as u32;
+        let upkeep = num_knights / knights_per_grain;
+        if upkeep == 0 {
+            return;
+        }
+
+        let paid = cmp::min(upkeep, self.get_active_player_inventory().resources.grain);
+        self.get_mut_active_player_inventory().resources.grain -= paid;
+
+        let mut num_deserters = 0;
+        for _ in paid..upkeep {
+            if let Ok(deserter) = self.game_board.disband_knight(player_color) {
+                self.get_mut_active_player_inventory().put_back(PieceType::Knight, 1);
+                self.animations.fade_out(PieceType::Knight, deserter.owner, deserter.position);
+                num_deserters += 1;
             }
+        }
+
+        if num_deserters > 0 {
+            self.notification_log.post(NotificationKind::Income,
+                localization::format("event_upkeep_deserters", &[&player_color.name(), &paid, &num_deserters]));
         } else {
-            false
+            self.notification_log.post(NotificationKind::Income, localization::format("event_upkeep", &[&player_color.name(), &paid]));
+        }
+    }
+
+    fn start_of_turn_phase(&mut self) {
+        self.collect_income();
+        self.pay_upkeep();
+    }
+
+    // Anything over the storage limit is lost.
+    fn end_of_turn_phase(&mut self) {
+        let max_stored_resources = self.settings.turn_rules.max_stored_resources;
+        if max_stored_resources == 0 {
+            return;
+        }
+        let discarded = self.get_mut_active_player_inventory().resources.limit_to(max_stored_resources);
+        if discarded != Resources::none() {
+            self.notification_log.post(NotificationKind::Income, localization::format("event_resources_lost", &[&self.player_color.name(), &discarded]));
         }
     }
 
     fn end_turn(&mut self) {
+        self.audio.play(SoundEffect::TurnChanged);
+        self.turn_timer.end_turn(self.player_color);
+        if self.turn_state.turn_number() > 0 {
+            self.end_of_turn_phase();
+        }
+
+        // Only the turns since the next player's last turn are worth remembering.
+        let finished_turn_summary = std::mem::replace(&mut self.turn_summary, TurnSummary::new(self.player_color));
+        self.previous_turn_summaries.push(finished_turn_summary);
+        while self.previous_turn_summaries.len() >= self.num_players as usize {
+            self.previous_turn_summaries.remove(0);
+        }
+
         // Change color
         self.player_color = match self.num_players {
             2 => {
@@ -217,17 +575,84 @@ impl GameUIData {
                 }
             }
             _ => {self.player_color}
+        };
+        self.notification_log.post(NotificationKind::Turn, localization::format("turn_started", &[&self.player_color.name()]));
+        self.turn_summary = TurnSummary::new(self.player_color);
+
+        if self.settings.hotseat_handoff {
+            self.handoff = Some(Handoff {
+                next_player: self.player_color,
+                summaries: self.previous_turn_summaries.clone()
+            });
+        } else {
+            self.turn_timer.start_turn();
+        }
+
+        // Turns are only counted, and settlements only produce, once the game proper has started.
+        if self.city_setup_completed() {
+            self.turn_state.start_turn(self.player_color == PlayerColor::Red);
+            self.start_of_turn_phase();
         }
     }
 }
 
+// The timer plays for a player who has run out of time.
+fn handle_timeout(game_ui_data: &mut GameUIData, active_player_action: &mut Box<actions::PlayerActionControl>) {
+    let player_color = game_ui_data.player_color;
+    game_ui_data.notification_log.post(NotificationKind::Turn, localization::format("out_of_time", &[&player_color.name()]));
+
+    // There is no passing during setup, because every player has to place their pieces.
+    let make_random_move = !game_ui_data.city_setup_completed() || game_ui_data.settings.timer_rules.timeout_action == TimeoutAction::RandomMove;
+    if make_random_move {
+        match active_player_action.random_move(game_ui_data) {
+            Some(state_transition) => {
+                apply_state_transition(game_ui_data, active_player_action, state_transition);
+            }
+            None if !game_ui_data.city_setup_completed() => {
+                // Nobody can pass during setup, so the player gets the time back and has to place the piece themselves.
+                game_ui_data.turn_timer.start_turn();
+                return;
+            }
+            None => {}
+        }
+    }
+
+    // Give up the rest of the turn, unless the random move already ended it.
+    if game_ui_data.player_color == player_color {
+        let state_transition = actions::StateTransition{next_action: actions::first_action(game_ui_data), action_completed: false};
+        apply_state_transition(game_ui_data, active_player_action, state_transition);
+        game_ui_data.end_turn();
+    }
+}
+
+// Switch to the next action, and end the turn if the player has finished their actions.
+fn apply_state_transition(game_ui_data: &mut GameUIData, active_player_action: &mut Box<actions::PlayerActionControl>, state_transition: actions::StateTransition) {
+    let finished_action_type = active_player_action.get_action_type();
+    *active_player_action = state_transition.next_action;
+    if state_transition.action_completed && game_ui_data.action_completed(finished_action_type) {
+        game_ui_data.end_turn();
+    }
+}
+
+// The turn clock turns red for the last ten seconds.
+fn clock_color(time_left: u32) -> drawing::ColorSpec {
+    if time_left <= 10000 {
+        drawing::ColorSpec { r: 0xFF, g: 0x40, b: 0x40 }
+    } else {
+        drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA }
+    }
+}
+
 struct EventFeedbackRunData
 {
     pub mouse_clicked: bool,
     pub mouse_moved: bool,
     pub current_mouse_pos: MousePos,
     pub key_pressed: bool,
-    pub last_key_pressed_scancode: Option<sdl2::keyboard::Scancode>
+    pub last_key_pressed_scancode: Option<sdl2::keyboard::Scancode>,
+    pub mouse_wheel_scrolled: i32,
+    // How far the mouse moved with the right button held down, in pixels.
+    pub mouse_dragged: (i32, i32)
 }
 
 enum EventFeedback
@@ -242,6 +667,8 @@ impl EventFeedback {
         let mut mouse_moved = false;
         let mut key_pressed = false;
         let mut last_key_pressed_scancode: Option<sdl2::keyboard::Scancode> = None;
+        let mut mouse_wheel_scrolled = 0;
+        let mut mouse_dragged = (0, 0);
         let mut current_mouse_pos = MousePos { x_pos: 0, y_pos: 0 };
 
         // Catch up on every event in the event_pump
@@ -251,13 +678,27 @@ impl EventFeedback {
                 // SDL_QuitEvent
                 sdl2::event::Event::Quit { .. } => { return EventFeedback::Quit }
                 // SDL_MouseButtonEvent
-                sdl2::event::Event::MouseButtonDown {timestamp: _, window_id: _, which: _, mouse_btn: _, clicks: _, x: _, y: _} => {
-                    mouse_clicked = true;
+                sdl2::event::Event::MouseButtonDown {timestamp: _, window_id: _, which: _, mouse_btn, clicks: _, x: _, y: _} => {
+                    // The right button drags the board around instead.
+                    if mouse_btn != sdl2::mouse::MouseButton::Right {
+                        mouse_clicked = true;
+                    }
                 }
                 // SDL_MouseMotionEvent
-                sdl2::event::Event::MouseMotion {timestamp: _, window_id: _, which: _, mousestate: _, x: x_mouse, y: y_mouse, xrel: _, yrel: _} => {
+                sdl2::event::Event::MouseMotion {timestamp: _, window_id: _, which: _, mousestate, x: x_mouse, y: y_mouse, xrel, yrel} => {
                     current_mouse_pos = MousePos { x_pos: x_mouse, y_pos: y_mouse };
                     mouse_moved = true;
+                    if mousestate.right() {
+                        mouse_dragged = (mouse_dragged.0 + xrel, mouse_dragged.1 + yrel);
+                    }
+                }
+                // SDL_MouseWheelEvent
+                sdl2::event::Event::MouseWheel {timestamp: _, window_id: _, which: _, x: _, y: y_scroll, direction} => {
+                    // Positive y is away from the user, unless the scroll direction has been flipped by the OS.
+                    mouse_wheel_scrolled += match direction {
+                        sdl2::mouse::MouseWheelDirection::Flipped => -y_scroll,
+                        _ => y_scroll
+                    };
                 }
                 // SDL_KeyboardEvent
                 sdl2::event::Event::KeyDown {timestamp: _, window_id: _, keycode: _, scancode, keymod: _, repeat: _} => {
@@ -287,11 +728,50 @@ impl EventFeedback {
             mouse_moved: mouse_moved,
             current_mouse_pos: current_mouse_pos,
             key_pressed: key_pressed,
-            last_key_pressed_scancode: last_key_pressed_scancode
+            last_key_pressed_scancode: last_key_pressed_scancode,
+            mouse_wheel_scrolled: mouse_wheel_scrolled,
+            mouse_dragged: mouse_dragged
         })
     }
 }
 
+// The deck named in the settings, or the standard deck if that can't be loaded.
+fn load_board_piece_deck(filereader: &FileReader, name: &str, problems: &mut Vec<String>) -> BoardPieceDeck {
+    match BoardPieceDeck::from_file(filereader, name) {
+        Ok(board_piece_deck) => board_piece_deck,
+        Err(e) => {
+            problems.push(format!("Could not load board pieces, using the standard deck: {:?}", e));
+            BoardPieceDeck::standard()
+        }
+    }
+}
+
+// Lay out a board the way the Generated board setup mode does, and save a picture of it.
+fn export_generated_board(filereader: &FileReader, path: &Path) {
+    let mut setup_problems = Vec::new();
+    let settings = GameSettings::from_file(filereader, "settings.cfg", &mut setup_problems);
+    colors::set_palette(settings.palette);
+    let board_piece_deck = load_board_piece_deck(filereader, &settings.board_deck, &mut setup_problems);
+    for problem in setup_problems {
+        println!("{}", problem);
+    }
+    let mut game_board = GameBoard::with_combat_rules(settings.combat_rules);
+    let mut unplaced_board_pieces = board_piece_deck.pieces().to_vec();
+    let num_pieces = cmp::min(game_constants::BOARD_PIECES_PER_PLAYER * 2, board_piece_deck.len());
+    if let Err(e) = deck::generate_board(&mut game_board, &mut unplaced_board_pieces, num_pieces) {
+        println!("Could not generate a board: {:?}", e);
+        return;
+    }
+    if path == Path::new("-") {
+        print!("{}", game_board);
+        return;
+    }
+    match export::export_board(&game_board, path) {
+        Ok(()) => { println!("Saved the board to {}", path.display()); }
+        Err(e) => { println!("Could not save the board to {}: {}", path.display(), e); }
+    }
+}
+
 //
 // Main function
 //
@@ -300,10 +780,21 @@ fn main() {
     // file reader object for loading GLSL shader program source files
     let filereader = FileReader::from_relative_exe_path(Path::new("assets")).unwrap();
 
+    // fast-and-feudalist --export-board board.png saves a picture of a generated board, without opening a window.
+    // A .txt file name saves the board as text instead, and - prints it.
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--export-board") {
+        match args.get(index + 1) {
+            Some(path) => { export_generated_board(&filereader, Path::new(path)); }
+            None => { println!("--export-board needs a file name ending in .svg, .png or .txt, or -"); }
+        }
+        return;
+    }
+
     let mut hw = HardwareResources::init();
 
     // Fonts
-    let mut font_resources = fonts::FontResources::new();
+    let mut font_resources = fonts::FontResources::new(&filereader);
 
     let (window_width, window_height) = hw.drawable_size;
     let (ddpi, hdpi, vdpi) = hw.display_dpi;
@@ -321,81 +812,238 @@ fn main() {
     let image_program = render_gl::Program::from_file(&hw.gl, &filereader, "shaders/image").unwrap();
     drawing::write_scale_data(&hw.gl, &shader_program, aspect_ratio);
     drawing::write_rotate_data(&hw.gl, &shader_program, 0.0);
+    drawing::write_opacity_data(&hw.gl, &shader_program, 1.0);
+    drawing::write_opacity_data(&hw.gl, &text_program, 1.0);
+    drawing::write_opacity_data(&hw.gl, &image_program, 1.0);
 
     let frames_per_second = 60;
 
     let mut frame_count: u32 = 0;
     let mut frame_time: u32;
 
-    let mut game_ui_data = GameUIData::defaults();
+    // Problems with the settings or the deck go in the message log, as well as on the console.
+    let mut setup_problems = Vec::new();
+    let settings = GameSettings::from_file(&filereader, "settings.cfg", &mut setup_problems);
+    localization::load_language(&filereader, &settings.language);
+    colors::set_palette(settings.palette);
+    let board_piece_deck = load_board_piece_deck(&filereader, &settings.board_deck, &mut setup_problems);
+    let mut audio = Audio::open(hw.audio_subsystem.as_ref(), &filereader);
+    audio.set_volume(settings.sound_volume as f32 / 100.0);
+    audio.set_muted(settings.sound_muted);
+    audio.set_music_volume(settings.music_volume as f32 / 100.0);
+    let mut game_ui_data = GameUIData::new(settings, board_piece_deck, audio);
+    for problem in setup_problems {
+        println!("{}", problem);
+        game_ui_data.notification_log.post(NotificationKind::Turn, localization::format("setup_problem", &[&problem]));
+    }
 
     // SVG images
-    let svg_images = SVGImages::new(ddpi, window_width);
+    let mut svg_images = SVGImages::new(&hw.gl, ddpi, window_width);
+
+    let mut active_player_action: Box<actions::PlayerActionControl> = actions::first_action(&game_ui_data);
+
+    // The terrain stays on the GPU between frames.
+    let mut board_mesh = BoardMesh::new(&hw.gl);
 
-    let mut active_player_action: Box<actions::PlayerActionControl> = Box::new(actions::SetupBoard {});
+    // Draw calls and vertices in the last frame, shown with F11.
+    let mut show_render_stats = false;
+    let mut render_stats = batch::take_render_stats();
+
+    // F12 saves the next frame that is drawn.
+    let mut screenshot_requested = false;
+
+    // Where the mouse was last seen, for zooming around it.
+    let mut mouse_pos = MousePos { x_pos: window_width as i32 / 2, y_pos: window_height as i32 / 2 };
 
     // Loop with label 'main (exited by the break 'main statement)
     'main: loop {
+        game_ui_data.notification_log.update_time(hw.timer_subsystem.ticks());
+        game_ui_data.turn_timer.update_time(hw.timer_subsystem.ticks());
+        game_ui_data.animations.update_time(hw.timer_subsystem.ticks());
+        let music_phase = if game_ui_data.turn_state.turn_number() > 0 { MusicPhase::MainGame } else { MusicPhase::Setup };
+        game_ui_data.audio.set_music_phase(music_phase);
+
         let event_feedback =
             match EventFeedback::consume_pending_events(&mut event_pump) {
                 EventFeedback::Quit => { break 'main; }
                 EventFeedback::Run(event_feedback_run_data) => { event_feedback_run_data }
             };
 
-
         if event_feedback.mouse_moved {
+            mouse_pos = event_feedback.current_mouse_pos;
+        }
+
+        // Move the camera first, so that the spaces under the mouse are found with the new view.
+        // The board stays where it is while it is hidden for the handoff to the next player.
+        let previous_camera = game_ui_data.camera;
+        if event_feedback.mouse_dragged != (0, 0) && game_ui_data.handoff.is_none() {
+            let (x_dragged, y_dragged) = event_feedback.mouse_dragged;
+            game_ui_data.camera.drag(x_dragged, y_dragged, (window_width, window_height));
+        }
+        if event_feedback.mouse_wheel_scrolled != 0 && game_ui_data.handoff.is_none() {
+            match active_player_action.get_action_type() {
+                // The mouse wheel turns the board piece during board setup.  Page Up and Page Down still zoom.
+                actions::PlayerActionType::SetupBoard => {}
+                _ => {
+                    let focus = mouse_pos_to_drawing_pos(mouse_pos, (window_width, window_height), &game_ui_data.camera);
+                    game_ui_data.camera.zoom_at(event_feedback.mouse_wheel_scrolled, focus);
+                }
+            }
+        }
+        if event_feedback.key_pressed && game_ui_data.handoff.is_none() {
+            use sdl2::keyboard::Scancode::*;
+            let view_center = game_ui_data.camera.from_view_pos(&drawing::PositionSpec { x: 0.0, y: 0.0 });
+            match event_feedback.last_key_pressed_scancode.unwrap() {
+                PageUp => { game_ui_data.camera.zoom_at(1, view_center); }
+                PageDown => { game_ui_data.camera.zoom_at(-1, view_center); }
+                Home => { game_ui_data.camera.reset(); }
+                Left => { game_ui_data.camera.pan_step(-1.0, 0.0); }
+                Right => { game_ui_data.camera.pan_step(1.0, 0.0); }
+                // Up and Down scroll the message history instead while it is open.
+                Up if !game_ui_data.show_history => { game_ui_data.camera.pan_step(0.0, 1.0); }
+                Down if !game_ui_data.show_history => { game_ui_data.camera.pan_step(0.0, -1.0); }
+                _ => {}
+            }
+        }
+        let camera_moved = game_ui_data.camera != previous_camera;
+
+        if event_feedback.mouse_moved || camera_moved {
             match active_player_action.get_action_type() {
                 actions::PlayerActionType::SetupBoard => {
                     // Only do the "three pos" calculation during the SetupBoard stage.
-                    game_ui_data.three_pos_under_mouse = mouse_pos_to_board_piece_destination(event_feedback.current_mouse_pos, (window_width, window_height));
+                    game_ui_data.three_pos_under_mouse = mouse_pos_to_board_piece_destination(mouse_pos, (window_width, window_height), &game_ui_data.camera);
                 }
                 _ => {}
             }
-            game_ui_data.one_pos_under_mouse = mouse_pos_to_game_board_pos(event_feedback.current_mouse_pos, (window_width, window_height));
+            game_ui_data.one_pos_under_mouse = mouse_pos_to_game_board_pos(mouse_pos, (window_width, window_height), &game_ui_data.camera);
         }
 
-        if event_feedback.mouse_clicked {
-            if let Some(state_transition) = active_player_action.mouse_clicked(&mut game_ui_data) {
-                active_player_action = state_transition.next_action;
-                if state_transition.turn_completed {
-                    game_ui_data.end_turn();
+        if game_ui_data.handoff.is_some() {
+            // The next player clicks to uncover the screen.  Nothing else reaches the game until then.
+            if event_feedback.mouse_clicked {
+                game_ui_data.handoff = None;
+                game_ui_data.turn_timer.start_turn();
+            }
+        } else if game_ui_data.animations.blocking() {
+            // Clicks and key presses are dropped until the pieces have finished moving.
+        } else {
+            if event_feedback.mouse_clicked {
+                if let Some(state_transition) = active_player_action.mouse_clicked(&mut game_ui_data) {
+                    apply_state_transition(&mut game_ui_data, &mut active_player_action, state_transition);
                 }
             }
-        }
 
-        if event_feedback.key_pressed {
-            if let Some(state_transition) = active_player_action.key_pressed(&mut game_ui_data, &event_feedback.last_key_pressed_scancode.unwrap()) {
-                active_player_action = state_transition.next_action;
-                if state_transition.turn_completed {
-                    game_ui_data.end_turn();
+            if event_feedback.mouse_wheel_scrolled != 0 {
+                if let Some(state_transition) = active_player_action.mouse_wheel_scrolled(&mut game_ui_data, event_feedback.mouse_wheel_scrolled) {
+                    apply_state_transition(&mut game_ui_data, &mut active_player_action, state_transition);
+                }
+            }
+
+            if event_feedback.key_pressed {
+                if let Some(state_transition) = active_player_action.key_pressed(&mut game_ui_data, &event_feedback.last_key_pressed_scancode.unwrap()) {
+                    apply_state_transition(&mut game_ui_data, &mut active_player_action, state_transition);
                 }
             }
+        }
+
+        if event_feedback.key_pressed {
             use sdl2::keyboard::Scancode::*;
             match event_feedback.last_key_pressed_scancode.unwrap() {
                 F2 => {
                     // Reset board
                     game_ui_data.reset();
-                    active_player_action = Box::new(actions::SetupBoard{});
+                    active_player_action = actions::first_action(&game_ui_data);
                 }
-                F3 => {
-                    // End turn
+                F3 if game_ui_data.handoff.is_none() && !game_ui_data.animations.blocking() => {
+                    // End turn.  Like any other move, only while the board is showing and nothing is moving.
                     game_ui_data.end_turn();
+                    active_player_action = actions::first_action(&game_ui_data);
+                }
+                F4 => {
+                    // Show or hide who controls each space
+                    game_ui_data.show_territory = !game_ui_data.show_territory;
+                }
+                F5 => {
+                    // Show or hide the message history
+                    game_ui_data.show_history = !game_ui_data.show_history;
+                }
+                F7 => {
+                    // Switch to the next color palette
+                    let palette = colors::current_palette().next();
+                    colors::set_palette(palette);
+                    game_ui_data.notification_log.post(NotificationKind::Turn, localization::format("palette_changed", &[&palette.name()]));
+                }
+                F8 => {
+                    // Mute or unmute the sound
+                    let muted = !game_ui_data.audio.muted();
+                    game_ui_data.audio.set_muted(muted);
+                    game_ui_data.notification_log.post(NotificationKind::Turn, localization::text(if muted { "sound_muted" } else { "sound_unmuted" }));
+                }
+                F9 | F10 => {
+                    // Turn the sound down or up
+                    let step = if event_feedback.last_key_pressed_scancode.unwrap() == F9 { -audio::VOLUME_STEP } else { audio::VOLUME_STEP };
+                    let volume = game_ui_data.audio.volume() + step;
+                    game_ui_data.audio.set_volume(volume);
+                    game_ui_data.notification_log.post(NotificationKind::Turn, localization::format("sound_volume", &[&((game_ui_data.audio.volume() * 100.0).round() as u32)]));
+                }
+                LeftBracket | RightBracket => {
+                    // Turn the music down or up
+                    let step = if event_feedback.last_key_pressed_scancode.unwrap() == LeftBracket { -audio::VOLUME_STEP } else { audio::VOLUME_STEP };
+                    let music_volume = game_ui_data.audio.music_volume() + step;
+                    game_ui_data.audio.set_music_volume(music_volume);
+                    game_ui_data.notification_log.post(NotificationKind::Turn, localization::format("music_volume", &[&((game_ui_data.audio.music_volume() * 100.0).round() as u32)]));
+                }
+                F11 => {
+                    // Show or hide the renderer statistics
+                    show_render_stats = !show_render_stats;
+                }
+                F12 => {
+                    // Save a screenshot
+                    screenshot_requested = true;
+                }
+                Up => {
+                    if game_ui_data.show_history {
+                        game_ui_data.notification_log.scroll_history_back();
+                    }
+                }
+                Down => {
+                    if game_ui_data.show_history {
+                        game_ui_data.notification_log.scroll_history_forward();
+                    }
                 }
                 _ => {}
             }
         }
 
+        if game_ui_data.handoff.is_none() && !game_ui_data.animations.blocking() && game_ui_data.turn_timer.out_of_time(game_ui_data.player_color, &game_ui_data.settings.timer_rules) {
+            handle_timeout(&mut game_ui_data, &mut active_player_action);
+        }
+
         // Clear the color buffer.
         unsafe {
             hw.gl.Clear(gl::COLOR_BUFFER_BIT);
         }
 
-        // Draw board
-        game_ui_data.game_board.draw_board(&hw.gl, &shader_program);
+        svg_images.update_palette(&hw.gl);
+
+        // Draw board, as seen through the camera.  Only the board area is drawn on, however far the board is moved.
+        clip_to_board_area(&hw.gl, (window_width, window_height), true);
+        game_ui_data.camera.write_shader_data(&hw.gl, &shader_program, aspect_ratio);
+        game_ui_data.game_board.draw_board(&shader_program, &mut board_mesh);
+        draw_board_animations(&hw.gl, &shader_program, &game_ui_data.animations);
+
+        if game_ui_data.show_territory {
+            draw_territory_overlay(&hw.gl, &shader_program, &game_ui_data.game_board);
+        }
 
         // Highlight the space underneath the mouse cursor
         active_player_action.draw_highlight(&mut game_ui_data, &hw.gl, &shader_program, &image_program, &svg_images, (window_width, window_height));
 
+        clip_to_board_area(&hw.gl, (window_width, window_height), false);
+
+        // The rest of the UI doesn't move with the camera.
+        drawing::write_scale_data(&hw.gl, &shader_program, aspect_ratio);
+
         // Draw rectangular border around the game board area.
         GameBoard::draw_border(&hw.gl, &shader_program);
 
@@ -404,8 +1052,8 @@ fn main() {
             &hw.gl,
             &image_program,
             &svg_images.scroll_image,
-            drawing::PositionSpec{ x: -0.99, y: 0.28 },
-            drawing::SizeSpec{ x: 0.18, y: 0.45 });
+            drawing::PositionSpec{ x: -0.99, y: 0.03 },
+            drawing::SizeSpec{ x: 0.18, y: 0.70 });
 
         // Draw text
         {
@@ -414,15 +1062,27 @@ fn main() {
                 shader_program: &text_program,
                 drawable_size: (window_width, window_height),
                 display_dpi: (ddpi, hdpi, vdpi),
-                font_face: &font_resources.cardinal_font_face,
+                fonts: &font_resources.registry,
                 text_cache: &mut font_resources.text_cache
             };
 
-            drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.95, y: 0.85 }, drawing::ObjectOriginLocation::Left, 48, drawing::ColorSpec { r: 0xFF, g: 0xD7, b: 0x00 },
-                "Fast and Feudalist".to_string());
+            drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.95, y: 0.85 }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Banner, drawing::ColorSpec { r: 0xFF, g: 0xD7, b: 0x00 },
+                localization::text("game_title"));
+
+            if game_ui_data.turn_state.turn_number() > 0 {
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: 0.95, y: 0.88 }, drawing::ObjectOriginLocation::Right, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::format("round_and_turn", &[&game_ui_data.turn_state.round_number(), &game_ui_data.turn_state.turn_number()]));
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: 0.95, y: 0.82 }, drawing::ObjectOriginLocation::Right, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::format("actions_left", &[&game_ui_data.turn_state.actions_left(&game_ui_data.settings.turn_rules)]));
+            } else {
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: 0.95, y: 0.88 }, drawing::ObjectOriginLocation::Right, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+                    localization::text("setup"));
+            }
 
             active_player_action.draw_text(&mut text_drawing_baggage, &mut game_ui_data);
 
+            notifications::draw_recent_notifications(&mut text_drawing_baggage, &game_ui_data.notification_log);
+
 
             {
                 let (mut x_scale, mut y_scale) = scaling_for_board((window_width, window_height));
@@ -459,19 +1119,76 @@ fn main() {
             }
             {
                 let player_inventory = game_ui_data.get_active_player_inventory();
-                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.60 }, drawing::ObjectOriginLocation::Left, 24, game_ui_data.player_color.color(),
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.60 }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Title, game_ui_data.player_color.color(),
                     player_inventory.num_cities.to_string());
-                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.52 }, drawing::ObjectOriginLocation::Left, 24, game_ui_data.player_color.color(),
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.52 }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Title, game_ui_data.player_color.color(),
                     player_inventory.num_strongholds.to_string());
-                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.44 }, drawing::ObjectOriginLocation::Left, 24, game_ui_data.player_color.color(),
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.44 }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Title, game_ui_data.player_color.color(),
                     player_inventory.num_villages.to_string());
-                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.36 }, drawing::ObjectOriginLocation::Left, 24, game_ui_data.player_color.color(),
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.88, y: 0.36 }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Title, game_ui_data.player_color.color(),
                     player_inventory.num_knights.to_string());
+
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.95, y: 0.28 }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Body, game_ui_data.player_color.color(),
+                    player_inventory.noble_rank.name().to_string());
+                let mut y_pos = 0.22;
+                for resource_type in ResourceType::ALL.iter() {
+                    drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.95, y: y_pos }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Body, game_ui_data.player_color.color(),
+                        format!("{}: {}", resource_type.name(), player_inventory.resources.get(*resource_type)));
+                    y_pos -= 0.05;
+                }
+            }
+
+            // Draw the clocks underneath the scroll.
+            if game_ui_data.settings.timer_rules.enabled() {
+                let timer_rules = game_ui_data.settings.timer_rules;
+                let mut y_pos = -0.03;
+                if let Some(time_left) = game_ui_data.turn_timer.turn_time_left(&timer_rules) {
+                    drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.95, y: y_pos }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Body, clock_color(time_left),
+                        localization::format("turn_clock", &[&turn_timer::format_time(time_left)]));
+                    y_pos -= 0.05;
+                }
+                for player_color in game_ui_data.players() {
+                    let is_active_player = player_color == game_ui_data.player_color;
+                    if let Some(time_left) = game_ui_data.turn_timer.game_time_left(player_color, &timer_rules, is_active_player) {
+                        drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: -0.95, y: y_pos }, drawing::ObjectOriginLocation::Left, fonts::TextStyle::Body, player_color.color(),
+                            format!("{}: {}", player_color.name(), turn_timer::format_time(time_left)));
+                        y_pos -= 0.05;
+                    }
+                }
             }
 
             // Draw player items
-            game_ui_data.game_board.draw_cities(&hw.gl, &image_program, (window_width, window_height), &svg_images);
-            game_ui_data.game_board.draw_knights(&hw.gl, &image_program, (window_width, window_height), &svg_images, &mut text_drawing_baggage);
+            clip_to_board_area(&hw.gl, (window_width, window_height), true);
+            game_ui_data.game_board.draw_cities(&hw.gl, &image_program, (window_width, window_height), &game_ui_data.camera, &svg_images, &game_ui_data.animations);
+            game_ui_data.game_board.draw_strongholds(&hw.gl, &image_program, (window_width, window_height), &game_ui_data.camera, &svg_images, &game_ui_data.animations);
+            game_ui_data.game_board.draw_villages(&hw.gl, &image_program, (window_width, window_height), &game_ui_data.camera, &svg_images, &game_ui_data.animations);
+            game_ui_data.game_board.draw_knights(&hw.gl, &image_program, (window_width, window_height), &game_ui_data.camera, &svg_images, &game_ui_data.animations, &mut text_drawing_baggage);
+            draw_piece_animations(&hw.gl, &image_program, (window_width, window_height), &game_ui_data.camera, &svg_images, &game_ui_data.animations);
+            clip_to_board_area(&hw.gl, (window_width, window_height), false);
+
+            if game_ui_data.show_history {
+                notifications::draw_history_panel(&hw.gl, &shader_program, &mut text_drawing_baggage, &game_ui_data.notification_log, aspect_ratio);
+            }
+
+            if let Some(ref handoff) = game_ui_data.handoff {
+                handoff::draw_handoff_screen(&hw.gl, &shader_program, &mut text_drawing_baggage, handoff, aspect_ratio);
+            }
+
+            if show_render_stats {
+                drawing::draw_text(&mut text_drawing_baggage, drawing::PositionSpec{ x: 0.95, y: -0.95 }, drawing::ObjectOriginLocation::Right, fonts::TextStyle::Small, drawing::ColorSpec { r: 0xFF, g: 0xFF, b: 0xFF },
+                    format!("{} draw calls, {} vertices", render_stats.draw_calls, render_stats.vertices));
+            }
+        }
+        render_stats = batch::take_render_stats();
+
+        if screenshot_requested {
+            screenshot_requested = false;
+            let seconds = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+            let file_name = format!("screenshot-{}.png", seconds);
+            match export::save_screenshot(&hw.gl, (window_width, window_height), Path::new(&file_name)) {
+                Ok(()) => { game_ui_data.notification_log.post(NotificationKind::Turn, localization::format("screenshot_saved", &[&file_name])); }
+                Err(e) => { game_ui_data.notification_log.post(NotificationKind::Turn, localization::format("screenshot_failed", &[&e])); }
+            }
         }
 
         // Swap the window pixels with what we have just rendered
@@ -493,3 +1210,28 @@ fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inventory_exhausted() {
+        let mut player_inventory = PlayerInventory::new();
+        assert_eq!(player_inventory.take(PieceType::Stronghold, 3, &Resources::none()), Err(RuleError::InventoryExhausted { piece: PieceType::Stronghold }));
+        assert_eq!(player_inventory.num_strongholds, 2);
+        assert_eq!(player_inventory.take(PieceType::Stronghold, 2, &Resources::none()), Ok(()));
+        assert_eq!(player_inventory.take(PieceType::Stronghold, 1, &Resources::none()), Err(RuleError::InventoryExhausted { piece: PieceType::Stronghold }));
+        assert_eq!(player_inventory.num_strongholds, 0);
+    }
+
+    #[test]
+    fn nothing_taken_when_unaffordable() {
+        let mut player_inventory = PlayerInventory::new();
+        let resources = player_inventory.resources;
+        let cost = resources.times(2);
+        assert_eq!(player_inventory.take(PieceType::Knight, 1, &cost), Err(RuleError::CannotAfford { cost: cost }));
+        assert_eq!(player_inventory.num_knights, 7);
+        assert_eq!(player_inventory.resources, resources);
+    }
+}
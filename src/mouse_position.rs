@@ -1,9 +1,10 @@
 
+use camera::Camera;
 use drawing;
 use drawing_constants;
 use game_constants;
 use gameboard::gameboard::GameBoardSpacePos;
-use gameboard::gameboard_drawing::scaling_for_board;
+use gameboard::hex::AxialPos;
 
 #[derive(Clone, Copy)]
 pub struct MousePos {
@@ -11,75 +12,35 @@ pub struct MousePos {
     pub y_pos: i32
 }
 
-fn mouse_pos_to_drawing_pos(mouse_position: MousePos, drawable_size: (u32, u32)) -> drawing::PositionSpec {
+// The inverse of what the camera does when drawing the board.
+pub fn mouse_pos_to_drawing_pos(mouse_position: MousePos, drawable_size: (u32, u32), camera: &Camera) -> drawing::PositionSpec {
     let (window_width, window_height) = drawable_size;
-    let (x_scale, y_scale) = scaling_for_board(drawable_size);
+    let (x_scale, y_scale) = camera.scaling(drawable_size);
 
     let drawing_x = (mouse_position.x_pos - (window_width as i32/ 2)) as f32 / window_width as f32 * 2.0 / x_scale;
     let drawing_y = ((window_height as i32 / 2) - mouse_position.y_pos) as f32 / window_height as f32 * 2.0 / y_scale;
 
-    drawing::PositionSpec { x: drawing_x, y: drawing_y }
+    camera.from_view_pos(&drawing::PositionSpec { x: drawing_x, y: drawing_y })
 }
 
-pub fn mouse_pos_to_game_board_pos(mouse_position: MousePos, drawable_size: (u32, u32)) -> Option<GameBoardSpacePos> {
-    let drawing_pos = mouse_pos_to_drawing_pos(mouse_position, drawable_size);
+pub fn mouse_pos_to_game_board_pos(mouse_position: MousePos, drawable_size: (u32, u32), camera: &Camera) -> Option<GameBoardSpacePos> {
+    let drawing_pos = mouse_pos_to_drawing_pos(mouse_position, drawable_size, camera);
 
-    let from_game_board_origin_x = drawing_pos.x - drawing_constants::GAME_BOARD_ORIGIN_X;
-    let from_game_board_origin_y = drawing_pos.y - drawing_constants::GAME_BOARD_ORIGIN_Y;
+    // Measure from the center of the bottom-left-most hexagon
+    let from_game_board_origin_x = drawing_pos.x - (drawing_constants::GAME_BOARD_ORIGIN_X + drawing_constants::HEXAGON_WIDTH / 2.0);
+    let from_game_board_origin_y = drawing_pos.y - (drawing_constants::GAME_BOARD_ORIGIN_Y + drawing_constants::HEXAGON_HEIGHT / 2.0);
 
-    // Cut the hexagons into quarters on the x axis, and halves on the y axis.
-    // The two center quarters form rectangles, and the two outter quarters form triangles.
-    // It's easy to know which hexagon the mouse pos is in if it falls in a rectangle.
-    // It's a little bit trickier if the mouse pos is in one of the triangles.
+    // Each column is 3/4 of a hexagon width to the right of the last one.
+    // In axial coordinates, each column to the right is also half a hexagon height higher (see hex.rs).
+    // Convert the point to fractional axial coordinates, and then round to the hexagon whose center is nearest.
+    let fractional_q = from_game_board_origin_x / drawing_constants::HEXAGON_X_SPACING;
+    let fractional_r = from_game_board_origin_y / drawing_constants::HEXAGON_Y_SPACING - fractional_q / 2.0;
 
-    let scaled_x = from_game_board_origin_x / drawing_constants::HEXAGON_WIDTH * 4.0;
-    let scaled_y = from_game_board_origin_y / drawing_constants::HEXAGON_HEIGHT * 2.0;
-
-    let rounded_x = scaled_x.floor() as i32;
-    let rounded_y = scaled_y.floor() as i32;
-
-    // Because of the way the hexagons are staggered, every three quarters is a new column.
-
-    let x_pos_game = rounded_x / 3 -
-        if rounded_x % 3 == 0 {
-            // Mouse pos is in a triangle. Determine if it was to the left or right of the diagonal line.
-            if (rounded_x % 6 == 0 && rounded_y % 2 == 1) || (rounded_x % 6 == 3 && rounded_y % 2 == 0) {
-                // positive slope
-                if scaled_y - scaled_y.floor() < scaled_x - scaled_x.floor() {
-                    // right
-                    0
-                }
-                else {
-                    // left
-                    1
-                }
-            } else {
-                // negative slope
-                if scaled_y - (scaled_y + 1.0).floor() < (scaled_x - scaled_x.floor()) * -1.0 {
-                    // left
-                    1
-                } else {
-                    // right
-                    0
-                }
-            }
-        } else {
-            // Mouse pos is in a rectangle.
-            0
-        };
-
-    let shifted_y = rounded_y - if x_pos_game % 2 == 1 { 1 } else { 0 };
-    let y_pos_game = shifted_y / 2;
-
-    if rounded_x < 0 || x_pos_game < 0 || x_pos_game >= game_constants::MAX_BOARD_WIDTH as i32 || shifted_y < 0 || y_pos_game >= game_constants::MAX_BOARD_HEIGHT as i32 {
-        return None;
-    }
-
-    Some(GameBoardSpacePos { x_pos: x_pos_game as u8, y_pos: y_pos_game as u8})
+    AxialPos::round(fractional_q, fractional_r).to_offset()
 }
 
-pub fn mouse_pos_to_board_piece_destination(mouse_position: MousePos, drawable_size: (u32, u32)) -> Option<(GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)> {
-    let drawing_pos = mouse_pos_to_drawing_pos(mouse_position, drawable_size);
+pub fn mouse_pos_to_board_piece_destination(mouse_position: MousePos, drawable_size: (u32, u32), camera: &Camera) -> Option<(GameBoardSpacePos, GameBoardSpacePos, GameBoardSpacePos)> {
+    let drawing_pos = mouse_pos_to_drawing_pos(mouse_position, drawable_size, camera);
 
     // Adjust the origin so it is at the center of the bottom-left-most hexagon
     let adjusted_game_board_origin_x = drawing_constants::GAME_BOARD_ORIGIN_X + drawing_constants::HEXAGON_WIDTH / 2.0;
@@ -0,0 +1,177 @@
+use audio::{self,Samples};
+use filereader::FileReader;
+use lewton::inside_ogg::OggStreamReader;
+use std::io::Cursor;
+use std::sync::Arc;
+
+// Background music.
+//
+// Each part of the game has its own track in assets/music, as an .ogg or .wav file (e.g. music/setup.ogg).
+// Tracks loop until the game moves on to another part, and then the next track fades in as the last one fades out.
+// .ogg files are decoded a little at a time while they play, in the audio thread.  Missing tracks are just silent.
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MusicPhase {
+    // Board and city setup.
+    Setup,
+    MainGame,
+    // Once somebody has won.  The game has no ending yet, so nothing switches to this phase.
+    EndScreen
+}
+
+impl MusicPhase {
+    pub const ALL: [MusicPhase; 3] = [MusicPhase::Setup, MusicPhase::MainGame, MusicPhase::EndScreen];
+
+    // Without the extension.
+    fn file_name(&self) -> &'static str {
+        match self {
+            MusicPhase::Setup => "music/setup",
+            MusicPhase::MainGame => "music/main_game",
+            MusicPhase::EndScreen => "music/end_screen"
+        }
+    }
+}
+
+// A track as it was read from its file.
+pub enum TrackData {
+    // Still compressed.
+    Ogg(Vec<u8>),
+    Wav(Samples)
+}
+
+impl TrackData {
+    pub fn load(filereader: &FileReader, phase: MusicPhase, sample_rate: u32) -> Option<TrackData> {
+        let ogg_name = format!("{}.ogg", phase.file_name());
+        if let Ok(bytes) = filereader.load_bytes(&ogg_name) {
+            // Check that it can be decoded now, rather than in the audio thread.
+            return match OggStreamReader::new(Cursor::new(&bytes[..])) {
+                Ok(_) => Some(TrackData::Ogg(bytes)),
+                Err(e) => {
+                    println!("Could not read {}: {:?}", ogg_name, e);
+                    None
+                }
+            };
+        }
+
+        let wav_name = format!("{}.wav", phase.file_name());
+        match filereader.load_bytes(&wav_name) {
+            Ok(bytes) => {
+                match audio::decode_wav(&bytes, sample_rate) {
+                    Ok(samples) => Some(TrackData::Wav(Arc::new(samples))),
+                    Err(e) => {
+                        println!("Could not read {}: {}", wav_name, e);
+                        None
+                    }
+                }
+            }
+            Err(_) => None
+        }
+    }
+
+    // Start playing the track from the beginning.
+    pub fn start(&self, sample_rate: u32) -> Option<MusicTrack> {
+        let decoder = match self {
+            TrackData::Ogg(bytes) => {
+                let reader = OggStreamReader::new(Cursor::new(bytes.clone())).ok()?;
+                TrackDecoder::Ogg(reader)
+            }
+            TrackData::Wav(samples) => TrackDecoder::Samples { samples: samples.clone(), next_sample: 0 }
+        };
+        let track_sample_rate = match decoder {
+            TrackDecoder::Ogg(ref reader) => reader.ident_hdr.audio_sample_rate,
+            // Already converted by decode_wav.
+            TrackDecoder::Samples { .. } => sample_rate
+        };
+        Some(MusicTrack {
+            decoder: decoder,
+            buffer: Vec::new(),
+            position: 0.0,
+            step: track_sample_rate as f64 / sample_rate as f64
+        })
+    }
+}
+
+enum TrackDecoder {
+    Ogg(OggStreamReader<Cursor<Vec<u8>>>),
+    Samples { samples: Samples, next_sample: usize }
+}
+
+// How many samples of a .wav track to hand over at a time.
+const WAV_CHUNK_SIZE: usize = 4096;
+
+// A track that is playing.  It never ends; it goes back to the beginning instead.
+pub struct MusicTrack {
+    decoder: TrackDecoder,
+    // Mono samples at the track's own sample rate, that haven't been played yet.
+    buffer: Vec<f32>,
+    // Where the next sample comes from in the buffer.  Tracks at another sample rate step through it faster or slower.
+    position: f64,
+    step: f64
+}
+
+impl MusicTrack {
+    // Add the next samples of the track to the buffer, starting over at the end.  Returns false if that can't be done.
+    fn decode_more(&mut self) -> bool {
+        for _ in 0..2 {
+            let decoded = match self.decoder {
+                TrackDecoder::Ogg(ref mut reader) => {
+                    let channels = reader.ident_hdr.audio_channels as usize;
+                    match reader.read_dec_packet_itl() {
+                        Ok(Some(interleaved)) => {
+                            self.buffer.extend(interleaved.chunks(channels).map(|frame| {
+                                frame.iter().map(|&sample| sample as f32 / 32768.0).sum::<f32>() / channels as f32
+                            }));
+                            true
+                        }
+                        Ok(None) => {
+                            if reader.seek_absgp_pg(0).is_err() {
+                                return false;
+                            }
+                            false
+                        }
+                        Err(e) => {
+                            println!("Could not decode the music: {:?}", e);
+                            return false;
+                        }
+                    }
+                }
+                TrackDecoder::Samples { ref samples, ref mut next_sample } => {
+                    if *next_sample >= samples.len() {
+                        *next_sample = 0;
+                        false
+                    } else {
+                        let chunk_end = (*next_sample + WAV_CHUNK_SIZE).min(samples.len());
+                        self.buffer.extend_from_slice(&samples[*next_sample..chunk_end]);
+                        *next_sample = chunk_end;
+                        true
+                    }
+                }
+            };
+            if decoded {
+                return true;
+            }
+            // The end of the track was reached, so try again from the beginning.  A track with nothing in it stops here.
+        }
+        false
+    }
+
+    // The next sample at the mixer's sample rate, or None if the track can't be played any further.
+    pub fn next_sample(&mut self) -> Option<f32> {
+        while self.position as usize + 1 >= self.buffer.len() {
+            if !self.decode_more() {
+                return None;
+            }
+        }
+        let index = self.position as usize;
+        let fraction = (self.position - index as f64) as f32;
+        let sample = self.buffer[index] * (1.0 - fraction) + self.buffer[index + 1] * fraction;
+        self.position += self.step;
+
+        // Drop what has been played now and then, rather than after every sample.
+        if index >= WAV_CHUNK_SIZE {
+            self.buffer.drain(..index);
+            self.position -= index as f64;
+        }
+        Some(sample)
+    }
+}
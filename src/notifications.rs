@@ -0,0 +1,179 @@
+use drawing;
+use fonts;
+use gl;
+use localization;
+use render_gl;
+use text;
+
+// Messages to the players about what just happened in the game.
+// New messages show up at the bottom of the screen for a few seconds and then fade away.
+// Every message is kept, so the whole session can be reviewed in the history panel.
+
+// How long a message stays on screen, including the time it takes to fade away.
+const DISPLAY_TIME_MS: u32 = 6000;
+const FADE_TIME_MS: u32 = 1500;
+
+const MAX_RECENT_LINES: usize = 5;
+const HISTORY_LINES: usize = 20;
+// The part of the history panel that the messages go in, in screen coordinates.
+const HISTORY_TEXT_WIDTH: f32 = 1.32;
+const HISTORY_TEXT_HEIGHT: f32 = 1.15;
+const HISTORY_LINE_GAP: f32 = 0.02;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum NotificationKind {
+    Turn,
+    RuleError,
+    Battle,
+    Construction,
+    Title,
+    Income
+}
+
+impl NotificationKind {
+    fn color(&self) -> drawing::ColorSpec {
+        match self {
+            NotificationKind::Turn => drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            NotificationKind::RuleError => drawing::ColorSpec { r: 0xFF, g: 0x40, b: 0x40 },
+            NotificationKind::Battle => drawing::ColorSpec { r: 0xFF, g: 0xA5, b: 0x00 },
+            NotificationKind::Construction => drawing::ColorSpec { r: 0x90, g: 0xEE, b: 0x90 },
+            NotificationKind::Title => drawing::ColorSpec { r: 0xFF, g: 0xD7, b: 0x00 },
+            NotificationKind::Income => drawing::ColorSpec { r: 0xB0, g: 0xC4, b: 0xDE }
+        }
+    }
+}
+
+pub struct Notification {
+    pub kind: NotificationKind,
+    pub text: String,
+    // Milliseconds since the program started.
+    pub time_posted: u32
+}
+
+pub struct NotificationLog {
+    notifications: Vec<Notification>,
+    current_time: u32,
+    // How many lines the history panel has been scrolled back from the newest message.
+    history_scroll: usize
+}
+
+impl NotificationLog {
+    pub fn new() -> NotificationLog {
+        NotificationLog {
+            notifications: Vec::new(),
+            current_time: 0,
+            history_scroll: 0
+        }
+    }
+
+    // Call once a frame, with the number of milliseconds since the program started.
+    pub fn update_time(&mut self, current_time: u32) {
+        self.current_time = current_time;
+    }
+
+    pub fn post(&mut self, kind: NotificationKind, text: String) {
+        self.notifications.push(Notification { kind: kind, text: text, time_posted: self.current_time });
+    }
+
+    pub fn notifications(&self) -> &[Notification] {
+        &self.notifications
+    }
+
+    pub fn scroll_history_back(&mut self) {
+        if self.history_scroll + HISTORY_LINES < self.notifications.len() {
+            self.history_scroll += 1;
+        }
+    }
+
+    pub fn scroll_history_forward(&mut self) {
+        if self.history_scroll > 0 {
+            self.history_scroll -= 1;
+        }
+    }
+
+    // The messages that are still on screen, oldest first, with their opacity.
+    fn recent(&self) -> Vec<(&Notification, f32)> {
+        let mut recent: Vec<(&Notification, f32)> = self.notifications.iter().rev()
+            .take(MAX_RECENT_LINES)
+            .filter_map(|notification| {
+                let age = self.current_time.saturating_sub(notification.time_posted);
+                if age >= DISPLAY_TIME_MS {
+                    None
+                } else if age + FADE_TIME_MS > DISPLAY_TIME_MS {
+                    Some((notification, (DISPLAY_TIME_MS - age) as f32 / FADE_TIME_MS as f32))
+                } else {
+                    Some((notification, 1.0))
+                }
+            })
+            .collect();
+        recent.reverse();
+        recent
+    }
+}
+
+// Draw the latest messages underneath the game board, newest at the bottom.
+pub fn draw_recent_notifications(baggage: &mut drawing::TextDrawingBaggage, notification_log: &NotificationLog) {
+    let recent = notification_log.recent();
+    let mut y_pos = -0.96 + 0.06 * (recent.len() as f32 - 1.0);
+    for (notification, opacity) in recent {
+        drawing::write_opacity_data(&baggage.gl, baggage.shader_program, opacity);
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: y_pos }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, notification.kind.color(),
+            notification.text.clone());
+        y_pos -= 0.06;
+    }
+    drawing::write_opacity_data(&baggage.gl, baggage.shader_program, 1.0);
+}
+
+// A panel over the game board, listing every message from this session.
+pub fn draw_history_panel(
+    gl: &gl::Gl,
+    shader_program: &render_gl::Program,
+    baggage: &mut drawing::TextDrawingBaggage,
+    notification_log: &NotificationLog,
+    aspect_ratio: f32)
+{
+    // Draw the background in screen coordinates, rather than scaled like the game board.
+    drawing::write_scale_data(gl, shader_program, 1.0);
+    drawing::write_opacity_data(gl, shader_program, 0.85);
+    drawing::draw_rectangle(
+        gl,
+        shader_program,
+        drawing::RectangleSpec {
+            color: drawing::ColorSpec { r: 0x10, g: 0x10, b: 0x10 },
+            pos: drawing::PositionSpec { x: -0.7, y: -0.7 },
+            size: drawing::SizeSpec { x: 1.4, y: 1.4 }});
+    drawing::write_opacity_data(gl, shader_program, 1.0);
+    drawing::write_scale_data(gl, shader_program, aspect_ratio);
+
+    drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.62 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Title, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+        localization::text("message_history"));
+
+    let notifications = notification_log.notifications();
+    if notifications.is_empty() {
+        drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: 0.0 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Body, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+            localization::text("message_history_empty"));
+    } else {
+        // Long messages wrap, so go back from the newest one until the panel is full.
+        let end = notifications.len() - notification_log.history_scroll;
+        let mut start = end;
+        let mut height_used = 0.0;
+        while start > 0 && end - start < HISTORY_LINES {
+            let height = text::measure_text(baggage, fonts::TextStyle::Small, &notifications[start - 1].text, Some(HISTORY_TEXT_WIDTH)).y;
+            if start < end && height_used + height > HISTORY_TEXT_HEIGHT {
+                break;
+            }
+            height_used += height + HISTORY_LINE_GAP;
+            start -= 1;
+        }
+
+        let mut y_pos = 0.55;
+        for notification in &notifications[start..end] {
+            let height = text::draw_text_box(baggage, drawing::PositionSpec{ x: -0.66, y: y_pos }, HISTORY_TEXT_WIDTH, text::TextAlign::Left, fonts::TextStyle::Small,
+                notification.kind.color(), &notification.text);
+            y_pos -= height + HISTORY_LINE_GAP;
+        }
+    }
+
+    drawing::draw_text(baggage, drawing::PositionSpec{ x: 0.0, y: -0.66 }, drawing::ObjectOriginLocation::Center, fonts::TextStyle::Small, drawing::ColorSpec { r: 0xEE, g: 0xE8, b: 0xAA },
+        localization::text("message_history_help"));
+}
@@ -0,0 +1,109 @@
+use economy::Resources;
+use gameboard::gameboard::GameBoardSpaceType;
+use localization;
+use std::error;
+use std::fmt;
+use PlayerColor;
+
+// Reasons why a move or placement breaks the rules of the game.
+// Anything that changes the state of the game checks the rules first, and returns one of these instead of changing anything.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PieceType {
+    Knight,
+    City,
+    Village,
+    Stronghold
+}
+
+impl PieceType {
+    pub fn name(&self) -> String {
+        localization::text(match self {
+            PieceType::Knight => "piece_knight",
+            PieceType::City => "piece_city",
+            PieceType::Village => "piece_village",
+            PieceType::Stronghold => "piece_stronghold"
+        })
+    }
+
+    pub fn plural_name(&self) -> String {
+        localization::text(match self {
+            PieceType::Knight => "piece_knights",
+            PieceType::City => "piece_cities",
+            PieceType::Village => "piece_villages",
+            PieceType::Stronghold => "piece_strongholds"
+        })
+    }
+}
+
+// Why a space can't be moved to, built on, or covered with a board piece.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockedReason {
+    NotOnMap,
+    Water,
+    WrongTerrain(GameBoardSpaceType),
+    AlreadyOnMap,
+    NotNextToLand,
+    TooCloseToCity,
+    AlreadyBuiltOn,
+    NoKnightThere,
+    NotNextToKnight,
+    TooManyOpposingKnights,
+    EnemyCity
+}
+
+impl fmt::Display for BlockedReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BlockedReason::NotOnMap => write!(f, "{}", localization::text("blocked_not_on_map")),
+            BlockedReason::Water => write!(f, "{}", localization::text("blocked_water")),
+            BlockedReason::WrongTerrain(space_type) => write!(f, "{}", localization::format("blocked_wrong_terrain", &[&space_type.display_name()])),
+            BlockedReason::AlreadyOnMap => write!(f, "{}", localization::text("blocked_already_on_map")),
+            BlockedReason::NotNextToLand => write!(f, "{}", localization::text("blocked_not_next_to_land")),
+            BlockedReason::TooCloseToCity => write!(f, "{}", localization::text("blocked_too_close_to_city")),
+            BlockedReason::AlreadyBuiltOn => write!(f, "{}", localization::text("blocked_already_built_on")),
+            BlockedReason::NoKnightThere => write!(f, "{}", localization::text("blocked_no_knight_there")),
+            BlockedReason::NotNextToKnight => write!(f, "{}", localization::text("blocked_not_next_to_knight")),
+            BlockedReason::TooManyOpposingKnights => write!(f, "{}", localization::text("blocked_too_many_opposing_knights")),
+            BlockedReason::EnemyCity => write!(f, "{}", localization::text("blocked_enemy_city"))
+        }
+    }
+}
+
+// There is no error for acting out of turn: every action is taken for the active player, and input is ignored until they have taken over.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleError {
+    NoKnightAtSource,
+    // The knights at the source belong to another player.
+    NotYourKnight { owner: PlayerColor },
+    DestinationBlocked { reason: BlockedReason },
+    InventoryExhausted { piece: PieceType },
+    CannotAfford { cost: Resources },
+    TooManyRecruits { max: u8 },
+    NoHigherTitle,
+    ActionRepeatLimit { max: u32 },
+    // The action doesn't belong to the current stage of the game.
+    WrongPhase
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::NoKnightAtSource => write!(f, "{}", localization::text("error_no_knight_at_source")),
+            RuleError::NotYourKnight { owner } => write!(f, "{}", localization::format("error_not_your_knight", &[&owner.name()])),
+            RuleError::DestinationBlocked { reason } => write!(f, "{}", localization::format("error_destination_blocked", &[reason])),
+            RuleError::InventoryExhausted { piece } => write!(f, "{}", localization::format("error_inventory_exhausted", &[&piece.plural_name()])),
+            RuleError::CannotAfford { cost } => write!(f, "{}", localization::format("error_cannot_afford", &[cost])),
+            RuleError::TooManyRecruits { max } => write!(f, "{}", localization::format("error_too_many_recruits", &[max])),
+            RuleError::NoHigherTitle => write!(f, "{}", localization::text("error_no_higher_title")),
+            RuleError::ActionRepeatLimit { max } => write!(f, "{}", localization::format("error_action_repeat_limit", &[max])),
+            RuleError::WrongPhase => write!(f, "{}", localization::text("error_wrong_phase"))
+        }
+    }
+}
+
+impl error::Error for RuleError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        None
+    }
+}
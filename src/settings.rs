@@ -0,0 +1,267 @@
+use colors::Palette;
+use filereader::FileReader;
+use gameboard::combat::{CombatRules,TieRule};
+use localization;
+use turn_timer::{TimeoutAction,TimerRules};
+use turns::TurnRules;
+
+// How the map gets built before the cities are placed.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BoardSetupMode {
+    // Players take turns dropping a random piece, which they can turn before dropping it.
+    Random,
+    // Players hold a hand of pieces, and pick which piece to drop and its orientation.
+    Draft { hand_size: usize },
+    // The whole map is built automatically, and the game starts at city setup.
+    Generated
+}
+
+// Game options that can be changed without recompiling, read from assets/settings.cfg.
+#[derive(Clone)]
+pub struct GameSettings {
+    pub board_deck: String,
+    pub board_setup_mode: BoardSetupMode,
+    // Every board piece after the first must be dropped next to the pieces that are already down.
+    pub require_adjacent_board_pieces: bool,
+    // The finished map must be one connected landmass.  This also makes every piece after the first go next to the others.
+    pub require_connected_map: bool,
+    pub combat_rules: CombatRules,
+    pub turn_rules: TurnRules,
+    pub timer_rules: TimerRules,
+    // Cover the screen between turns, for players sharing one computer.
+    pub hotseat_handoff: bool,
+    // Which file in assets/lang the text comes from, e.g. "en" for lang/en.txt.
+    pub language: String,
+    pub palette: Palette,
+    // Sound effect volume in percent, from 0 to 100.
+    pub sound_volume: u32,
+    pub sound_muted: bool,
+    // Music volume in percent, from 0 to 100.
+    pub music_volume: u32
+}
+
+impl GameSettings {
+    pub fn defaults() -> GameSettings {
+        GameSettings {
+            board_deck: "decks/standard.deck".to_string(),
+            board_setup_mode: BoardSetupMode::Random,
+            require_adjacent_board_pieces: true,
+            require_connected_map: true,
+            combat_rules: CombatRules::defaults(),
+            turn_rules: TurnRules::defaults(),
+            timer_rules: TimerRules::defaults(),
+            hotseat_handoff: false,
+            language: localization::DEFAULT_LANGUAGE.to_string(),
+            palette: Palette::Standard,
+            sound_volume: 80,
+            sound_muted: false,
+            music_volume: 60
+        }
+    }
+
+    // Read settings from a file of "key = value" lines.  Anything after a '#' is a comment.
+    // Missing files and unknown keys are not fatal; whatever can't be read keeps its default value, and the problem is added to problems.
+    pub fn from_file(filereader: &FileReader, name: &str, problems: &mut Vec<String>) -> GameSettings {
+        let mut settings = GameSettings::defaults();
+
+        let source = match filereader.load_string(name) {
+            Ok(source) => source,
+            Err(e) => {
+                problems.push(format!("Could not read {}, using default settings: {:?}", name, e));
+                return settings;
+            }
+        };
+
+        let mut draft_hand_size = 3;
+        let mut setup_mode_name = "random".to_string();
+
+        for line in source.lines() {
+            let contents = match line.find('#') {
+                Some(comment_start) => &line[..comment_start],
+                None => line
+            };
+            if contents.trim().is_empty() {
+                continue;
+            }
+
+            let mut key_and_value = contents.splitn(2, '=');
+            let key = key_and_value.next().unwrap().trim();
+            let value = match key_and_value.next() {
+                Some(value) => value.trim(),
+                None => {
+                    problems.push(format!("{}: ignoring line without a value: {}", name, line));
+                    continue;
+                }
+            };
+
+            match key {
+                "board_deck" => { settings.board_deck = value.to_string(); }
+                "board_setup_mode" => { setup_mode_name = value.to_string(); }
+                "draft_hand_size" => {
+                    match value.parse::<usize>() {
+                        Ok(hand_size) if hand_size >= 1 && hand_size <= 9 => { draft_hand_size = hand_size; }
+                        _ => { problems.push(format!("{}: draft_hand_size must be a number from 1 to 9", name)); }
+                    }
+                }
+                "require_adjacent_board_pieces" => {
+                    match parse_bool(value) {
+                        Some(value) => { settings.require_adjacent_board_pieces = value; }
+                        None => { problems.push(format!("{}: require_adjacent_board_pieces must be true or false", name)); }
+                    }
+                }
+                "require_connected_map" => {
+                    match parse_bool(value) {
+                        Some(value) => { settings.require_connected_map = value; }
+                        None => { problems.push(format!("{}: require_connected_map must be true or false", name)); }
+                    }
+                }
+                "hotseat_handoff" => {
+                    match parse_bool(value) {
+                        Some(value) => { settings.hotseat_handoff = value; }
+                        None => { problems.push(format!("{}: hotseat_handoff must be true or false", name)); }
+                    }
+                }
+                "language" => { settings.language = value.to_string(); }
+                "palette" => {
+                    match Palette::from_name(value) {
+                        Some(palette) => { settings.palette = palette; }
+                        None => { problems.push(format!("{}: unknown palette {}", name, value)); }
+                    }
+                }
+                "sound_volume" => {
+                    match value.parse::<u32>() {
+                        Ok(volume) if volume <= 100 => { settings.sound_volume = volume; }
+                        _ => { problems.push(format!("{}: sound_volume must be a number from 0 to 100", name)); }
+                    }
+                }
+                "sound_muted" => {
+                    match parse_bool(value) {
+                        Some(value) => { settings.sound_muted = value; }
+                        None => { problems.push(format!("{}: sound_muted must be true or false", name)); }
+                    }
+                }
+                "music_volume" => {
+                    match value.parse::<u32>() {
+                        Ok(volume) if volume <= 100 => { settings.music_volume = volume; }
+                        _ => { problems.push(format!("{}: music_volume must be a number from 0 to 100", name)); }
+                    }
+                }
+                "stronghold_defence_bonus" => {
+                    match value.parse::<u32>() {
+                        Ok(bonus) => { settings.combat_rules.stronghold_defence_bonus = bonus; }
+                        Err(_) => { problems.push(format!("{}: stronghold_defence_bonus must be a number", name)); }
+                    }
+                }
+                "city_defence_bonus" => {
+                    match value.parse::<u32>() {
+                        Ok(bonus) => { settings.combat_rules.city_defence_bonus = bonus; }
+                        Err(_) => { problems.push(format!("{}: city_defence_bonus must be a number", name)); }
+                    }
+                }
+                "mountain_defence_bonus" => {
+                    match value.parse::<u32>() {
+                        Ok(bonus) => { settings.combat_rules.mountain_defence_bonus = bonus; }
+                        Err(_) => { problems.push(format!("{}: mountain_defence_bonus must be a number", name)); }
+                    }
+                }
+                "allow_city_attacks" => {
+                    match parse_bool(value) {
+                        Some(value) => { settings.combat_rules.allow_city_attacks = value; }
+                        None => { problems.push(format!("{}: allow_city_attacks must be true or false", name)); }
+                    }
+                }
+                "allow_mountain_attacks" => {
+                    match parse_bool(value) {
+                        Some(value) => { settings.combat_rules.allow_mountain_attacks = value; }
+                        None => { problems.push(format!("{}: allow_mountain_attacks must be true or false", name)); }
+                    }
+                }
+                "combat_tie_rule" => {
+                    match value {
+                        "standoff" => { settings.combat_rules.tie_rule = TieRule::Standoff; }
+                        "defender_wins" => { settings.combat_rules.tie_rule = TieRule::DefenderWins; }
+                        "mutual_destruction" => { settings.combat_rules.tie_rule = TieRule::MutualDestruction; }
+                        _ => { problems.push(format!("{}: unknown combat_tie_rule {}", name, value)); }
+                    }
+                }
+                "actions_per_turn" => {
+                    match parse_number(value, 1) {
+                        Some(number) => { settings.turn_rules.actions_per_turn = number; }
+                        None => { problems.push(format!("{}: actions_per_turn must be a number, at least 1", name)); }
+                    }
+                }
+                "max_repeats_per_action" => {
+                    match parse_number(value, 1) {
+                        Some(number) => { settings.turn_rules.max_repeats_per_action = number; }
+                        None => { problems.push(format!("{}: max_repeats_per_action must be a number, at least 1", name)); }
+                    }
+                }
+                "moves_per_movement" => {
+                    match parse_number(value, 1) {
+                        Some(number) => { settings.turn_rules.moves_per_movement = number; }
+                        None => { problems.push(format!("{}: moves_per_movement must be a number, at least 1", name)); }
+                    }
+                }
+                "knights_per_upkeep_grain" => {
+                    match parse_number(value, 0) {
+                        Some(number) => { settings.turn_rules.knights_per_upkeep_grain = number; }
+                        None => { problems.push(format!("{}: knights_per_upkeep_grain must be a number", name)); }
+                    }
+                }
+                "max_stored_resources" => {
+                    match parse_number(value, 0) {
+                        Some(number) => { settings.turn_rules.max_stored_resources = number; }
+                        None => { problems.push(format!("{}: max_stored_resources must be a number", name)); }
+                    }
+                }
+                "turn_time_limit" => {
+                    match parse_number(value, 0) {
+                        Some(seconds) => { settings.timer_rules.turn_time_limit = seconds; }
+                        None => { problems.push(format!("{}: turn_time_limit must be a number of seconds", name)); }
+                    }
+                }
+                "game_time_limit" => {
+                    match parse_number(value, 0) {
+                        Some(seconds) => { settings.timer_rules.game_time_limit = seconds; }
+                        None => { problems.push(format!("{}: game_time_limit must be a number of seconds", name)); }
+                    }
+                }
+                "timeout_action" => {
+                    match value {
+                        "pass" => { settings.timer_rules.timeout_action = TimeoutAction::Pass; }
+                        "random_move" => { settings.timer_rules.timeout_action = TimeoutAction::RandomMove; }
+                        _ => { problems.push(format!("{}: unknown timeout_action {}", name, value)); }
+                    }
+                }
+                _ => { problems.push(format!("{}: unknown setting {}", name, key)); }
+            }
+        }
+
+        settings.board_setup_mode = match setup_mode_name.as_str() {
+            "random" => BoardSetupMode::Random,
+            "draft" => BoardSetupMode::Draft { hand_size: draft_hand_size },
+            "generated" => BoardSetupMode::Generated,
+            _ => {
+                problems.push(format!("{}: unknown board_setup_mode {}", name, setup_mode_name));
+                BoardSetupMode::Random
+            }
+        };
+
+        settings
+    }
+}
+
+fn parse_number(value: &str, minimum: u32) -> Option<u32> {
+    match value.parse::<u32>() {
+        Ok(number) if number >= minimum => Some(number),
+        _ => None
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "on" => Some(true),
+        "false" | "no" | "off" => Some(false),
+        _ => None
+    }
+}
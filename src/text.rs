@@ -0,0 +1,531 @@
+use batch;
+use drawing::{ColorSpec,PositionSpec,SizeSpec,TextDrawingBaggage};
+use fonts::{FontRegistry,TextStyle};
+use freetype;
+use gl;
+use std;
+use std::collections::HashMap;
+
+// Text layout and rendering.
+//
+// FreeType renders each glyph once, into a texture (an atlas) that holds every glyph used at one font size.
+// A string is drawn as one batch of quads cut out of the atlas, so it only takes one draw call however long it is.
+//
+// Text is laid out in pixels, with kerning between pairs of characters, line breaks at '\n',
+// and word wrap when there is a maximum width.  A layout can be measured without drawing it.
+
+const ATLAS_WIDTH: u32 = 512;
+const INITIAL_ATLAS_HEIGHT: u32 = 128;
+// Empty space around each glyph in the atlas, so that neighbouring glyphs don't bleed into each other.
+const GLYPH_PADDING: u32 = 1;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right
+}
+
+#[derive(Clone, Copy)]
+struct Glyph {
+    // The face that the glyph was rendered from, which can be a fallback face.
+    face_index: usize,
+    glyph_index: u32,
+    // Where the bitmap is in the atlas, in pixels.
+    atlas_x: u32,
+    atlas_y: u32,
+    width: u32,
+    height: u32,
+    bearing_x: i32,
+    bearing_y: i32,
+    advance: i32
+}
+
+// What layout needs to know about the glyphs in an atlas.  (kept apart from the texture, so that it doesn't need GL)
+struct GlyphMetrics {
+    glyphs: HashMap<char, Glyph>,
+    kerning: HashMap<(char, char), i32>,
+    // In pixels.
+    line_height: i32,
+    ascender: i32,
+    descender: i32
+}
+
+struct GlyphAtlas {
+    gl: gl::Gl,
+    texture_id: gl::types::GLuint,
+    // A copy of the texture, so that it can be uploaded again when the atlas grows.
+    pixels: Vec<u8>,
+    height: u32,
+    // Glyphs are packed in rows, left to right, top to bottom.
+    cursor_x: u32,
+    cursor_y: u32,
+    row_height: u32,
+    metrics: GlyphMetrics
+}
+
+impl GlyphAtlas {
+    // The font face must already be set to the size of this atlas.
+    fn new(gl: &gl::Gl, font_face: &freetype::face::Face) -> GlyphAtlas {
+        let (line_height, ascender, descender) = match font_face.size_metrics() {
+            Some(metrics) => ((metrics.height >> 6) as i32, (metrics.ascender >> 6) as i32, (metrics.descender >> 6) as i32),
+            None => (0, 0, 0)
+        };
+
+        let mut texture_id: gl::types::GLuint = 0;
+        unsafe {
+            gl.GenTextures(1, &mut texture_id);
+        }
+        let atlas = GlyphAtlas {
+            gl: gl.clone(),
+            texture_id: texture_id,
+            pixels: vec![0; (ATLAS_WIDTH * INITIAL_ATLAS_HEIGHT) as usize],
+            height: INITIAL_ATLAS_HEIGHT,
+            cursor_x: GLYPH_PADDING,
+            cursor_y: GLYPH_PADDING,
+            row_height: 0,
+            metrics: GlyphMetrics {
+                glyphs: HashMap::new(),
+                kerning: HashMap::new(),
+                line_height: line_height,
+                ascender: ascender,
+                descender: descender
+            }
+        };
+        atlas.upload();
+        atlas
+    }
+
+    // Upload the whole atlas.
+    fn upload(&self) {
+        let gl = &self.gl;
+        unsafe {
+            gl.ActiveTexture(gl::TEXTURE0);
+            gl.BindTexture(gl::TEXTURE_2D, self.texture_id);
+            gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl.TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RED as i32,
+                ATLAS_WIDTH as i32,
+                self.height as i32,
+                0,
+                gl::RED,
+                gl::UNSIGNED_BYTE,
+                self.pixels.as_ptr() as *const std::os::raw::c_void
+            );
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+        }
+    }
+
+    // Render a glyph into the atlas.  The font face must already be set to the size of this atlas.
+    fn add_glyph(&mut self, character: char, font_face: &freetype::face::Face, face_index: usize) -> Glyph {
+        let glyph_index = font_face.get_char_index(character as usize);
+        if font_face.load_char(character as usize, freetype::face::LoadFlag::RENDER).is_err() {
+            println!("Could not render the character {:?}", character);
+        }
+        let glyph_slot = font_face.glyph();
+        let bitmap = glyph_slot.bitmap();
+        let mut width = bitmap.width() as u32;
+        let mut height = bitmap.rows() as u32;
+
+        // A glyph wider than the whole atlas can never be packed into it, so it is left out.  It still takes up its space in the text.
+        if width + 2 * GLYPH_PADDING > ATLAS_WIDTH {
+            println!("The character {:?} is too wide for the glyph atlas", character);
+            width = 0;
+            height = 0;
+        }
+
+        // Start a new row if the glyph doesn't fit on this one, and make the atlas taller if it doesn't fit at all.
+        if self.cursor_x + width + GLYPH_PADDING > ATLAS_WIDTH {
+            self.cursor_x = GLYPH_PADDING;
+            self.cursor_y += self.row_height + GLYPH_PADDING;
+            self.row_height = 0;
+        }
+        let mut grew = false;
+        while self.cursor_y + height + GLYPH_PADDING > self.height {
+            self.height *= 2;
+            self.pixels.resize((ATLAS_WIDTH * self.height) as usize, 0);
+            grew = true;
+        }
+
+        // Copy the bitmap in, one row at a time.  (rows in the FreeType bitmap can be padded)
+        let pitch = bitmap.pitch().abs() as usize;
+        let buffer = bitmap.buffer();
+        for row in 0..height as usize {
+            let source_start = row * pitch;
+            let destination_start = (self.cursor_y as usize + row) * ATLAS_WIDTH as usize + self.cursor_x as usize;
+            self.pixels[destination_start..destination_start + width as usize].copy_from_slice(&buffer[source_start..source_start + width as usize]);
+        }
+
+        if grew {
+            self.upload();
+        } else if width > 0 && height > 0 {
+            let glyph_pixels: Vec<u8> = (0..height as usize).flat_map(|row| {
+                let start = (self.cursor_y as usize + row) * ATLAS_WIDTH as usize + self.cursor_x as usize;
+                self.pixels[start..start + width as usize].to_vec()
+            }).collect();
+            unsafe {
+                self.gl.ActiveTexture(gl::TEXTURE0);
+                self.gl.BindTexture(gl::TEXTURE_2D, self.texture_id);
+                self.gl.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+                self.gl.TexSubImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    self.cursor_x as i32,
+                    self.cursor_y as i32,
+                    width as i32,
+                    height as i32,
+                    gl::RED,
+                    gl::UNSIGNED_BYTE,
+                    glyph_pixels.as_ptr() as *const std::os::raw::c_void
+                );
+            }
+        }
+
+        let glyph = Glyph {
+            face_index: face_index,
+            glyph_index: glyph_index,
+            atlas_x: self.cursor_x,
+            atlas_y: self.cursor_y,
+            width: width,
+            height: height,
+            bearing_x: glyph_slot.bitmap_left(),
+            bearing_y: glyph_slot.bitmap_top(),
+            advance: (glyph_slot.advance().x >> 6) as i32
+        };
+        self.cursor_x += width + GLYPH_PADDING;
+        self.row_height = std::cmp::max(self.row_height, height);
+        self.metrics.glyphs.insert(character, glyph);
+        glyph
+    }
+}
+
+impl Drop for GlyphAtlas {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.DeleteTextures(1, &mut self.texture_id);
+        }
+    }
+}
+
+// One atlas for each face and font size that has been used.
+pub struct TextCache {
+    atlases: HashMap<(usize, u32), GlyphAtlas>,
+    // The size each font face was last set to, so that it is only changed when a glyph has to be rendered.
+    face_font_sizes: HashMap<usize, u32>
+}
+
+fn set_face_size(face_font_sizes: &mut HashMap<usize, u32>, fonts: &FontRegistry, face_index: usize, font_size: u32, display_dpi: (f32, f32, f32)) {
+    if face_font_sizes.get(&face_index) != Some(&font_size) {
+        let (_ddpi, hdpi, vdpi) = display_dpi;
+        fonts.face(face_index).set_char_size((font_size << 6) as isize, 0, hdpi as u32, vdpi as u32).unwrap();
+        face_font_sizes.insert(face_index, font_size);
+    }
+}
+
+impl TextCache {
+    pub fn new() -> TextCache {
+        TextCache {
+            atlases: HashMap::new(),
+            face_font_sizes: HashMap::new()
+        }
+    }
+
+    // Make sure that every character in the text has been rendered, and look up the kerning between them.
+    // Characters that the face doesn't have are rendered from a fallback face, into the same atlas.
+    fn prepare(&mut self, gl: &gl::Gl, fonts: &FontRegistry, face_index: usize, display_dpi: (f32, f32, f32), font_size: u32, text: &str) {
+        let TextCache { ref mut atlases, ref mut face_font_sizes } = *self;
+
+        if !atlases.contains_key(&(face_index, font_size)) {
+            set_face_size(face_font_sizes, fonts, face_index, font_size, display_dpi);
+            atlases.insert((face_index, font_size), GlyphAtlas::new(gl, fonts.face(face_index)));
+        }
+        let atlas = atlases.get_mut(&(face_index, font_size)).unwrap();
+
+        let mut previous_character: Option<char> = None;
+        for character in text.chars() {
+            if character == '\n' {
+                previous_character = None;
+                continue;
+            }
+            if !atlas.metrics.glyphs.contains_key(&character) {
+                let glyph_face_index = fonts.face_for_character(face_index, character);
+                set_face_size(face_font_sizes, fonts, glyph_face_index, font_size, display_dpi);
+                atlas.add_glyph(character, fonts.face(glyph_face_index), glyph_face_index);
+            }
+            if let Some(previous_character) = previous_character {
+                if !atlas.metrics.kerning.contains_key(&(previous_character, character)) {
+                    let left_glyph = atlas.metrics.glyphs[&previous_character];
+                    let right_glyph = atlas.metrics.glyphs[&character];
+                    // Kerning only makes sense between two glyphs from the same face.
+                    let kerning_face = fonts.face(left_glyph.face_index);
+                    let kerning = if left_glyph.face_index == right_glyph.face_index && kerning_face.has_kerning() {
+                        set_face_size(face_font_sizes, fonts, left_glyph.face_index, font_size, display_dpi);
+                        match kerning_face.get_kerning(left_glyph.glyph_index, right_glyph.glyph_index, freetype::face::KerningMode::KerningDefault) {
+                            Ok(vector) => (vector.x >> 6) as i32,
+                            Err(_) => 0
+                        }
+                    } else {
+                        0
+                    };
+                    atlas.metrics.kerning.insert((previous_character, character), kerning);
+                }
+            }
+            previous_character = Some(character);
+        }
+    }
+}
+
+struct PlacedGlyph {
+    glyph: Glyph,
+    // Pen position, in pixels from the left end of the first baseline.  y goes up.
+    x: f32,
+    y: f32
+}
+
+// Text that has been broken into lines and positioned, ready to draw.  All of the measurements are in pixels.
+pub struct TextLayout {
+    // Which atlas the glyphs are in.
+    atlas_key: (usize, u32),
+    glyphs: Vec<PlacedGlyph>,
+    pub width: f32,
+    pub num_lines: usize,
+    // How far the text goes above the first baseline, and below it (negative) on the last line.
+    pub top: f32,
+    pub bottom: f32
+}
+
+impl TextLayout {
+    pub fn height(&self) -> f32 {
+        self.top - self.bottom
+    }
+}
+
+fn kerned_width(metrics: &GlyphMetrics, line: &str) -> f32 {
+    let mut width = 0;
+    let mut previous_character: Option<char> = None;
+    for character in line.chars() {
+        if let Some(previous_character) = previous_character {
+            width += *metrics.kerning.get(&(previous_character, character)).unwrap_or(&0);
+        }
+        width += metrics.glyphs[&character].advance;
+        previous_character = Some(character);
+    }
+    width as f32
+}
+
+// Break a paragraph into lines no wider than max_width, between words.  A word that is too long by itself gets a line to itself.
+fn wrap_paragraph(metrics: &GlyphMetrics, paragraph: &str, max_width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current_line = String::new();
+    for word in paragraph.split(' ') {
+        let candidate = if current_line.is_empty() { word.to_string() } else { format!("{} {}", current_line, word) };
+        if !current_line.is_empty() && kerned_width(metrics, &candidate) > max_width {
+            lines.push(current_line);
+            current_line = word.to_string();
+        } else {
+            current_line = candidate;
+        }
+    }
+    lines.push(current_line);
+    lines
+}
+
+// max_width is in pixels.  Lines are aligned within max_width, or within the widest line if there is no max_width.
+pub fn layout_text(baggage: &mut TextDrawingBaggage, style: TextStyle, text: &str, max_width: Option<f32>, align: TextAlign) -> TextLayout {
+    let atlas_key = (baggage.fonts.face_index(style.face_name()), style.font_size());
+    baggage.text_cache.prepare(&baggage.gl, baggage.fonts, atlas_key.0, baggage.display_dpi, atlas_key.1, text);
+    let metrics = &baggage.text_cache.atlases[&atlas_key].metrics;
+
+    let mut lines: Vec<String> = Vec::new();
+    for paragraph in text.split('\n') {
+        match max_width {
+            Some(max_width) => { lines.extend(wrap_paragraph(metrics, paragraph, max_width)); }
+            None => { lines.push(paragraph.to_string()); }
+        }
+    }
+
+    let line_widths: Vec<f32> = lines.iter().map(|line| kerned_width(metrics, line)).collect();
+    let widest_line = line_widths.iter().cloned().fold(0.0, f32::max);
+    let block_width = max_width.unwrap_or(widest_line);
+
+    let mut glyphs = Vec::new();
+    let mut top: Option<f32> = None;
+    let mut bottom: Option<f32> = None;
+    for (line_number, line) in lines.iter().enumerate() {
+        let baseline = -(line_number as f32) * metrics.line_height as f32;
+        let mut pen_x = match align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => (block_width - line_widths[line_number]) / 2.0,
+            TextAlign::Right => block_width - line_widths[line_number]
+        };
+
+        let mut previous_character: Option<char> = None;
+        for character in line.chars() {
+            if let Some(previous_character) = previous_character {
+                pen_x += *metrics.kerning.get(&(previous_character, character)).unwrap_or(&0) as f32;
+            }
+            let glyph = metrics.glyphs[&character];
+            glyphs.push(PlacedGlyph { glyph: glyph, x: pen_x, y: baseline });
+            pen_x += glyph.advance as f32;
+            previous_character = Some(character);
+        }
+
+        // Measure the ink rather than the font's ascender and descender, so that text lines up tightly to its origin.
+        for glyph in line.chars().map(|character| metrics.glyphs[&character]).filter(|glyph| glyph.height > 0) {
+            if line_number == 0 {
+                let glyph_top = glyph.bearing_y as f32;
+                top = Some(top.map_or(glyph_top, |top| top.max(glyph_top)));
+            }
+            if line_number == lines.len() - 1 {
+                let glyph_bottom = baseline + glyph.bearing_y as f32 - glyph.height as f32;
+                bottom = Some(bottom.map_or(glyph_bottom, |bottom| bottom.min(glyph_bottom)));
+            }
+        }
+    }
+
+    // Lines with nothing to see (e.g. only spaces) fall back to the font's own measurements.
+    let last_baseline = -((lines.len() - 1) as f32) * metrics.line_height as f32;
+    TextLayout {
+        atlas_key: atlas_key,
+        glyphs: glyphs,
+        width: widest_line,
+        num_lines: lines.len(),
+        top: top.unwrap_or(metrics.ascender as f32),
+        bottom: bottom.unwrap_or(last_baseline + metrics.descender as f32)
+    }
+}
+
+// Draw a layout with the left end of its first baseline at position (in screen coordinates).
+pub fn draw_layout(baggage: &mut TextDrawingBaggage, layout: &TextLayout, position: PositionSpec, color: ColorSpec) {
+    let gl = &baggage.gl;
+    let shader_program = &baggage.shader_program;
+    let (window_width, window_height) = baggage.drawable_size;
+    let atlas = &baggage.text_cache.atlases[&layout.atlas_key];
+
+    shader_program.set_used();
+    unsafe {
+        let color_loc = gl.GetUniformLocation(shader_program.id(), std::ffi::CString::new("textColor").unwrap().as_ptr());
+        gl.ProgramUniform3f(shader_program.id(), color_loc, color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0);
+
+        let text_loc = gl.GetUniformLocation(shader_program.id(), std::ffi::CString::new("text").unwrap().as_ptr());
+        gl.ProgramUniform1i(shader_program.id(), text_loc, 0);
+    }
+
+    // TODO do the scaling in the GPU
+    let scaling_x = 2.0 / window_width as f32;
+    let scaling_y = 2.0 / window_height as f32;
+    let atlas_width = ATLAS_WIDTH as f32;
+    let atlas_height = atlas.height as f32;
+
+    let mut vertices: Vec<f32> = Vec::new();
+    for placed_glyph in layout.glyphs.iter().filter(|placed_glyph| placed_glyph.glyph.width > 0 && placed_glyph.glyph.height > 0) {
+        let glyph = &placed_glyph.glyph;
+        let left = position.x + (placed_glyph.x + glyph.bearing_x as f32) * scaling_x;
+        let top = position.y + (placed_glyph.y + glyph.bearing_y as f32) * scaling_y;
+        let right = left + glyph.width as f32 * scaling_x;
+        let bottom = top - glyph.height as f32 * scaling_y;
+
+        let u_left = glyph.atlas_x as f32 / atlas_width;
+        let u_right = (glyph.atlas_x + glyph.width) as f32 / atlas_width;
+        let v_top = glyph.atlas_y as f32 / atlas_height;
+        let v_bottom = (glyph.atlas_y + glyph.height) as f32 / atlas_height;
+
+        let top_left =     [left,  top,    u_left,  v_top];
+        let top_right =    [right, top,    u_right, v_top];
+        let bottom_left =  [left,  bottom, u_left,  v_bottom];
+        let bottom_right = [right, bottom, u_right, v_bottom];
+        for corner in [top_left, top_right, bottom_left, top_right, bottom_left, bottom_right].iter() {
+            vertices.extend_from_slice(corner);
+        }
+    }
+
+    unsafe {
+        gl.ActiveTexture(gl::TEXTURE0);
+        gl.BindTexture(gl::TEXTURE_2D, atlas.texture_id);
+    }
+    batch::draw_vertices(gl, batch::VertexFormat::Textured, gl::TRIANGLES, &vertices);
+}
+
+// The size of some text in screen coordinates, without drawing it.  max_width is in screen coordinates too.
+pub fn measure_text(baggage: &mut TextDrawingBaggage, style: TextStyle, text: &str, max_width: Option<f32>) -> SizeSpec {
+    let (window_width, window_height) = baggage.drawable_size;
+    let scaling_x = 2.0 / window_width as f32;
+    let scaling_y = 2.0 / window_height as f32;
+    let layout = layout_text(baggage, style, text, max_width.map(|max_width| max_width / scaling_x), TextAlign::Left);
+    SizeSpec { x: layout.width * scaling_x, y: layout.height() * scaling_y }
+}
+
+// Draw text inside a box that is width wide, with its top left corner at position (in screen coordinates).
+// The text wraps onto as many lines as it needs.  Returns the height of the text, in screen coordinates.
+pub fn draw_text_box(
+    baggage: &mut TextDrawingBaggage,
+    position: PositionSpec,
+    width: f32,
+    align: TextAlign,
+    style: TextStyle,
+    color: ColorSpec,
+    text: &str) -> f32
+{
+    let (window_width, window_height) = baggage.drawable_size;
+    let scaling_x = 2.0 / window_width as f32;
+    let scaling_y = 2.0 / window_height as f32;
+    let layout = layout_text(baggage, style, text, Some(width / scaling_x), align);
+    let baseline = PositionSpec { x: position.x, y: position.y - layout.top * scaling_y };
+    draw_layout(baggage, &layout, baseline, color);
+    layout.height() * scaling_y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every glyph is 10 pixels wide, except for a narrow 'i', and "AV" is kerned together.
+    fn metrics() -> GlyphMetrics {
+        let mut glyphs = HashMap::new();
+        for character in "abcdefghjklmnopqrstuvwxyzAV ".chars() {
+            glyphs.insert(character, glyph(10));
+        }
+        glyphs.insert('i', glyph(4));
+        let mut kerning = HashMap::new();
+        kerning.insert(('A', 'V'), -3);
+        GlyphMetrics { glyphs: glyphs, kerning: kerning, line_height: 20, ascender: 15, descender: -5 }
+    }
+
+    fn glyph(advance: i32) -> Glyph {
+        Glyph { face_index: 0, glyph_index: 0, atlas_x: 0, atlas_y: 0, width: 0, height: 0, bearing_x: 0, bearing_y: 0, advance: advance }
+    }
+
+    #[test]
+    fn kerned_widths() {
+        let metrics = metrics();
+        assert_eq!(kerned_width(&metrics, ""), 0.0);
+        assert_eq!(kerned_width(&metrics, "abc"), 30.0);
+        assert_eq!(kerned_width(&metrics, "aib"), 24.0);
+        assert_eq!(kerned_width(&metrics, "AV"), 17.0);
+        assert_eq!(kerned_width(&metrics, "VA"), 20.0);
+        assert_eq!(kerned_width(&metrics, "a b"), 30.0);
+    }
+
+    #[test]
+    fn wrap_between_words() {
+        let metrics = metrics();
+        assert_eq!(wrap_paragraph(&metrics, "ab cd ef", 1000.0), vec!["ab cd ef"]);
+        assert_eq!(wrap_paragraph(&metrics, "ab cd ef", 50.0), vec!["ab cd", "ef"]);
+        assert_eq!(wrap_paragraph(&metrics, "ab cd ef", 49.0), vec!["ab", "cd", "ef"]);
+        // Kerning counts towards the width of a line.
+        assert_eq!(wrap_paragraph(&metrics, "ab AV", 47.0), vec!["ab AV"]);
+        assert_eq!(wrap_paragraph(&metrics, "", 50.0), vec![""]);
+    }
+
+    #[test]
+    fn long_words_get_their_own_line() {
+        let metrics = metrics();
+        assert_eq!(wrap_paragraph(&metrics, "abcdefgh ab", 30.0), vec!["abcdefgh", "ab"]);
+        assert_eq!(wrap_paragraph(&metrics, "ab abcdefgh ab", 30.0), vec!["ab", "abcdefgh", "ab"]);
+    }
+}
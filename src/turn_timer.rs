@@ -0,0 +1,190 @@
+use PlayerColor;
+use std::collections::HashMap;
+
+// Time limits, for tournament play.
+//
+// There are two limits, and either can be turned off:
+// - a limit on each turn, which starts again every turn, and
+// - a limit on the whole game for each player, which only counts down during that player's turns (like a chess clock).
+// When a player runs out of either, the timer plays for them (see TimeoutAction).
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TimeoutAction {
+    // Give up the rest of the turn.
+    Pass,
+    // Make a random legal move, then give up the rest of the turn.
+    RandomMove
+}
+
+#[derive(Clone, Copy)]
+pub struct TimerRules {
+    // Seconds.  0 means there is no limit.
+    pub turn_time_limit: u32,
+    pub game_time_limit: u32,
+    pub timeout_action: TimeoutAction
+}
+
+impl TimerRules {
+    pub fn defaults() -> TimerRules {
+        TimerRules {
+            turn_time_limit: 0,
+            game_time_limit: 0,
+            timeout_action: TimeoutAction::Pass
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.turn_time_limit > 0 || self.game_time_limit > 0
+    }
+}
+
+pub struct TurnTimer {
+    // Milliseconds since the program started.
+    current_time: u32,
+    turn_started_at: u32,
+    // The clock is stopped between turns, e.g. while the screen is covered for the next player.
+    running: bool,
+    // Milliseconds used by each player on their earlier turns.
+    game_time_used: HashMap<PlayerColor, u32>
+}
+
+impl TurnTimer {
+    pub fn new() -> TurnTimer {
+        TurnTimer {
+            current_time: 0,
+            turn_started_at: 0,
+            running: false,
+            game_time_used: HashMap::new()
+        }
+    }
+
+    // Call once a frame, with the number of milliseconds since the program started.
+    pub fn update_time(&mut self, current_time: u32) {
+        self.current_time = current_time;
+    }
+
+    pub fn start_turn(&mut self) {
+        self.turn_started_at = self.current_time;
+        self.running = true;
+    }
+
+    // Stop the clock, and charge the time to the player whose turn it was.
+    pub fn end_turn(&mut self, player: PlayerColor) {
+        let turn_time_used = self.turn_time_used();
+        *self.game_time_used.entry(player).or_insert(0) += turn_time_used;
+        self.running = false;
+    }
+
+    fn turn_time_used(&self) -> u32 {
+        if self.running { self.current_time.saturating_sub(self.turn_started_at) } else { 0 }
+    }
+
+    // Milliseconds left in this turn, or None if there is no limit.
+    pub fn turn_time_left(&self, rules: &TimerRules) -> Option<u32> {
+        if rules.turn_time_limit == 0 {
+            return None;
+        }
+        Some(rules.turn_time_limit.saturating_mul(1000).saturating_sub(self.turn_time_used()))
+    }
+
+    // Milliseconds left on the player's game clock, or None if there is no limit.
+    pub fn game_time_left(&self, player: PlayerColor, rules: &TimerRules, is_active_player: bool) -> Option<u32> {
+        if rules.game_time_limit == 0 {
+            return None;
+        }
+        let mut game_time_used = *self.game_time_used.get(&player).unwrap_or(&0);
+        if is_active_player {
+            game_time_used += self.turn_time_used();
+        }
+        Some(rules.game_time_limit.saturating_mul(1000).saturating_sub(game_time_used))
+    }
+
+    pub fn out_of_time(&self, player: PlayerColor, rules: &TimerRules) -> bool {
+        self.running && (self.turn_time_left(rules) == Some(0) || self.game_time_left(player, rules, true) == Some(0))
+    }
+}
+
+// e.g. "1:05"
+pub fn format_time(milliseconds: u32) -> String {
+    // Round up, so that the clock shows 0:00 only when the time is up.
+    let seconds = milliseconds / 1000 + if milliseconds % 1000 > 0 { 1 } else { 0 };
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(turn_time_limit: u32, game_time_limit: u32) -> TimerRules {
+        TimerRules { turn_time_limit: turn_time_limit, game_time_limit: game_time_limit, timeout_action: TimeoutAction::Pass }
+    }
+
+    #[test]
+    fn no_limits() {
+        let mut timer = TurnTimer::new();
+        timer.start_turn();
+        timer.update_time(1_000_000);
+        assert!(!rules(0, 0).enabled());
+        assert_eq!(timer.turn_time_left(&rules(0, 0)), None);
+        assert_eq!(timer.game_time_left(PlayerColor::Red, &rules(0, 0), true), None);
+        assert!(!timer.out_of_time(PlayerColor::Red, &rules(0, 0)));
+    }
+
+    #[test]
+    fn turn_limit_starts_again_every_turn() {
+        let rules = rules(30, 0);
+        let mut timer = TurnTimer::new();
+        timer.update_time(5000);
+        timer.start_turn();
+        timer.update_time(15000);
+        assert_eq!(timer.turn_time_left(&rules), Some(20000));
+        timer.update_time(40000);
+        assert_eq!(timer.turn_time_left(&rules), Some(0));
+        assert!(timer.out_of_time(PlayerColor::Red, &rules));
+
+        timer.end_turn(PlayerColor::Red);
+        assert!(!timer.out_of_time(PlayerColor::Blue, &rules));
+        timer.start_turn();
+        assert_eq!(timer.turn_time_left(&rules), Some(30000));
+    }
+
+    #[test]
+    fn game_clock_only_runs_on_the_players_turns() {
+        let rules = rules(0, 60);
+        let mut timer = TurnTimer::new();
+        timer.start_turn();
+        timer.update_time(10000);
+        assert_eq!(timer.game_time_left(PlayerColor::Red, &rules, true), Some(50000));
+        assert_eq!(timer.game_time_left(PlayerColor::Blue, &rules, false), Some(60000));
+        timer.end_turn(PlayerColor::Red);
+
+        // Time between turns isn't charged to anyone.
+        timer.update_time(20000);
+        timer.start_turn();
+        timer.update_time(45000);
+        timer.end_turn(PlayerColor::Blue);
+        assert_eq!(timer.game_time_left(PlayerColor::Red, &rules, false), Some(50000));
+        assert_eq!(timer.game_time_left(PlayerColor::Blue, &rules, false), Some(35000));
+    }
+
+    #[test]
+    fn huge_limits_dont_overflow() {
+        let rules = rules(::std::u32::MAX, ::std::u32::MAX);
+        let mut timer = TurnTimer::new();
+        timer.start_turn();
+        timer.update_time(1000);
+        assert_eq!(timer.turn_time_left(&rules), Some(::std::u32::MAX - 1000));
+        assert_eq!(timer.game_time_left(PlayerColor::Red, &rules, true), Some(::std::u32::MAX - 1000));
+    }
+
+    #[test]
+    fn format_time_rounds_up() {
+        assert_eq!(format_time(0), "0:00");
+        assert_eq!(format_time(1), "0:01");
+        assert_eq!(format_time(1000), "0:01");
+        assert_eq!(format_time(65000), "1:05");
+        assert_eq!(format_time(64001), "1:05");
+        assert_eq!(format_time(3600000), "60:00");
+        assert_eq!(format_time(::std::u32::MAX), "71582:48");
+    }
+}
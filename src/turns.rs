@@ -0,0 +1,154 @@
+use actions::PlayerActionType;
+use std::collections::HashMap;
+
+// The structure of a turn, once the cities have been placed:
+//
+// 1. Start of turn: the player's settlements produce resources, and the player pays upkeep for their knights.
+// 2. Actions: the player picks actions from the menu, until they have used up their actions or choose to stop.
+// 3. End of turn: anything the player can't store is lost.
+//
+// The numbers that control this are house rules, read from settings.cfg.
+
+#[derive(Clone, Copy)]
+pub struct TurnRules {
+    pub actions_per_turn: u32,
+    // How many times the same action can be picked in one turn.
+    pub max_repeats_per_action: u32,
+    // How many knights can be moved with one Movement action.
+    pub moves_per_movement: u32,
+    // Every this many knights on the board cost 1 Grain at the start of the turn.  0 turns upkeep off.
+    pub knights_per_upkeep_grain: u32,
+    // The most of each resource that a player can keep at the end of their turn.  0 means there is no limit.
+    pub max_stored_resources: u32
+}
+
+impl TurnRules {
+    pub fn defaults() -> TurnRules {
+        TurnRules {
+            actions_per_turn: 1,
+            max_repeats_per_action: 1,
+            moves_per_movement: 2,
+            knights_per_upkeep_grain: 0,
+            max_stored_resources: 0
+        }
+    }
+}
+
+// Keeps count of where we are in the game, and what has been done this turn.
+pub struct TurnState {
+    // Both counters start at 1 when the first player takes their first turn after city setup, and are 0 before that.
+    turn_number: u32,
+    round_number: u32,
+    actions_taken: u32,
+    action_counts: HashMap<PlayerActionType, u32>
+}
+
+impl TurnState {
+    pub fn new() -> TurnState {
+        TurnState {
+            turn_number: 0,
+            round_number: 0,
+            actions_taken: 0,
+            action_counts: HashMap::new()
+        }
+    }
+
+    pub fn turn_number(&self) -> u32 {
+        self.turn_number
+    }
+
+    pub fn round_number(&self) -> u32 {
+        self.round_number
+    }
+
+    pub fn actions_taken(&self) -> u32 {
+        self.actions_taken
+    }
+
+    // Move on to the next turn.  A new round starts when play gets back around to the first player.
+    pub fn start_turn(&mut self, first_player: bool) {
+        if self.turn_number == 0 {
+            self.round_number = 1;
+        } else if first_player {
+            self.round_number += 1;
+        }
+        self.turn_number += 1;
+        self.actions_taken = 0;
+        self.action_counts.clear();
+    }
+
+    // Returns true if the player has used up all of their actions for this turn.
+    pub fn record_action(&mut self, action_type: PlayerActionType, rules: &TurnRules) -> bool {
+        self.actions_taken += 1;
+        *self.action_counts.entry(action_type).or_insert(0) += 1;
+        self.actions_taken >= rules.actions_per_turn
+    }
+
+    pub fn action_allowed(&self, action_type: PlayerActionType, rules: &TurnRules) -> bool {
+        self.actions_taken < rules.actions_per_turn &&
+            self.action_counts.get(&action_type).map_or(true, |&count| count < rules.max_repeats_per_action)
+    }
+
+    pub fn actions_left(&self, rules: &TurnRules) -> u32 {
+        rules.actions_per_turn.saturating_sub(self.actions_taken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(actions_per_turn: u32, max_repeats_per_action: u32) -> TurnRules {
+        TurnRules { actions_per_turn: actions_per_turn, max_repeats_per_action: max_repeats_per_action, ..TurnRules::defaults() }
+    }
+
+    #[test]
+    fn rounds_start_with_the_first_player() {
+        let mut turn_state = TurnState::new();
+        assert_eq!((turn_state.turn_number(), turn_state.round_number()), (0, 0));
+        // Whoever takes the first turn starts round 1.
+        turn_state.start_turn(false);
+        assert_eq!((turn_state.turn_number(), turn_state.round_number()), (1, 1));
+        turn_state.start_turn(false);
+        turn_state.start_turn(false);
+        assert_eq!((turn_state.turn_number(), turn_state.round_number()), (3, 1));
+        turn_state.start_turn(true);
+        assert_eq!((turn_state.turn_number(), turn_state.round_number()), (4, 2));
+    }
+
+    #[test]
+    fn record_action_counts_down() {
+        let rules = rules(3, 3);
+        let mut turn_state = TurnState::new();
+        turn_state.start_turn(true);
+        assert_eq!(turn_state.actions_left(&rules), 3);
+        assert!(!turn_state.record_action(PlayerActionType::Movement, &rules));
+        assert!(!turn_state.record_action(PlayerActionType::Recruitment, &rules));
+        assert_eq!(turn_state.actions_taken(), 2);
+        assert!(turn_state.record_action(PlayerActionType::Movement, &rules));
+        assert_eq!(turn_state.actions_left(&rules), 0);
+
+        turn_state.start_turn(false);
+        assert_eq!(turn_state.actions_taken(), 0);
+        assert_eq!(turn_state.actions_left(&rules), 3);
+    }
+
+    #[test]
+    fn repeat_limits() {
+        let rules = rules(3, 2);
+        let mut turn_state = TurnState::new();
+        turn_state.start_turn(true);
+        turn_state.record_action(PlayerActionType::Movement, &rules);
+        assert!(turn_state.action_allowed(PlayerActionType::Movement, &rules));
+        turn_state.record_action(PlayerActionType::Movement, &rules);
+        assert!(!turn_state.action_allowed(PlayerActionType::Movement, &rules));
+        assert!(turn_state.action_allowed(PlayerActionType::Construction, &rules));
+        turn_state.record_action(PlayerActionType::Construction, &rules);
+        // Out of actions altogether.
+        assert!(!turn_state.action_allowed(PlayerActionType::Recruitment, &rules));
+
+        // The counts start again every turn.
+        turn_state.start_turn(false);
+        assert!(turn_state.action_allowed(PlayerActionType::Movement, &rules));
+    }
+}